@@ -0,0 +1,47 @@
+//! Embed the version and the revision of the Matrix Rust SDK, a path
+//! dependency, for the bug reports.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+fn main() {
+    let manifest_directory = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let lock_path = manifest_directory.join("Cargo.lock");
+    let sdk_path = manifest_directory.join("../matrix-rust-sdk");
+
+    // The version resolved by Cargo, as the SDK may inherit it from its
+    // workspace.
+    let version = fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|lock| {
+            let mut lines = lock.lines();
+            lines.find(|line| *line == "name = \"matrix-sdk\"")?;
+
+            Some(lines.next()?.strip_prefix("version = ")?.trim_matches('"').to_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    let git = |arguments: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(&sdk_path)
+            .args(arguments)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+
+    let revision = match git(&["rev-parse", "HEAD"]) {
+        Some(revision) => match git(&["status", "--porcelain", "--untracked-files=no"]) {
+            Some(changes) if !changes.is_empty() => format!("{revision}, with local changes"),
+            _ => revision,
+        },
+        None => "unknown".to_owned(),
+    };
+
+    println!("cargo::rustc-env=MATRIX_SDK_VERSION={version}");
+    println!("cargo::rustc-env=MATRIX_SDK_REVISION={revision}");
+    println!("cargo::rerun-if-changed={}", lock_path.display());
+    println!("cargo::rerun-if-changed={}", sdk_path.join(".git/HEAD").display());
+    println!("cargo::rerun-if-changed={}", sdk_path.join(".git/index").display());
+}
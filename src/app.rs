@@ -1,7 +1,18 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
+use crossterm::{execute, terminal::SetTitle};
 use futures::{Stream, StreamExt, pin_mut};
-use matrix_sdk::{Client, Room};
+use matrix_sdk::{
+    Client, Room, SessionChange,
+    ruma::{OwnedEventId, OwnedRoomId, OwnedRoomOrAliasId},
+    send_queue::LocalEchoContent,
+};
 use matrix_sdk_ui::sync_service::{self, SyncService};
 use ratatui::{
     DefaultTerminal,
@@ -9,28 +20,97 @@ use ratatui::{
     layout::{Constraint, Layout, Margin, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Paragraph, Widget, Wrap},
+    widgets::{Paragraph, Tabs, Widget, Wrap},
 };
 use tokio::{
     spawn,
-    sync::mpsc::{Receiver, Sender, channel},
+    sync::{
+        broadcast::error::RecvError,
+        mpsc::{Receiver, Sender, channel},
+    },
+    time::{Instant, interval, timeout, timeout_at},
 };
+use tracing::{error, info};
 
 use crate::{
-    Error,
+    Error, avatar, bandwidth, bug_report,
+    bus::{self, Destination},
+    clipboard, config, debug_panel, inbox,
     input::{self, Input},
-    mode, room,
-    task_ext::JoinHandleExt,
+    layout, layout_debug, mode, network, performance, rate_limit, room, screenshot, status,
+    task_ext::{self, AbortOnDrop, JoinHandleExt},
+    time, timeline, toast,
 };
 
 pub enum Message {
     Quit,
-    OpenRoom(Room),
+    ToggleLayoutDebug,
+    ToggleSplitLogger,
+    Screenshot,
+    Logout,
+    ReloadConfig,
+    BugReport {
+        with_linked_chunk: bool,
+    },
+    OpenReauthentication {
+        soft_logout: bool,
+    },
+    Reauthenticated,
+    /// Change the password, or deactivate the account.
+    OpenAccount(mode::account::Action),
+    /// Export the room keys to a file, or import them from a file.
+    OpenRoomKeys(mode::room_keys::Action),
+    /// Take a new snapshot of the memory usage.
+    OpenMemory,
+    /// Force the client offline, or bring it back online.
+    ToggleOffline,
+    UpdateNumberOfQueuedMessages(usize),
+    UpdateSyncServiceState(sync_service::State),
+    /// Restart the sync service after an error, at the end of the backoff.
+    RestartSyncService,
+    UpdateLayout(layout::Config),
+    UpdateTitle(String),
+    OpenRoom(Room, Option<OwnedEventId>),
+    /// A command emitted on the bus, or returned by a mode.
+    Bus(bus::Command),
+    GoBack,
+    GoForward,
+    NextTab,
+    PreviousTab,
+    /// Activate the tab at this index, if any.
+    SelectTab(usize),
+    CloseTab,
+    /// A message for the opened room, i.e. the active tab.
     Room(room::Message),
+    /// A message for the room with this ID, whether it's in the active tab or
+    /// not.
+    RoomWithId(OwnedRoomId, room::Message),
     Mode(Mode),
     Space(mode::space::Message),
     RoomList(mode::room_list::Message),
     Logger(mode::logger::Message),
+    PowerLevels(mode::power_levels::Message),
+    Moderation(mode::moderation::Message),
+    Export(mode::export::Message),
+    Command(mode::command::Message),
+    DebugPanels(mode::debug_panels::Message),
+    Reauthenticate(mode::reauthenticate::Message),
+    Switcher(mode::switcher::Message),
+    History(mode::history::Message),
+    Directory(mode::directory::Message),
+    Crypto(mode::crypto::Message),
+    Devices(mode::devices::Message),
+    InboundSessions(mode::inbound_sessions::Message),
+    AuditLog(mode::audit_log::Message),
+    Federation(mode::federation::Message),
+    CrossSigning(mode::cross_signing::Message),
+    SecretStorage(mode::secret_storage::Message),
+    Inbox(mode::inbox::Message),
+    ThirdPartyIds(mode::third_party_ids::Message),
+    Network(mode::network::Message),
+    JumpToDate(mode::jump_to_date::Message),
+    Account(mode::account::Message),
+    RoomKeys(mode::room_keys::Message),
 }
 
 #[derive(Default)]
@@ -42,6 +122,41 @@ pub enum Mode {
     RoomList(mode::room_list::Model),
     Room(mode::room::Model),
     Logger(mode::logger::Model),
+    PowerLevels(mode::power_levels::Model),
+    Moderation(mode::moderation::Model),
+    Export(mode::export::Model),
+    Command(mode::command::Model),
+    DebugPanels(mode::debug_panels::Model),
+    Reauthenticate(mode::reauthenticate::Model),
+    Switcher(mode::switcher::Model),
+    History(mode::history::Model),
+    Directory(mode::directory::Model),
+    Crypto(mode::crypto::Model),
+    Devices(mode::devices::Model),
+    InboundSessions(mode::inbound_sessions::Model),
+    AuditLog(mode::audit_log::Model),
+    Federation(mode::federation::Model),
+    CrossSigning(mode::cross_signing::Model),
+    SecretStorage(mode::secret_storage::Model),
+    Inbox(mode::inbox::Model),
+    ThirdPartyIds(mode::third_party_ids::Model),
+    Network(mode::network::Model),
+    JumpToDate(mode::jump_to_date::Model),
+    Account(mode::account::Model),
+    RoomKeys(mode::room_keys::Model),
+    Memory(mode::memory::Model),
+    Tasks(mode::tasks::Model),
+}
+
+/// The first delay before restarting the sync service after an error.
+const MINIMUM_SYNC_SERVICE_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The delay, doubled after each failed restart, stops growing at this one.
+const MAXIMUM_SYNC_SERVICE_BACKOFF: Duration = Duration::from_secs(60);
+
+struct SyncServiceRestart {
+    at: Instant,
+    _handle: AbortOnDrop<()>,
 }
 
 pub struct Model {
@@ -50,11 +165,40 @@ pub struct Model {
     pub client: Client,
     pub sync_service: Arc<SyncService>,
     pub mode: Mode,
-    pub room: Option<room::Model>,
+    /// The opened rooms, one per tab.
+    rooms: Vec<room::Model>,
+    /// The index in `rooms` of the active tab.
+    active_room: usize,
+    /// The drafts of the rooms which are no longer opened.
+    drafts: HashMap<OwnedRoomId, String>,
+    /// The rooms opened before the current one, the most recent last.
+    previous_rooms: Vec<Room>,
+    /// The rooms left with [`Message::GoBack`], the most recent last.
+    next_rooms: Vec<Room>,
+    /// Whether the sync and the send queue have been stopped manually.
+    is_forced_offline: bool,
+    /// The number of messages waiting in the send queues of all the rooms.
+    number_of_queued_messages: usize,
+    /// The restart of the sync service scheduled after an error.
+    sync_service_restart: Option<SyncServiceRestart>,
+    /// The number of restarts since the sync service last ran, to compute the
+    /// backoff.
+    number_of_sync_service_restarts: u32,
+    pub layout_debug: bool,
+    pub split_logger: Option<mode::logger::Model>,
+    pub screenshot_is_requested: bool,
+    pub layout: layout::Config,
+    layout_path: PathBuf,
+    session_path: PathBuf,
 }
 
 impl Model {
-    pub async fn new(client: Client, input_sender: Sender<Input>) -> Result<Self, Error> {
+    pub async fn new(
+        client: Client,
+        session_path: PathBuf,
+        input_sender: Sender<Input>,
+    ) -> Result<Self, Error> {
+        let layout_path = layout::Config::path(&session_path);
         let sync_service = SyncService::builder(client.clone()).with_offline_mode().build().await?;
         sync_service.start().await;
 
@@ -64,23 +208,296 @@ impl Model {
             client,
             sync_service: Arc::new(sync_service),
             mode: Mode::default(),
-            room: None,
+            rooms: Vec::new(),
+            active_room: 0,
+            drafts: HashMap::new(),
+            previous_rooms: Vec::new(),
+            next_rooms: Vec::new(),
+            is_forced_offline: false,
+            number_of_queued_messages: 0,
+            sync_service_restart: None,
+            number_of_sync_service_restarts: 0,
+            layout_debug: false,
+            split_logger: None,
+            screenshot_is_requested: false,
+            layout: layout::Config::load(&layout_path),
+            layout_path,
+            session_path,
         })
     }
 
     pub async fn update(&mut self, message: Message) -> Option<Message> {
         match message {
             Message::Quit => self.exit = true,
-            Message::OpenRoom(room) => {
+            Message::Logout => {
+                self.sync_service.stop().await;
+
+                if let Err(error) = self.client.logout().await {
+                    error!("Failed to log out: {error}");
+                }
+
+                if let Err(error) = crate::remove_session(&self.session_path) {
+                    error!("Failed to remove the session: {error}");
+                }
+
+                self.exit = true;
+            }
+            Message::ReloadConfig => {
+                // Only the options that can change while running are applied,
+                // the others need a restart.
+                match config::Config::load() {
+                    Ok(config) => {
+                        config.apply();
+                        info!("Configuration reloaded");
+                    }
+                    Err(error) => error!("Failed to reload the configuration: {error}"),
+                }
+
+                return Some(Message::Mode(Mode::None));
+            }
+            Message::BugReport { with_linked_chunk } => {
+                let linked_chunk = match (self.room(), with_linked_chunk) {
+                    (Some(room_model), true) => Some(
+                        bug_report::dump_linked_chunk(&self.client, room_model.room().room_id())
+                            .await
+                            .unwrap_or_else(|error| format!("failed to dump: {error}")),
+                    ),
+                    _ => None,
+                };
+                let report = bug_report::Report {
+                    panic: None,
+                    sync_inspector: Some(self.sync_inspector()),
+                    linked_chunk,
+                };
+
+                match bug_report::write(&report) {
+                    Ok(path) => {
+                        let path = path.display().to_string();
+                        let _ = clipboard::copy(&path);
+
+                        info!("Bug report written to `{path}`");
+                    }
+                    Err(error) => error!("Failed to write the bug report: {error}"),
+                }
+
+                return Some(Message::Mode(Mode::None));
+            }
+            Message::OpenReauthentication { soft_logout } => {
+                self.mode = Mode::Reauthenticate(mode::reauthenticate::Model::new(
+                    self.client.clone(),
+                    self.session_path.clone(),
+                    soft_logout,
+                ));
+            }
+            Message::OpenAccount(action) => {
+                let mut account_model = mode::account::Model::new(self.client.clone(), action);
+                let message = account_model.start().await;
+                self.mode = Mode::Account(account_model);
+
+                return message;
+            }
+            Message::OpenRoomKeys(action) => {
+                self.mode =
+                    Mode::RoomKeys(mode::room_keys::Model::new(self.client.clone(), action));
+            }
+            Message::OpenMemory => {
+                let usages = self.room().map(|room_model| room_model.timeline().memory_usage());
+
+                self.mode = Mode::Memory(mode::memory::Model::new(
+                    &self.client,
+                    usages.unwrap_or_default(),
+                ));
+            }
+            Message::Reauthenticated => {
+                info!("Logged in again, restarting the sync");
+
+                if !self.is_forced_offline {
+                    self.sync_service.start().await;
+                }
+
                 self.mode = Mode::None;
-                self.sync_service.room_list_service().subscribe_to_rooms(&[room.room_id()]).await;
-                self.room = Some(room::Model::new(room, self.input_sender.clone()).await);
+            }
+            Message::ToggleOffline => {
+                self.is_forced_offline = !self.is_forced_offline;
+
+                // The messages sent while offline are kept in the send queues,
+                // and sent once back online.
+                if self.is_forced_offline {
+                    info!("Going offline");
+
+                    self.sync_service.stop().await;
+                    self.client.send_queue().set_enabled(false).await;
+                } else {
+                    info!("Going back online");
+
+                    self.client.send_queue().set_enabled(true).await;
+                    self.sync_service.start().await;
+                }
+            }
+            Message::UpdateNumberOfQueuedMessages(number_of_queued_messages) => {
+                self.number_of_queued_messages = number_of_queued_messages;
+            }
+            Message::UpdateSyncServiceState(state) => {
+                match state {
+                    sync_service::State::Error(error) => {
+                        let backoff = MINIMUM_SYNC_SERVICE_BACKOFF
+                            .saturating_mul(
+                                2u32.saturating_pow(self.number_of_sync_service_restarts),
+                            )
+                            .min(MAXIMUM_SYNC_SERVICE_BACKOFF);
+
+                        info!("Sync service failed: {error}, restarting it in {backoff:?}");
+
+                        self.sync_service_restart = Some(SyncServiceRestart {
+                            at: Instant::now() + backoff,
+                            _handle: spawn(restart_sync_service_task(
+                                backoff,
+                                self.input_sender.clone(),
+                            ))
+                            .abort_on_drop(),
+                        });
+                    }
+                    sync_service::State::Running => {
+                        self.sync_service_restart = None;
+                        self.number_of_sync_service_restarts = 0;
+                    }
+                    sync_service::State::Idle
+                    | sync_service::State::Terminated
+                    | sync_service::State::Offline => self.sync_service_restart = None,
+                }
+
+                // Kept for the bug reports written on panic.
+                bug_report::record_sync_inspector(self.sync_inspector());
+            }
+            Message::RestartSyncService => {
+                self.sync_service_restart = None;
+
+                if matches!(self.sync_service.state().get(), sync_service::State::Error(_)) {
+                    self.number_of_sync_service_restarts += 1;
+                    self.sync_service.start().await;
+                }
+            }
+            Message::ToggleLayoutDebug => {
+                self.layout_debug = !self.layout_debug;
+
+                return Some(Message::Mode(Mode::None));
+            }
+            Message::ToggleSplitLogger => {
+                self.split_logger = match self.split_logger {
+                    Some(_) => None,
+                    None => Some(mode::logger::Model::new(self.input_sender.clone())),
+                };
+
+                return Some(Message::Mode(Mode::None));
+            }
+            Message::Screenshot => {
+                // The screenshot is taken after the next draw, once the mode
+                // is closed.
+                self.screenshot_is_requested = true;
+
+                return Some(Message::Mode(Mode::None));
+            }
+            Message::UpdateLayout(layout) => {
+                if let Err(error) = layout.save(&self.layout_path) {
+                    error!("Failed to save the layout: {error}");
+                }
+
+                self.layout = layout;
+            }
+            Message::UpdateTitle(title) => {
+                if let Err(error) =
+                    execute!(io::stdout(), SetTitle(format!("{title} — multiverse")))
+                {
+                    error!("Failed to update the terminal title: {error}");
+                }
+            }
+            Message::OpenRoom(room, focused_event_id) => {
+                if let Some(current_room) = self
+                    .current_room()
+                    .filter(|current_room| current_room.room_id() != room.room_id())
+                {
+                    // Keep each room once in the history, at its most recent place.
+                    self.previous_rooms.retain(|previous_room| {
+                        previous_room.room_id() != room.room_id()
+                            && previous_room.room_id() != current_room.room_id()
+                    });
+                    self.previous_rooms.push(current_room);
+                    self.next_rooms.clear();
+                }
+
+                return self.open_room(room, focused_event_id).await;
+            }
+            Message::Bus(command) => {
+                bus::publish(&command);
+
+                return self.dispatch(command).await;
+            }
+            Message::GoBack => {
+                let room = self.previous_rooms.pop()?;
+                self.next_rooms.extend(self.current_room());
+
+                return self.open_room(room, None).await;
+            }
+            Message::GoForward => {
+                let room = self.next_rooms.pop()?;
+                self.previous_rooms.extend(self.current_room());
+
+                return self.open_room(room, None).await;
+            }
+            Message::NextTab => {
+                if self.rooms.len() > 1 {
+                    self.active_room = (self.active_room + 1) % self.rooms.len();
+
+                    return Some(Message::Room(room::Message::UpdateInfo));
+                }
+            }
+            Message::PreviousTab => {
+                if self.rooms.len() > 1 {
+                    self.active_room = (self.active_room + self.rooms.len() - 1) % self.rooms.len();
+
+                    return Some(Message::Room(room::Message::UpdateInfo));
+                }
+            }
+            Message::SelectTab(index) => {
+                if index < self.rooms.len() && index != self.active_room {
+                    self.active_room = index;
+
+                    return Some(Message::Room(room::Message::UpdateInfo));
+                }
+            }
+            Message::CloseTab => {
+                self.mode = Mode::None;
+
+                if self.active_room < self.rooms.len() {
+                    let mut room_model = self.rooms.remove(self.active_room);
+                    room_model.flush_delayed_message().await;
+                    self.keep_draft(&room_model).await;
+
+                    self.active_room = self.active_room.min(self.rooms.len().saturating_sub(1));
+
+                    if !self.rooms.is_empty() {
+                        return Some(Message::Room(room::Message::UpdateInfo));
+                    }
+                }
             }
             Message::Room(room_message) => {
-                if let Some(room_model) = &mut self.room {
+                if let Some(room_model) = self.rooms.get_mut(self.active_room) {
                     return room_model.update(room_message).await;
                 }
             }
+            Message::RoomWithId(room_id, room_message) => {
+                let index = self
+                    .rooms
+                    .iter()
+                    .position(|room_model| *room_model.room().room_id() == *room_id)?;
+                let next_message = self.rooms[index].update(room_message).await;
+
+                // The rooms in the other tabs are updated in the background,
+                // they don't change the app, e.g. its title.
+                if index == self.active_room {
+                    return next_message;
+                }
+            }
             Message::Mode(mode) => self.mode = mode,
             Message::Space(space_message) => {
                 if let Mode::Space(space_model) = &mut self.mode {
@@ -97,21 +514,328 @@ impl Model {
                     return logger_model.update(logger_message);
                 }
             }
+            Message::PowerLevels(power_levels_message) => {
+                if let Mode::PowerLevels(power_levels_model) = &mut self.mode {
+                    return power_levels_model.update(power_levels_message).await;
+                }
+            }
+            Message::Moderation(moderation_message) => {
+                if let Mode::Moderation(moderation_model) = &mut self.mode {
+                    return moderation_model.update(moderation_message);
+                }
+            }
+            Message::Export(export_message) => {
+                if let Mode::Export(export_model) = &mut self.mode {
+                    return export_model.update(export_message).await;
+                }
+            }
+            Message::Reauthenticate(reauthenticate_message) => {
+                if let Mode::Reauthenticate(reauthenticate_model) = &mut self.mode {
+                    return reauthenticate_model.update(reauthenticate_message).await;
+                }
+            }
+            Message::Command(command_message) => {
+                if let Mode::Command(command_model) = &mut self.mode {
+                    return command_model.update(command_message);
+                }
+            }
+            Message::DebugPanels(debug_panels_message) => {
+                if let Mode::DebugPanels(debug_panels_model) = &mut self.mode {
+                    return debug_panels_model.update(debug_panels_message);
+                }
+            }
+            Message::Switcher(switcher_message) => {
+                if let Mode::Switcher(switcher_model) = &mut self.mode {
+                    return switcher_model.update(switcher_message);
+                }
+            }
+            Message::History(history_message) => {
+                if let Mode::History(history_model) = &mut self.mode {
+                    return history_model.update(history_message);
+                }
+            }
+            Message::Directory(directory_message) => {
+                if let Mode::Directory(directory_model) = &mut self.mode {
+                    return directory_model.update(directory_message).await;
+                }
+            }
+            Message::Crypto(crypto_message) => {
+                if let Mode::Crypto(crypto_model) = &mut self.mode {
+                    return crypto_model.update(crypto_message);
+                }
+            }
+            Message::Devices(devices_message) => {
+                if let Mode::Devices(devices_model) = &mut self.mode {
+                    return devices_model.update(devices_message).await;
+                }
+            }
+            Message::InboundSessions(inbound_sessions_message) => {
+                if let Mode::InboundSessions(inbound_sessions_model) = &mut self.mode {
+                    return inbound_sessions_model.update(inbound_sessions_message).await;
+                }
+            }
+            Message::AuditLog(audit_log_message) => {
+                if let Mode::AuditLog(audit_log_model) = &mut self.mode {
+                    return audit_log_model.update(audit_log_message);
+                }
+            }
+            Message::Federation(federation_message) => {
+                if let Mode::Federation(federation_model) = &mut self.mode {
+                    return federation_model.update(federation_message);
+                }
+            }
+            Message::CrossSigning(cross_signing_message) => {
+                if let Mode::CrossSigning(cross_signing_model) = &mut self.mode {
+                    return cross_signing_model.update(cross_signing_message).await;
+                }
+            }
+            Message::SecretStorage(secret_storage_message) => {
+                if let Mode::SecretStorage(secret_storage_model) = &mut self.mode {
+                    return secret_storage_model.update(secret_storage_message).await;
+                }
+            }
+            Message::Inbox(inbox_message) => {
+                if let Mode::Inbox(inbox_model) = &mut self.mode {
+                    return inbox_model.update(inbox_message).await;
+                }
+            }
+            Message::ThirdPartyIds(third_party_ids_message) => {
+                if let Mode::ThirdPartyIds(third_party_ids_model) = &mut self.mode {
+                    return third_party_ids_model.update(third_party_ids_message).await;
+                }
+            }
+            Message::Network(network_message) => {
+                if let Mode::Network(network_model) = &mut self.mode {
+                    return network_model.update(network_message);
+                }
+            }
+            Message::JumpToDate(jump_to_date_message) => {
+                if let Mode::JumpToDate(jump_to_date_model) = &mut self.mode {
+                    return jump_to_date_model.update(jump_to_date_message).await;
+                }
+            }
+            Message::Account(account_message) => {
+                if let Mode::Account(account_model) = &mut self.mode {
+                    return account_model.update(account_message).await;
+                }
+            }
+            Message::RoomKeys(room_keys_message) => {
+                if let Mode::RoomKeys(room_keys_model) = &mut self.mode {
+                    return room_keys_model.update(room_keys_message).await;
+                }
+            }
         }
 
         None
     }
 
+    /// Run a command of the bus, and return the message it maps to, if any.
+    async fn dispatch(&mut self, command: bus::Command) -> Option<Message> {
+        Some(match command {
+            bus::Command::OpenRoom(room, focused_event_id) => {
+                Message::OpenRoom(room, focused_event_id)
+            }
+            bus::Command::Toast(message) => {
+                toast::error(message, &self.input_sender);
+
+                return None;
+            }
+            bus::Command::Navigate(destination) => match destination {
+                Destination::RoomList => Message::Mode(Mode::RoomList(
+                    mode::room_list::Model::new(
+                        self.sync_service.clone(),
+                        self.layout.clone(),
+                        self.input_sender.clone(),
+                    )
+                    .await,
+                )),
+                Destination::Logger => {
+                    Message::Mode(Mode::Logger(mode::logger::Model::new(self.input_sender.clone())))
+                }
+                Destination::Memory => Message::OpenMemory,
+                Destination::Tasks => Message::Mode(Mode::Tasks(mode::tasks::Model::new())),
+            },
+            bus::Command::RunCommand(command) => match mode::command::run(&command) {
+                Ok(message) => message,
+                Err(error) => {
+                    toast::error(error, &self.input_sender);
+
+                    return None;
+                }
+            },
+        })
+    }
+
+    /// The rooms opened before the current one, the most recent first.
+    pub fn recent_rooms(&self) -> impl Iterator<Item = &Room> {
+        self.previous_rooms.iter().rev()
+    }
+
+    /// Keep the draft of a room which is being closed, to restore it when the
+    /// room is opened again.
+    async fn keep_draft(&mut self, room_model: &room::Model) {
+        let draft = room_model.draft();
+        let room_id = room_model.room().room_id().to_owned();
+
+        room_model.save_draft().await;
+
+        if draft.is_empty() {
+            self.drafts.remove(&room_id);
+        } else {
+            self.drafts.insert(room_id, draft);
+        }
+    }
+
+    /// The opened room, i.e. the one in the active tab.
+    pub fn room(&self) -> Option<&room::Model> {
+        self.rooms.get(self.active_room)
+    }
+
+    /// A snapshot of the sync: the states of the services, the restarts after
+    /// the errors, and what may slow it down or stop it.
+    fn sync_inspector(&self) -> String {
+        let next_restart = match &self.sync_service_restart {
+            Some(restart) => {
+                format!("in {:?}", restart.at.saturating_duration_since(Instant::now()))
+            }
+            None => "none".to_owned(),
+        };
+        let network = if network::is_running() {
+            format!("{}ms of latency, {}% of failures", network::latency(), network::failure_rate())
+        } else {
+            "not simulated".to_owned()
+        };
+
+        format!(
+            "sync service: {:?}\n\
+             room list service: {:?}\n\
+             restarts since the last success: {}\n\
+             next restart: {next_restart}\n\
+             forced offline: {}\n\
+             queued messages: {}\n\
+             rate limit: {}\n\
+             network: {network}\n\
+             terminated tasks: {}\n",
+            self.sync_service.state().get(),
+            self.sync_service.room_list_service().state().get(),
+            self.number_of_sync_service_restarts,
+            self.is_forced_offline,
+            self.number_of_queued_messages,
+            rate_limit::status().unwrap_or_else(|| "none".to_owned()),
+            task_ext::number_of_terminated_tasks(),
+        )
+    }
+
+    fn current_room(&self) -> Option<Room> {
+        self.room().map(|room_model| room_model.room().clone())
+    }
+
+    /// Open `room` in a new tab, or activate its tab if it's already opened.
+    async fn open_room(
+        &mut self,
+        room: Room,
+        focused_event_id: Option<OwnedEventId>,
+    ) -> Option<Message> {
+        self.mode = Mode::None;
+
+        let index =
+            self.rooms.iter().position(|room_model| room_model.room().room_id() == room.room_id());
+
+        self.active_room = match (index, focused_event_id) {
+            // The room is already opened, and there is no event to focus on:
+            // keep its timeline, its scroll position and its draft.
+            (Some(index), None) => index,
+            (index, focused_event_id) => {
+                self.sync_service.room_list_service().subscribe_to_rooms(&[room.room_id()]).await;
+
+                // The room is opened again to focus on an event: keep the
+                // draft of its previous timeline.
+                if let Some(index) = index {
+                    self.drafts.insert(room.room_id().to_owned(), self.rooms[index].draft());
+                }
+
+                let draft = self.drafts.remove(room.room_id());
+                let room_model = match room::Model::new(
+                    room.clone(),
+                    focused_event_id,
+                    draft.clone(),
+                    self.input_sender.clone(),
+                )
+                .await
+                {
+                    Ok(room_model) => room_model,
+                    Err(error) => {
+                        // Keep the draft for the next time the room is opened,
+                        // unless its tab still has it.
+                        if let (None, Some(draft)) = (index, draft) {
+                            self.drafts.insert(room.room_id().to_owned(), draft);
+                        }
+
+                        toast::error(
+                            format!("failed to open {}: {error}", room::display_name(&room)),
+                            &self.input_sender,
+                        );
+
+                        return None;
+                    }
+                };
+
+                match index {
+                    Some(index) => {
+                        self.rooms[index].flush_delayed_message().await;
+                        self.rooms[index] = room_model;
+
+                        index
+                    }
+                    None => {
+                        self.rooms.push(room_model);
+
+                        self.rooms.len() - 1
+                    }
+                }
+            }
+        };
+
+        Some(Message::Room(room::Message::UpdateInfo))
+    }
+
     pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
         let [app_area, status_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)]).areas(area);
 
+        layout_debug::register("app", app_area);
+        layout_debug::register("status", status_area);
+
         // App.
         {
             if let Mode::Logger(logger_model) = &self.mode {
                 logger_model.render(app_area, buffer);
-            } else if let Some(room_model) = &self.room {
-                room_model.render(app_area, buffer);
+            } else if let Some(room_model) = self.room() {
+                let [tabs_area, rooms_area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Percentage(100)])
+                        .areas(app_area);
+
+                layout_debug::register("tabs", tabs_area);
+
+                Tabs::new(
+                    self.rooms.iter().map(|room_model| room::display_name(room_model.room())),
+                )
+                .select(self.active_room)
+                .highlight_style(Style::default().bold().black().on_yellow())
+                .render(tabs_area, buffer);
+
+                if let Some(split_logger) = &self.split_logger {
+                    let [room_area, logger_area] = Layout::horizontal([
+                        Constraint::Percentage(50),
+                        Constraint::Percentage(50),
+                    ])
+                    .areas(rooms_area);
+
+                    room_model.render(room_area, buffer);
+                    split_logger.render(logger_area, buffer);
+                } else {
+                    room_model.render(rooms_area, buffer);
+                }
             } else {
                 let app_area = area.inner(Margin { horizontal: 4, vertical: 2 });
                 let italic = Style::default().italic();
@@ -156,6 +880,37 @@ impl Model {
                         Span::styled("Logger", yellow),
                         Span::raw(" mode (highly experimental),"),
                     ]),
+                    Line::from(vec![
+                        Span::raw("* Press "),
+                        Span::styled("<:>", italic),
+                        Span::raw(" to type a "),
+                        Span::styled("Command", yellow),
+                        Span::raw(", e.g. `logout`,"),
+                    ]),
+                    Line::from(vec![
+                        Span::raw("* Press "),
+                        Span::styled("<Ctrl-k>", italic),
+                        Span::raw(" to "),
+                        Span::styled("jump to a room", yellow),
+                        Span::raw(", and "),
+                        Span::styled("<Alt-←>", italic),
+                        Span::raw(" or "),
+                        Span::styled("<Alt-→>", italic),
+                        Span::raw(" to go back and forth between the opened rooms, "),
+                        Span::styled("<h>", italic),
+                        Span::raw(" to list them,"),
+                    ]),
+                    Line::from(vec![
+                        Span::raw("* Press "),
+                        Span::styled("<Ctrl-Tab>", italic),
+                        Span::raw(" to go to the next "),
+                        Span::styled("tab", yellow),
+                        Span::raw(", or "),
+                        Span::styled("<Alt-1>", italic),
+                        Span::raw(" to "),
+                        Span::styled("<Alt-9>", italic),
+                        Span::raw(" to go to a tab, each opened room has its own tab,"),
+                    ]),
                     Line::from(vec![
                         Span::raw("* Press "),
                         Span::styled("<Esc>", italic),
@@ -199,70 +954,443 @@ impl Model {
                     ("room", Color::Gray)
                 }
                 Mode::Logger(_) => ("logger", Color::Gray),
+                Mode::PowerLevels(power_levels_model) => {
+                    power_levels_model.render(app_area, buffer);
+
+                    ("power levels", Color::Gray)
+                }
+                Mode::Moderation(moderation_model) => {
+                    moderation_model.render(app_area, buffer);
+
+                    ("moderation", Color::Gray)
+                }
+                Mode::Export(export_model) => {
+                    export_model.render(app_area, buffer);
+
+                    ("export", Color::Gray)
+                }
+                Mode::Command(command_model) => {
+                    command_model.render(app_area, buffer);
+
+                    ("command", Color::Gray)
+                }
+                Mode::Reauthenticate(reauthenticate_model) => {
+                    reauthenticate_model.render(app_area, buffer);
+
+                    ("reauthenticate", Color::Yellow)
+                }
+                Mode::DebugPanels(debug_panels_model) => {
+                    debug_panels_model.render(app_area, buffer);
+
+                    ("debug panels", Color::Gray)
+                }
+                Mode::Switcher(switcher_model) => {
+                    switcher_model.render(app_area, buffer);
+
+                    ("switcher", Color::Gray)
+                }
+                Mode::History(history_model) => {
+                    history_model.render(app_area, buffer);
+
+                    ("history", Color::Gray)
+                }
+                Mode::Directory(directory_model) => {
+                    directory_model.render(app_area, buffer);
+
+                    ("directory", Color::Gray)
+                }
+                Mode::Crypto(crypto_model) => {
+                    crypto_model.render(app_area, buffer);
+
+                    ("crypto", Color::Gray)
+                }
+                Mode::Devices(devices_model) => {
+                    devices_model.render(app_area, buffer);
+
+                    ("devices", Color::Gray)
+                }
+                Mode::InboundSessions(inbound_sessions_model) => {
+                    inbound_sessions_model.render(app_area, buffer);
+
+                    ("inbound sessions", Color::Gray)
+                }
+                Mode::AuditLog(audit_log_model) => {
+                    audit_log_model.render(app_area, buffer);
+
+                    ("audit log", Color::Gray)
+                }
+                Mode::Federation(federation_model) => {
+                    federation_model.render(app_area, buffer);
+
+                    ("federation", Color::Gray)
+                }
+                Mode::CrossSigning(cross_signing_model) => {
+                    cross_signing_model.render(app_area, buffer);
+
+                    ("cross-signing", Color::Gray)
+                }
+                Mode::SecretStorage(secret_storage_model) => {
+                    secret_storage_model.render(app_area, buffer);
+
+                    ("secret storage", Color::Gray)
+                }
+                Mode::Inbox(inbox_model) => {
+                    inbox_model.render(app_area, buffer);
+
+                    ("inbox", Color::Gray)
+                }
+                Mode::ThirdPartyIds(third_party_ids_model) => {
+                    third_party_ids_model.render(app_area, buffer);
+
+                    ("3PIDs", Color::Gray)
+                }
+                Mode::Network(network_model) => {
+                    network_model.render(app_area, buffer);
+
+                    ("network", Color::Gray)
+                }
+                Mode::JumpToDate(jump_to_date_model) => {
+                    jump_to_date_model.render(app_area, buffer);
+
+                    ("jump to date", Color::Gray)
+                }
+                Mode::Account(account_model) => {
+                    account_model.render(app_area, buffer);
+
+                    ("account", Color::Gray)
+                }
+                Mode::RoomKeys(room_keys_model) => {
+                    room_keys_model.render(app_area, buffer);
+
+                    ("room keys", Color::Gray)
+                }
+                Mode::Memory(memory_model) => {
+                    memory_model.render(app_area, buffer);
+
+                    ("memory", Color::Gray)
+                }
+                Mode::Tasks(tasks_model) => {
+                    tasks_model.render(app_area, buffer);
+
+                    ("tasks", Color::Gray)
+                }
             };
 
-            let (sync_service_label, sync_service_color) = match self.sync_service.state().get() {
+            let sync_service_state = self.sync_service.state().get();
+            let (sync_service_label, sync_service_color) = match &sync_service_state {
+                _ if self.is_forced_offline => ("forced offline", Color::Blue),
                 sync_service::State::Idle => ("idle", Color::Gray),
                 sync_service::State::Running => ("running", Color::Green),
                 sync_service::State::Terminated => ("terminated", Color::Yellow),
                 sync_service::State::Error(_) => ("ERROR", Color::Red),
                 sync_service::State::Offline => ("offline", Color::Blue),
             };
+            let error = match (&sync_service_state, &self.sync_service_restart) {
+                (sync_service::State::Error(error), Some(restart)) => {
+                    // Round up, to not show 0s before the restart.
+                    let remaining = restart.at.saturating_duration_since(Instant::now());
+
+                    format!(
+                        ": {error} · restart in {}s, or now with <Space S>",
+                        remaining.as_millis().div_ceil(1000)
+                    )
+                }
+                (sync_service::State::Error(error), None) => format!(": {error}"),
+                _ => String::new(),
+            };
+            let queued_messages = if self.is_forced_offline
+                || matches!(sync_service_state, sync_service::State::Offline)
+            {
+                format!(" · {} queued messages", self.number_of_queued_messages)
+            } else {
+                String::new()
+            };
+
+            let mut mode_line = Line::from(Span::styled(
+                format!("mode `{mode_label}`"),
+                Style::new().fg(mode_color),
+            ));
+
+            for field in status::fields() {
+                let value = match field {
+                    status::Field::Room => {
+                        self.room().map(|room_model| room::display_name(room_model.room()))
+                    }
+                    status::Field::Unread => {
+                        let rooms = self.client.joined_rooms();
+                        let number_of_unread_rooms =
+                            rooms.iter().filter(|room| room.num_unread_messages() > 0).count();
+                        let number_of_unread_mentions =
+                            rooms.iter().map(|room| room.num_unread_mentions()).sum::<u64>();
+
+                        Some(format!(
+                            "{number_of_unread_rooms} unread rooms, {number_of_unread_mentions} mentions"
+                        ))
+                    }
+                    status::Field::UserId => self.client.user_id().map(ToString::to_string),
+                    status::Field::Clock => Some(time::format_clock(SystemTime::now())),
+                };
+
+                if let Some(value) = value {
+                    mode_line.push_span(Span::raw(format!(" · {value}")).dark_gray());
+                }
+            }
+
+            if let Some(rate_limit_status) = rate_limit::status() {
+                mode_line.push_span(Span::raw(format!(" · {rate_limit_status}")).yellow());
+            }
+
+            // Until the requests are handled, not to miss them.
+            if let Some(inbox_badge) = inbox::badge() {
+                mode_line
+                    .push_span(Span::raw(format!(" · {inbox_badge}, see <Space R>")).magenta());
+            }
+
+            let number_of_terminated_tasks = task_ext::number_of_terminated_tasks();
+
+            if number_of_terminated_tasks > 0 {
+                mode_line.push_span(
+                    Span::raw(format!(
+                        " · {number_of_terminated_tasks} tasks terminated, see <Space T>"
+                    ))
+                    .red(),
+                );
+            }
 
-            Line::from(format!("mode `{}`", mode_label))
-                .style(Style::new().fg(mode_color))
-                .render(mode_area, buffer);
+            mode_line.render(mode_area, buffer);
 
-            Line::from(format!("sync service `{}`", sync_service_label))
-                .style(Style::new().fg(sync_service_color))
-                .right_aligned()
-                .render(sync_service_area, buffer);
+            let low_bandwidth = if bandwidth::is_low() { " · low bandwidth" } else { "" };
+
+            Line::from(format!(
+                "sync service `{sync_service_label}`{error}{queued_messages}{low_bandwidth}"
+            ))
+            .style(Style::new().fg(sync_service_color))
+            .right_aligned()
+            .render(sync_service_area, buffer);
+        }
+
+        // Layout debug overlay.
+        if self.layout_debug {
+            layout_debug::render(app_area, buffer);
+        } else {
+            layout_debug::clear();
         }
+
+        // The overlays of the debug panels, e.g. the performance HUD.
+        debug_panel::render(app_area, buffer);
+
+        toast::render(app_area, buffer);
     }
 }
 
+/// The minimum duration between two frames.
+const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+/// How long to wait for the room given on the command line to be known.
+const OPEN_ROOM_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct App {
     model: Model,
     input_receiver: Receiver<Input>,
+    deep_link: Option<(OwnedRoomOrAliasId, Option<OwnedEventId>)>,
 }
 
 impl App {
-    pub async fn new(client: Client) -> Result<Self, Error> {
+    pub async fn new(
+        client: Client,
+        session_path: PathBuf,
+        deep_link: Option<(OwnedRoomOrAliasId, Option<OwnedEventId>)>,
+    ) -> Result<Self, Error> {
         let (input_sender, input_receiver) = channel(128);
+        // Before the terminal events are read, to read the answers of the
+        // terminal.
+        avatar::init(session_path.join(crate::AVATAR_CACHE_DIRECTORY), input_sender.clone());
+        bug_report::init(session_path.join(crate::BUG_REPORTS_DIRECTORY));
+        bus::init(input_sender.clone());
+        timeline::register_debug_panels();
+        performance::register_debug_panel();
 
-        Ok(Self { model: Model::new(client, input_sender).await?, input_receiver })
+        Ok(Self {
+            model: Model::new(client, session_path, input_sender).await?,
+            input_receiver,
+            deep_link,
+        })
     }
 
     pub async fn run(mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        let input_sender = self.model.input_sender.clone();
+
         let _terminal_events_task =
-            spawn(input::handle_terminal_events_task(self.model.input_sender.clone()))
-                .abort_on_drop();
+            task_ext::supervise_with_restart("terminal events", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+
+                move || input::handle_terminal_events_task(input_sender.clone())
+            });
+
+        let _sync_service_task =
+            task_ext::supervise_with_restart("sync service states", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+                let sync_service = self.model.sync_service.clone();
+
+                move || handle_sync_service_states_task(input_sender.clone(), sync_service.state())
+            });
+
+        let _send_queue_task =
+            task_ext::supervise_with_restart("send queue updates", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+                let client = self.model.client.clone();
+
+                move || handle_send_queue_updates_task(client.clone(), input_sender.clone())
+            });
+
+        let _send_queue_errors_task =
+            task_ext::supervise_with_restart("send queue errors", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+                let client = self.model.client.clone();
+
+                move || rate_limit::send_queue_errors_task(client.clone(), input_sender.clone())
+            });
+
+        let _inbox_task = task_ext::supervise_with_restart("inbox", input_sender.clone(), {
+            let input_sender = input_sender.clone();
+            let client = self.model.client.clone();
+
+            move || inbox::requests_task(client.clone(), input_sender.clone())
+        });
+
+        let _session_changes_task =
+            task_ext::supervise_with_restart("session changes", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+                let client = self.model.client.clone();
+                let session_path = self.model.session_path.clone();
+
+                move || {
+                    handle_session_changes_task(
+                        client.clone(),
+                        session_path.clone(),
+                        input_sender.clone(),
+                    )
+                }
+            });
+
+        let _relative_timestamps_task =
+            task_ext::supervise_with_restart("relative timestamps", input_sender.clone(), {
+                let input_sender = input_sender.clone();
+
+                move || time::refresh_relative_timestamps_task(input_sender.clone())
+            });
 
-        let _sync_service_task = spawn(handle_sync_service_states_task(
-            self.model.input_sender.clone(),
-            self.model.sync_service.state(),
-        ))
-        .abort_on_drop();
+        let _deep_link_task = self.deep_link.take().map(|(room_id_or_alias, focused_event_id)| {
+            spawn(open_room_task(self.model.client.clone(), room_id_or_alias, focused_event_id))
+                .abort_on_drop()
+        });
+
+        let _bus_log_task =
+            task_ext::supervise_with_restart("bus log", input_sender.clone(), bus::log_task);
+
+        let mut is_dirty = true;
+        let mut last_draw = Instant::now();
+        // When the first input of the pending redraw has been received.
+        let mut dirty_since = None;
 
         // Run the app.
         while !self.model.exit {
-            // Render the app.
-            terminal.draw(|frame| self.model.render(frame.area(), frame.buffer_mut()))?;
+            // Render the app, only if something has changed.
+            if is_dirty {
+                let render_start = Instant::now();
+                let frame =
+                    terminal.draw(|frame| self.model.render(frame.area(), frame.buffer_mut()))?;
+
+                performance::record_render_time(render_start.elapsed());
 
-            // Handle inputs and get a `Message` in return.
-            let mut next_message =
-                input::map_input_to_message(&mut self.input_receiver, &self.model).await;
+                if let Some(dirty_since) = dirty_since.take() {
+                    performance::record_input_latency(dirty_since.elapsed());
+                }
+
+                if self.model.screenshot_is_requested {
+                    self.model.screenshot_is_requested = false;
+
+                    match screenshot::save(frame.buffer) {
+                        Ok(path) => {
+                            let path = path.display().to_string();
+                            let _ = clipboard::copy(&path);
+
+                            info!("Screenshot saved to `{path}`");
+                        }
+                        Err(error) => error!("Failed to save the screenshot: {error}"),
+                    }
+                }
 
-            // Process the `Message` and the subsequent `Message`s if any are chained.
-            while let Some(message) = next_message {
-                next_message = self.model.update(message).await;
+                is_dirty = false;
+                last_draw = Instant::now();
+
+                // The render has told whether the top of the timeline is
+                // visible: load the older messages, and show the spinner.
+                if self.model.room().is_some_and(|room_model| {
+                    room_model.timeline().paginate_backwards_automatically()
+                }) {
+                    is_dirty = true;
+                }
+            }
+
+            // Wait for the next input.
+            let Some(input) = self.input_receiver.recv().await else {
+                break;
+            };
+
+            let received_at = Instant::now();
+            performance::record_input_backlog(self.input_receiver.len());
+
+            if self.handle_input(input).await {
+                is_dirty = true;
+                dirty_since = Some(received_at);
             }
+
+            // Coalesce all the inputs received before the end of the frame
+            // budget, so that a burst of updates leads to a single redraw.
+            let deadline = last_draw + FRAME_BUDGET;
+
+            while !self.model.exit {
+                match timeout_at(deadline, self.input_receiver.recv()).await {
+                    Ok(Some(input)) => {
+                        let received_at = Instant::now();
+                        performance::record_input_backlog(self.input_receiver.len());
+
+                        if self.handle_input(input).await {
+                            is_dirty = true;
+                            dirty_since.get_or_insert(received_at);
+                        }
+                    }
+                    Ok(None) | Err(_) => break,
+                }
+            }
+        }
+
+        for room_model in &mut self.model.rooms {
+            room_model.flush_delayed_message().await;
+            room_model.save_draft().await;
         }
 
         self.model.sync_service.stop().await;
 
         Ok(())
     }
+
+    /// Handle an input, and return whether the app must be redrawn.
+    async fn handle_input(&mut self, input: Input) -> bool {
+        let is_redraw = matches!(input, Input::Redraw);
+
+        // Map the input to a `Message`.
+        let mut next_message = input::map_input_to_message(input, &self.model);
+        let is_dirty = is_redraw || next_message.is_some();
+
+        // Process the `Message` and the subsequent `Message`s if any are chained.
+        while let Some(message) = next_message {
+            next_message = self.model.update(message).await;
+        }
+
+        is_dirty
+    }
 }
 
 async fn handle_sync_service_states_task(
@@ -271,7 +1399,110 @@ async fn handle_sync_service_states_task(
 ) {
     pin_mut!(state_receiver);
 
-    while let Some(_state) = state_receiver.next().await {
+    while let Some(state) = state_receiver.next().await {
+        let _ = input_sender.send(Input::SyncServiceStateUpdate(state)).await;
+    }
+}
+
+/// Wait for `backoff` before restarting the sync service, and redraw each
+/// second for the countdown.
+async fn restart_sync_service_task(backoff: Duration, input_sender: Sender<Input>) {
+    let at = Instant::now() + backoff;
+    let mut countdown = interval(Duration::from_secs(1));
+
+    while Instant::now() < at {
+        let _ = timeout_at(at, countdown.tick()).await;
         let _ = input_sender.send(Input::Redraw).await;
     }
+
+    let _ = input_sender.send(Input::SyncServiceRestart).await;
+}
+
+async fn handle_send_queue_updates_task(client: Client, input_sender: Sender<Input>) {
+    let send_queue = client.send_queue();
+    let mut updates = send_queue.subscribe();
+
+    loop {
+        // Count the messages from the local echoes, instead of tracking the
+        // updates, to also count the messages queued by a previous session.
+        let number_of_queued_messages = match send_queue.local_echoes().await {
+            Ok(local_echoes) => local_echoes
+                .values()
+                .flatten()
+                .filter(|local_echo| matches!(local_echo.content, LocalEchoContent::Event { .. }))
+                .count(),
+            Err(error) => {
+                error!("Failed to load the local echoes: {error}");
+                0
+            }
+        };
+
+        let _ =
+            input_sender.send(Input::NumberOfQueuedMessagesUpdate(number_of_queued_messages)).await;
+
+        match updates.recv().await {
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn handle_session_changes_task(
+    client: Client,
+    session_path: PathBuf,
+    input_sender: Sender<Input>,
+) {
+    let mut session_changes = client.subscribe_to_session_changes();
+
+    loop {
+        match session_changes.recv().await {
+            Ok(SessionChange::TokensRefreshed) => {
+                // Persist the new tokens, otherwise the next start restores a
+                // session with outdated tokens.
+                if let Err(error) = crate::save_session(&client, &session_path) {
+                    error!("Failed to save the refreshed session: {error}");
+                }
+            }
+            Ok(SessionChange::UnknownToken { soft_logout }) => {
+                let _ = input_sender.send(Input::UnknownToken { soft_logout }).await;
+            }
+            Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn open_room_task(
+    client: Client,
+    room_id_or_alias: OwnedRoomOrAliasId,
+    focused_event_id: Option<OwnedEventId>,
+) {
+    let room_id = match OwnedRoomId::try_from(room_id_or_alias) {
+        Ok(room_id) => room_id,
+        Err(room_alias) => match client.resolve_room_alias(&room_alias).await {
+            Ok(response) => response.room_id,
+            Err(error) => {
+                error!("Failed to resolve the room alias `{room_alias}`: {error}");
+                bus::emit(bus::Command::Toast(format!(
+                    "failed to resolve the room alias `{room_alias}`: {error}"
+                )));
+
+                return;
+            }
+        },
+    };
+
+    // The room may not be known yet, e.g. on the first sync, or never if it
+    // hasn't been joined.
+    let Ok(room) = timeout(OPEN_ROOM_TIMEOUT, client.await_room_remote_echo(&room_id)).await else {
+        error!("{room_id} is still unknown after {OPEN_ROOM_TIMEOUT:?}");
+        bus::emit(bus::Command::Toast(format!(
+            "{room_id} is unknown, has it been joined? Gave up after {}s",
+            OPEN_ROOM_TIMEOUT.as_secs()
+        )));
+
+        return;
+    };
+
+    bus::emit(bus::Command::OpenRoom(room, focused_event_id));
 }
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+};
+
+use matrix_sdk::{
+    Room,
+    media::{MediaFormat, MediaRequestParameters, MediaThumbnailSettings},
+    ruma::{OwnedMxcUri, UInt, events::room::MediaSource},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::Widget,
+};
+use ratatui_image::{
+    Image, Resize,
+    picker::{Picker, ProtocolType},
+    protocol::Protocol,
+};
+use tokio::{spawn, sync::mpsc::Sender};
+use tracing::{debug, warn};
+
+use crate::{input::Input, room::display_name, timeline::color_of};
+
+/// The size of an avatar, in cells: roughly a square.
+pub const WIDTH: u16 = 4;
+pub const HEIGHT: u16 = 2;
+
+/// The size of the downloaded thumbnails, in pixels.
+const THUMBNAIL_SIZE: u32 = 64;
+
+static CONTEXT: Mutex<Option<Context>> = Mutex::new(None);
+static AVATARS: LazyLock<Mutex<HashMap<OwnedMxcUri, Avatar>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct Context {
+    picker: Picker,
+    /// The directory of the thumbnails, named after their MXC URI.
+    directory: PathBuf,
+    input_sender: Sender<Input>,
+}
+
+enum Avatar {
+    Loading,
+    Loaded(Protocol),
+    Failed,
+}
+
+/// Query the terminal for its graphics support. Without it, e.g. only the
+/// half blocks are supported, the avatars are the initials of the rooms.
+///
+/// Must be called once the terminal is in raw mode, and before the terminal
+/// events are read.
+pub fn init(directory: PathBuf, input_sender: Sender<Input>) {
+    let picker = match Picker::from_query_stdio() {
+        Ok(picker) => picker,
+        Err(error) => {
+            debug!("No terminal graphics: {error}");
+
+            return;
+        }
+    };
+
+    if picker.protocol_type() == ProtocolType::Halfblocks {
+        debug!("No terminal graphics, only half blocks");
+
+        return;
+    }
+
+    *CONTEXT.lock().unwrap() = Some(Context { picker, directory, input_sender });
+}
+
+/// Render the avatar of `room` in `area`, of [`WIDTH`] by [`HEIGHT`] cells.
+/// The thumbnail is loaded on the first render; meanwhile, or if it can't be
+/// shown, the initial of the room is rendered.
+pub fn render(room: &Room, area: Rect, buffer: &mut Buffer) {
+    let area = area.intersection(Rect { width: WIDTH, height: HEIGHT, ..area });
+
+    if let Some(avatar_url) = room.avatar_url() {
+        let context = CONTEXT.lock().unwrap();
+
+        if let Some(context) = context.as_ref() {
+            let mut avatars = AVATARS.lock().unwrap();
+
+            match avatars.get(&avatar_url) {
+                Some(Avatar::Loaded(protocol)) => {
+                    Image::new(protocol).render(area, buffer);
+
+                    return;
+                }
+                Some(Avatar::Loading | Avatar::Failed) => {}
+                None => {
+                    avatars.insert(avatar_url.clone(), Avatar::Loading);
+                    spawn(load_task(
+                        room.clone(),
+                        avatar_url,
+                        context.directory.clone(),
+                        context.input_sender.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    render_initial(room, area, buffer);
+}
+
+/// The first letter of the name of the room, on a colour derived from its ID.
+fn render_initial(room: &Room, area: Rect, buffer: &mut Buffer) {
+    let initial = display_name(room)
+        .chars()
+        .find(|character| character.is_alphanumeric())
+        .map(|character| character.to_uppercase().to_string())
+        .unwrap_or_else(|| "#".to_owned());
+
+    buffer.set_style(area, Style::new().bg(color_of(room.room_id().as_str())));
+
+    Line::from(initial)
+        .bold()
+        .black()
+        .centered()
+        .render(Rect { height: 1, y: area.y + area.height.saturating_sub(1) / 2, ..area }, buffer);
+}
+
+/// Load the thumbnail of `avatar_url`, from the cache directory or else from
+/// the server, and redraw.
+async fn load_task(
+    room: Room,
+    avatar_url: OwnedMxcUri,
+    directory: PathBuf,
+    input_sender: Sender<Input>,
+) {
+    let avatar = match load(&room, &avatar_url, directory).await {
+        Ok(protocol) => Avatar::Loaded(protocol),
+        Err(error) => {
+            warn!("Failed to load the avatar `{avatar_url}`: {error}");
+
+            Avatar::Failed
+        }
+    };
+
+    AVATARS.lock().unwrap().insert(avatar_url, avatar);
+
+    let _ = input_sender.send(Input::Redraw).await;
+}
+
+async fn load(
+    room: &Room,
+    avatar_url: &OwnedMxcUri,
+    directory: PathBuf,
+) -> Result<Protocol, String> {
+    let path = directory.join(file_name(avatar_url));
+
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let request = MediaRequestParameters {
+                source: MediaSource::Plain(avatar_url.clone()),
+                format: MediaFormat::Thumbnail(MediaThumbnailSettings::new(
+                    UInt::from(THUMBNAIL_SIZE),
+                    UInt::from(THUMBNAIL_SIZE),
+                )),
+            };
+            let bytes = room
+                .client()
+                .media()
+                .get_media_content(&request, true)
+                .await
+                .map_err(|error| error.to_string())?;
+
+            // The cache is only an optimisation, the avatar is shown anyway.
+            if let Err(error) =
+                fs::create_dir_all(&directory).and_then(|_| fs::write(&path, &bytes))
+            {
+                warn!("Failed to cache the avatar `{avatar_url}`: {error}");
+            }
+
+            bytes
+        }
+    };
+
+    let image = image::load_from_memory(&bytes).map_err(|error| error.to_string())?;
+    let context = CONTEXT.lock().unwrap();
+    let Some(context) = context.as_ref() else {
+        return Err("no terminal graphics".to_owned());
+    };
+
+    context
+        .picker
+        .new_protocol(image, Rect::new(0, 0, WIDTH, HEIGHT), Resize::Fit(None))
+        .map_err(|error| error.to_string())
+}
+
+/// The name of the cached thumbnail of `avatar_url`, e.g.
+/// `example_org_abcdef` for `mxc://example.org/abcdef`.
+fn file_name(avatar_url: &OwnedMxcUri) -> String {
+    avatar_url
+        .as_str()
+        .trim_start_matches("mxc://")
+        .chars()
+        .map(|character| if character.is_ascii_alphanumeric() { character } else { '_' })
+        .collect()
+}
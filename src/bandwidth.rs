@@ -0,0 +1,17 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The timeout of the requests in low-bandwidth mode, in seconds, unless
+/// another one is given.
+pub const LOW_BANDWIDTH_REQUEST_TIMEOUT: u64 = 120;
+
+static IS_LOW: AtomicBool = AtomicBool::new(false);
+
+/// Whether the low-bandwidth mode is enabled: the URL previews aren't fetched,
+/// and the read receipts aren't sent.
+pub fn is_low() -> bool {
+    IS_LOW.load(Ordering::Relaxed)
+}
+
+pub fn set_low(is_low: bool) {
+    IS_LOW.store(is_low, Ordering::Relaxed);
+}
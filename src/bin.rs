@@ -1,15 +1,151 @@
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf, str::FromStr};
 
 use argh::FromArgs;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId, OwnedRoomOrAliasId};
+
+use crate::time;
 
 /// Small Matrix client tailored for debugging the Matrix Rust SDK.
+///
+/// The options can also be set in the configuration file, the command line
+/// wins.
 #[derive(Debug, FromArgs)]
 pub struct Options {
-    /// the homeserver the client should connect to.
-    #[argh(option, short = 's', default = "\"matrix.org\".to_owned()")]
-    pub server_name: String,
+    /// the configuration file, `~/.config/multiverse/config.toml` by default.
+    #[argh(option)]
+    pub config: Option<PathBuf>,
+
+    /// the homeserver the client should connect to, `matrix.org` by default.
+    #[argh(option, short = 's')]
+    pub server_name: Option<String>,
+
+    /// the path where session specific data should be stored, the directory
+    /// of the profile by default.
+    #[argh(option)]
+    pub session_path: Option<PathBuf>,
+
+    /// the profile, with its own session in
+    /// `~/.local/share/multiverse/<profile>`; the existing ones are listed to
+    /// pick one if there are several, else it's named after the homeserver.
+    #[argh(option)]
+    pub profile: Option<String>,
+
+    /// the directory where logs should also be written, rotated daily.
+    #[argh(option)]
+    pub log_dir: Option<PathBuf>,
+
+    /// the `tracing` directives to filter logs with, e.g.
+    /// `matrix_sdk=trace,matrix_sdk_crypto=debug,hyper=off`, `trace` by
+    /// default.
+    #[argh(option)]
+    pub log: Option<String>,
+
+    /// the room to open right after startup, by ID or by alias, e.g.
+    /// `#room:example.org`.
+    #[argh(option)]
+    pub room: Option<OwnedRoomOrAliasId>,
+
+    /// the event to focus on, in the room given by `--room`.
+    #[argh(option)]
+    pub event: Option<OwnedEventId>,
+
+    /// how timestamps are rendered: `24h` (by default), `12h`, `seconds` or
+    /// `relative`.
+    #[argh(option)]
+    pub time_format: Option<time::Format>,
+
+    /// fetch URL previews in encrypted rooms too; the homeserver learns the
+    /// previewed URLs.
+    #[argh(switch)]
+    pub url_previews_in_encrypted_rooms: bool,
+
+    /// a regular expression matching the bots and the bridged users, e.g.
+    /// `^@telegram_`; can be repeated. By default, `^@[^:]*bot:` and `^@_`.
+    #[argh(option)]
+    pub bot_pattern: Vec<String>,
+
+    /// save the unsent messages in the state store, so that they are restored
+    /// after a restart.
+    #[argh(switch)]
+    pub persist_drafts: bool,
+
+    /// print every timeline diff of this room as JSON lines on stdout, without
+    /// the user interface.
+    #[argh(option)]
+    pub tail_room: Option<OwnedRoomId>,
+
+    /// replay the diffs of this file, exported from the diff log or printed by
+    /// `--tail-room`, on an empty list of items, then print the items and the
+    /// unexpected diffs, without the timeline nor the user interface; it fails
+    /// if there is any unexpected diff.
+    #[argh(option)]
+    pub replay_diff_log: Option<PathBuf>,
+
+    /// where the stores are: `sqlite` (by default), in the session path, or
+    /// `memory`, lost on exit; with `memory`, the session isn't restored nor
+    /// saved either.
+    #[argh(option)]
+    pub store: Option<Store>,
+
+    /// remove the session and the stores before starting, to start from a
+    /// clean slate.
+    #[argh(switch)]
+    pub fresh: bool,
+
+    /// the proxy to send the requests through, e.g. `http://localhost:8080` or
+    /// `socks5://localhost:1080`.
+    #[argh(option)]
+    pub proxy: Option<String>,
+
+    /// the user agent of the requests.
+    #[argh(option)]
+    pub user_agent: Option<String>,
+
+    /// the timeout of the requests, in seconds.
+    #[argh(option)]
+    pub request_timeout: Option<u64>,
+
+    /// send the requests through a local proxy, to add latency or failures to
+    /// them from the space panel; can't be used with `--proxy`.
+    #[argh(switch)]
+    pub simulate_network: bool,
+
+    /// do not fetch the URL previews, nor send the read receipts, and wait
+    /// longer for the requests, for slow or metered connections; it can be
+    /// toggled from the space panel.
+    #[argh(switch)]
+    pub low_bandwidth: bool,
+
+    /// do not verify the TLS certificates, e.g. for a local homeserver with a
+    /// self-signed certificate.
+    #[argh(switch)]
+    pub insecure: bool,
+}
+
+/// The backend of the stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Store {
+    Sqlite,
+    Memory,
+}
+
+impl FromStr for Store {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "sqlite" => Self::Sqlite,
+            "memory" => Self::Memory,
+            _ => return Err(format!("unknown store `{value}`, expected `sqlite` or `memory`")),
+        })
+    }
+}
 
-    /// the path where session specific data should be stored.
-    #[argh(option, default = "PathBuf::from(\"/tmp/\")")]
-    pub session_path: PathBuf,
+impl fmt::Display for Store {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Sqlite => "sqlite",
+            Self::Memory => "memory",
+        })
+    }
 }
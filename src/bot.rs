@@ -0,0 +1,21 @@
+use std::sync::Mutex;
+
+use matrix_sdk::ruma::UserId;
+use regex::Regex;
+
+/// The patterns used when none are configured: the users whose localpart ends
+/// with `bot`, and the puppets of the bridges, whose localpart conventionally
+/// starts with `_`.
+pub const DEFAULT_PATTERNS: [&str; 2] = ["^@[^:]*bot:", "^@_"];
+
+static PATTERNS: Mutex<Vec<Regex>> = Mutex::new(Vec::new());
+
+/// Set the patterns used by [`is_bot`], matched against the full user ID.
+pub fn set_patterns(patterns: Vec<Regex>) {
+    *PATTERNS.lock().unwrap() = patterns;
+}
+
+/// Whether `user_id` is a bot, or a user bridged from another network.
+pub fn is_bot(user_id: &UserId) -> bool {
+    PATTERNS.lock().unwrap().iter().any(|pattern| pattern.is_match(user_id.as_str()))
+}
@@ -0,0 +1,147 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::Local;
+use matrix_sdk::{
+    Client,
+    linked_chunk::{ChunkContent, LinkedChunkId},
+    ruma::RoomId,
+};
+
+use crate::logs;
+
+/// The directory of the bug reports, in the profile of the session.
+static DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The last snapshot of the sync inspector, for the reports written on panic.
+static SYNC_INSPECTOR: Mutex<Option<String>> = Mutex::new(None);
+
+/// What goes into a bug report, besides the logs and the versions.
+#[derive(Default)]
+pub struct Report {
+    /// The panic message and its location, if the report is generated on panic.
+    pub panic: Option<String>,
+
+    /// The snapshot of the sync inspector: the states of the sync services,
+    /// the restarts, and the simulated network conditions.
+    pub sync_inspector: Option<String>,
+
+    /// The linked chunk of the opened room, only with the consent of the user
+    /// since it reveals the event IDs, the senders and the event types.
+    pub linked_chunk: Option<String>,
+}
+
+/// Write the next reports in `directory`, instead of the current directory.
+pub fn init(directory: PathBuf) {
+    *DIRECTORY.lock().unwrap() = Some(directory);
+}
+
+/// Keep `snapshot` of the sync inspector, for the reports which can't take
+/// one, i.e. on panic.
+pub fn record_sync_inspector(snapshot: String) {
+    *SYNC_INSPECTOR.lock().unwrap() = Some(snapshot);
+}
+
+/// Write `report` in a timestamped directory, ready to be attached to an
+/// issue. Return the path of the directory.
+pub fn write(report: &Report) -> io::Result<PathBuf> {
+    // Not to panic again if the report is written on panic.
+    let directory = DIRECTORY.lock().ok().and_then(|directory| directory.clone());
+    let path = directory
+        .unwrap_or_default()
+        .join(format!("multiverse-bug-report-{}", Local::now().format("%Y%m%d-%H%M%S")));
+    let sync_inspector = report
+        .sync_inspector
+        .clone()
+        .or_else(|| SYNC_INSPECTOR.lock().ok().and_then(|sync_inspector| sync_inspector.clone()));
+
+    fs::create_dir_all(&path)?;
+
+    fs::write(
+        path.join("logs.txt"),
+        logs::with_logs(|logs| logs.iter().map(|log| format!("{log}\n")).collect::<String>()),
+    )?;
+    fs::write(path.join("versions.txt"), versions())?;
+
+    write_optional(&path, "panic.txt", &report.panic)?;
+    write_optional(&path, "sync-inspector.txt", &sync_inspector)?;
+    write_optional(&path, "linked-chunk.txt", &report.linked_chunk)?;
+
+    Ok(path.canonicalize().unwrap_or(path))
+}
+
+fn write_optional(directory: &Path, name: &str, content: &Option<String>) -> io::Result<()> {
+    match content {
+        Some(content) => fs::write(directory.join(name), content),
+        None => Ok(()),
+    }
+}
+
+fn versions() -> String {
+    format!(
+        "{name} {version}\n\
+         target: {os} {arch}\n\
+         matrix-sdk: {sdk_version}, revision {sdk_revision}\n",
+        name = env!("CARGO_PKG_NAME"),
+        version = env!("CARGO_PKG_VERSION"),
+        sdk_version = env!("MATRIX_SDK_VERSION"),
+        sdk_revision = env!("MATRIX_SDK_REVISION"),
+        os = env::consts::OS,
+        arch = env::consts::ARCH,
+    )
+}
+
+/// Dump the chunks of the linked chunk of `room_id` stored in the event cache,
+/// from the most recent one.
+pub async fn dump_linked_chunk(client: &Client, room_id: &RoomId) -> Result<String, String> {
+    let event_cache_store = client.event_cache_store();
+    let event_cache_store = event_cache_store.lock().await.map_err(|error| error.to_string())?;
+    let event_cache_store =
+        event_cache_store.as_clean().ok_or_else(|| "the event cache is dirty".to_owned())?;
+
+    let mut dump = format!("linked chunk of {room_id}\n");
+    let (mut next_chunk, _) = event_cache_store
+        .load_last_chunk(LinkedChunkId::Room(room_id))
+        .await
+        .map_err(|error| error.to_string())?;
+
+    while let Some(chunk) = next_chunk {
+        match &chunk.content {
+            ChunkContent::Gap(gap) => {
+                dump.push_str(&format!(
+                    "chunk #{}: gap, previous token `{}`\n",
+                    chunk.identifier.index(),
+                    gap.prev_token
+                ));
+            }
+            ChunkContent::Items(events) => {
+                dump.push_str(&format!(
+                    "chunk #{}: {} events\n",
+                    chunk.identifier.index(),
+                    events.len()
+                ));
+
+                for event in events {
+                    let raw = event.raw();
+
+                    dump.push_str(&format!(
+                        "    {} {} from {}\n",
+                        event.event_id().map_or_else(|| "no ID".to_owned(), |id| id.to_string()),
+                        raw.get_field::<String>("type").ok().flatten().unwrap_or_default(),
+                        raw.get_field::<String>("sender").ok().flatten().unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        next_chunk = event_cache_store
+            .load_previous_chunk(LinkedChunkId::Room(room_id), chunk.identifier)
+            .await
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(dump)
+}
@@ -0,0 +1,97 @@
+use std::sync::{LazyLock, Mutex};
+
+use matrix_sdk::{Room, ruma::OwnedEventId};
+use tokio::{
+    spawn,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc::{Sender, error::TrySendError},
+    },
+};
+use tracing::{debug, warn};
+
+use crate::input::Input;
+
+/// A command that any mode, or any background task, can emit for the app,
+/// without a dedicated `Input` and `app::Message` of its own.
+#[derive(Clone, Debug)]
+pub enum Command {
+    /// Open the room in the active tab, focused on the event if any.
+    OpenRoom(Room, Option<OwnedEventId>),
+    /// Show an error in a toast.
+    Toast(String),
+    Navigate(Destination),
+    /// Run a command as typed in the command prompt, e.g. `check event-cache`.
+    /// The commands to be confirmed open the prompt with the confirmation.
+    RunCommand(String),
+}
+
+/// Where `Command::Navigate` goes, the modes being built by the app.
+#[derive(Clone, Copy, Debug)]
+pub enum Destination {
+    RoomList,
+    Logger,
+    Memory,
+    Tasks,
+}
+
+/// The input channel of the app, set once the app has started.
+static INPUT_SENDER: Mutex<Option<Sender<Input>>> = Mutex::new(None);
+
+/// The subscribers to the commands dispatched by the app.
+static SUBSCRIBERS: LazyLock<broadcast::Sender<Command>> =
+    LazyLock::new(|| broadcast::channel(64).0);
+
+/// Connect the bus to the input channel, so that the commands emitted are
+/// dispatched with the other inputs.
+pub fn init(input_sender: Sender<Input>) {
+    *INPUT_SENDER.lock().unwrap() = Some(input_sender);
+}
+
+/// Emit `command`, to be dispatched by the app after the current input. It
+/// doesn't block, so it can be called from the `update` of a mode.
+pub fn emit(command: Command) {
+    let Some(input_sender) = INPUT_SENDER.lock().unwrap().clone() else {
+        warn!("The bus isn't initialised, {command:?} is dropped");
+
+        return;
+    };
+
+    match input_sender.try_send(Input::Bus(command)) {
+        Ok(()) | Err(TrySendError::Closed(_)) => {}
+        // Wait for the inputs to be handled, in the background.
+        Err(TrySendError::Full(input)) => {
+            spawn(async move {
+                let _ = input_sender.send(input).await;
+            });
+        }
+    }
+}
+
+/// Subscribe to the commands dispatched by the app, e.g. to know when a room
+/// is opened.
+pub fn subscribe() -> broadcast::Receiver<Command> {
+    SUBSCRIBERS.subscribe()
+}
+
+/// Tell the subscribers that `command` is dispatched.
+pub fn publish(command: &Command) {
+    // There may be no subscriber.
+    let _ = SUBSCRIBERS.send(command.clone());
+}
+
+/// Log the commands dispatched by the app, to follow what the modes and the
+/// tasks ask for.
+pub async fn log_task() {
+    let mut commands = subscribe();
+
+    loop {
+        match commands.recv().await {
+            Ok(command) => debug!("Dispatching {command:?}"),
+            Err(RecvError::Lagged(number_of_commands)) => {
+                debug!("{number_of_commands} commands dispatched without being logged")
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
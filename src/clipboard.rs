@@ -0,0 +1,13 @@
+use std::io::{self, Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+/// Copy `text` into the system clipboard, with the OSC 52 escape sequence.
+///
+/// The terminal must support OSC 52, otherwise it is a no-op.
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
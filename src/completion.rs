@@ -0,0 +1,95 @@
+use std::ops::Not;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{TextArea, block::block};
+
+/// Maximum number of candidates shown by the popup.
+const MAXIMUM_NUMBER_OF_CANDIDATES: usize = 8;
+
+/// A completion candidate.
+pub struct Candidate<T> {
+    /// What is shown in the popup.
+    pub label: String,
+
+    /// What replaces the word being completed.
+    pub replacement: String,
+
+    /// Anything the caller needs once the candidate is accepted.
+    pub data: T,
+}
+
+/// A completion popup, for the word before the cursor of a [`TextArea`].
+///
+/// The caller finds the candidates for the word, see
+/// [`TextArea::word_before_cursor`]; the popup lets the user pick one, and
+/// replaces the word with it.
+pub struct Completion<T> {
+    candidates: Vec<Candidate<T>>,
+    selected: usize,
+}
+
+impl<T> Completion<T> {
+    /// Create a completion popup, or `None` if there is no candidate.
+    pub fn new(candidates: impl IntoIterator<Item = Candidate<T>>) -> Option<Self> {
+        let candidates =
+            candidates.into_iter().take(MAXIMUM_NUMBER_OF_CANDIDATES).collect::<Vec<_>>();
+
+        candidates.is_empty().not().then_some(Self { candidates, selected: 0 })
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.candidates.len() - 1);
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+
+    /// Replace the word before the cursor of `textarea` by the selected
+    /// candidate, and return it.
+    pub fn accept(mut self, textarea: &mut TextArea) -> Candidate<T> {
+        let candidate = self.candidates.swap_remove(self.selected);
+        textarea.replace_word_before_cursor(&candidate.replacement);
+
+        candidate
+    }
+
+    /// Render the popup right above `anchor`.
+    pub fn render(&self, anchor: Rect, buffer: &mut Buffer) {
+        let height = self.candidates.len() as u16 + 2;
+        let width = self
+            .candidates
+            .iter()
+            .map(|candidate| candidate.label.chars().count() as u16 + 4)
+            .max()
+            .unwrap_or_default()
+            .min(anchor.width);
+        let area = Rect {
+            x: anchor.x,
+            y: anchor.y.saturating_sub(height),
+            width,
+            height: height.min(anchor.y),
+        };
+
+        Clear.render(area, buffer);
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+
+        StatefulWidget::render(
+            List::new(
+                self.candidates.iter().map(|candidate| ListItem::new(candidate.label.as_str())),
+            )
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .block(block()),
+            area,
+            buffer,
+            &mut state,
+        );
+    }
+}
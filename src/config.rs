@@ -0,0 +1,243 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId, OwnedRoomOrAliasId};
+use regex::Regex;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+    bandwidth,
+    bin::{self, Store},
+    bot, divider, profile, room, spell_check, status, textarea, time, url_preview,
+};
+
+/// The content of the configuration file. Every entry is optional.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct File {
+    server_name: Option<String>,
+    session_path: Option<PathBuf>,
+    log_dir: Option<PathBuf>,
+    log: Option<String>,
+    time_format: Option<String>,
+    /// `date`, `none`, or `every-N` for a compact divider every N messages.
+    dividers: Option<String>,
+    status_fields: Option<Vec<String>>,
+    /// The text of the marker at the start of the timeline, empty to disable
+    /// it.
+    start_marker: Option<String>,
+    url_previews_in_encrypted_rooms: Option<bool>,
+    persist_drafts: Option<bool>,
+    send_delay: Option<u64>,
+    key_bindings: Option<String>,
+    spell_check_languages: Option<Vec<String>>,
+    dictionaries_dir: Option<PathBuf>,
+    bot_patterns: Option<Vec<String>>,
+    store: Option<String>,
+    proxy: Option<String>,
+    simulate_network: Option<bool>,
+    low_bandwidth: Option<bool>,
+    user_agent: Option<String>,
+    request_timeout: Option<u64>,
+    insecure: Option<bool>,
+}
+
+impl File {
+    /// Load the configuration file at `path`. A missing file is an empty
+    /// configuration.
+    fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|error| format!("`{}` is invalid: {error}", path.display())),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(format!("`{}` can't be read: {error}", path.display())),
+        }
+    }
+}
+
+/// The options of the client, from the command line, or else from the
+/// configuration file, or else their default value.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub server_name: String,
+    pub session_path: PathBuf,
+    /// Whether the session path has been given, rather than found from the
+    /// profile.
+    pub has_explicit_session_path: bool,
+    pub profile: Option<String>,
+    pub log_dir: Option<PathBuf>,
+    pub log: String,
+    pub room: Option<OwnedRoomOrAliasId>,
+    pub event: Option<OwnedEventId>,
+    pub time_format: time::Format,
+    pub dividers: divider::Dividers,
+    pub start_marker: String,
+    pub status_fields: Vec<status::Field>,
+    pub url_previews_in_encrypted_rooms: bool,
+    pub persist_drafts: bool,
+    pub send_delay: u64,
+    pub key_bindings: textarea::KeyBindings,
+    pub spell_check_languages: Vec<String>,
+    pub dictionaries_dir: PathBuf,
+    pub bot_patterns: Vec<Regex>,
+    pub tail_room: Option<OwnedRoomId>,
+    pub replay_diff_log: Option<PathBuf>,
+    pub store: Store,
+    pub fresh: bool,
+    pub proxy: Option<String>,
+    pub simulate_network: bool,
+    pub low_bandwidth: bool,
+    pub user_agent: Option<String>,
+    pub request_timeout: Option<u64>,
+    pub insecure: bool,
+}
+
+impl Config {
+    /// Parse the command line, and merge it with the configuration file.
+    pub fn load() -> Result<Self, String> {
+        let config = Self::load_unchecked()?;
+
+        if config.simulate_network && config.proxy.is_some() {
+            return Err("`simulate-network` can't be used with `proxy`".to_owned());
+        }
+
+        if config.event.is_some() && config.room.is_none() {
+            return Err("`event` can't be used without `room`".to_owned());
+        }
+
+        Ok(config)
+    }
+
+    fn load_unchecked() -> Result<Self, String> {
+        let options: bin::Options = argh::from_env();
+        let path = match &options.config {
+            Some(path) => path.clone(),
+            None => default_path().ok_or_else(|| "no home directory".to_owned())?,
+        };
+        let file = File::load(&path)?;
+        let server_name =
+            options.server_name.or(file.server_name).unwrap_or_else(|| "matrix.org".to_owned());
+        let explicit_session_path = options.session_path.or(file.session_path);
+        let has_explicit_session_path = explicit_session_path.is_some();
+        let session_path = match explicit_session_path {
+            Some(session_path) => session_path,
+            None => profile::session_path(options.profile.clone(), &server_name)?,
+        };
+
+        Ok(Self {
+            server_name,
+            session_path,
+            has_explicit_session_path,
+            profile: options.profile,
+            log_dir: options.log_dir.or(file.log_dir),
+            log: options.log.or(file.log).unwrap_or_else(|| "trace".to_owned()),
+            room: options.room,
+            event: options.event,
+            time_format: match options.time_format {
+                Some(time_format) => time_format,
+                None => file
+                    .time_format
+                    .as_deref()
+                    .map(str::parse::<time::Format>)
+                    .transpose()?
+                    .unwrap_or(time::Format::Hour24),
+            },
+            dividers: file
+                .dividers
+                .as_deref()
+                .map(str::parse::<divider::Dividers>)
+                .transpose()?
+                .unwrap_or(divider::Dividers::Date),
+            start_marker: file
+                .start_marker
+                .unwrap_or_else(|| divider::DEFAULT_START_MARKER.to_owned()),
+            status_fields: match file.status_fields {
+                Some(fields) => fields
+                    .iter()
+                    .map(|field| field.parse::<status::Field>())
+                    .collect::<Result<_, _>>()?,
+                None => status::DEFAULT_FIELDS.to_vec(),
+            },
+            url_previews_in_encrypted_rooms: options.url_previews_in_encrypted_rooms
+                || file.url_previews_in_encrypted_rooms.unwrap_or(false),
+            persist_drafts: options.persist_drafts || file.persist_drafts.unwrap_or(false),
+            send_delay: file.send_delay.unwrap_or(0),
+            key_bindings: file
+                .key_bindings
+                .as_deref()
+                .map(str::parse::<textarea::KeyBindings>)
+                .transpose()?
+                .unwrap_or(textarea::KeyBindings::Emacs),
+            spell_check_languages: file.spell_check_languages.unwrap_or_default(),
+            dictionaries_dir: file
+                .dictionaries_dir
+                .unwrap_or_else(|| PathBuf::from(spell_check::DEFAULT_DICTIONARIES_DIRECTORY)),
+            bot_patterns: {
+                let patterns = if !options.bot_pattern.is_empty() {
+                    options.bot_pattern
+                } else {
+                    file.bot_patterns.unwrap_or_else(|| {
+                        bot::DEFAULT_PATTERNS.into_iter().map(ToOwned::to_owned).collect()
+                    })
+                };
+
+                patterns
+                    .iter()
+                    .map(|pattern| {
+                        Regex::new(pattern)
+                            .map_err(|error| format!("invalid bot pattern `{pattern}`: {error}"))
+                    })
+                    .collect::<Result<_, _>>()?
+            },
+            tail_room: options.tail_room,
+            replay_diff_log: options.replay_diff_log,
+            store: match options.store {
+                Some(store) => store,
+                None => file
+                    .store
+                    .as_deref()
+                    .map(str::parse::<Store>)
+                    .transpose()?
+                    .unwrap_or(Store::Sqlite),
+            },
+            fresh: options.fresh,
+            proxy: options.proxy.or(file.proxy),
+            simulate_network: options.simulate_network || file.simulate_network.unwrap_or(false),
+            low_bandwidth: options.low_bandwidth || file.low_bandwidth.unwrap_or(false),
+            user_agent: options.user_agent.or(file.user_agent),
+            request_timeout: options.request_timeout.or(file.request_timeout),
+            insecure: options.insecure || file.insecure.unwrap_or(false),
+        })
+    }
+
+    /// Apply the options that can change while the client is running.
+    pub fn apply(&self) {
+        time::set_format(self.time_format);
+        divider::set_dividers(self.dividers);
+        divider::set_start_marker(self.start_marker.clone());
+        status::set_fields(self.status_fields.clone());
+        url_preview::enable_in_encrypted_rooms(self.url_previews_in_encrypted_rooms);
+        room::persist_drafts(self.persist_drafts);
+        room::set_send_delay(self.send_delay);
+        textarea::set_key_bindings(self.key_bindings);
+
+        if let Err(error) = spell_check::load(&self.spell_check_languages, &self.dictionaries_dir) {
+            error!("Failed to load the spell checking dictionaries: {error}");
+        }
+        bot::set_patterns(self.bot_patterns.clone());
+        bandwidth::set_low(self.low_bandwidth);
+    }
+}
+
+/// `$XDG_CONFIG_HOME/multiverse/config.toml`, or
+/// `~/.config/multiverse/config.toml`.
+fn default_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("multiverse").join("config.toml"))
+}
@@ -0,0 +1,15 @@
+use std::sync::{Arc, OnceLock};
+
+use matrix_sdk_crypto::store::DynCryptoStore;
+
+/// The crypto store given to the client, kept to inspect what the client
+/// doesn't expose, e.g. the room key requests or the withheld codes.
+static CRYPTO_STORE: OnceLock<Arc<DynCryptoStore>> = OnceLock::new();
+
+pub fn set(crypto_store: Arc<DynCryptoStore>) {
+    let _ = CRYPTO_STORE.set(crypto_store);
+}
+
+pub fn get() -> Option<Arc<DynCryptoStore>> {
+    CRYPTO_STORE.get().cloned()
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use ratatui::{buffer::Buffer, layout::Rect};
+use tracing::warn;
+
+use crate::app;
+
+/// What a panel does when it's opened.
+#[derive(Clone, Copy)]
+pub enum Hook {
+    /// Run a message, e.g. to show some details of the timeline, which renders
+    /// them itself.
+    Update(fn() -> app::Message),
+    /// Toggle an overlay, drawn over the app until it's toggled again.
+    Render(fn(Rect, &mut Buffer)),
+}
+
+/// A debug panel, listed in the panel switcher.
+#[derive(Clone, Copy)]
+pub struct Panel {
+    pub name: &'static str,
+    /// The key opening the panel in the switcher.
+    pub key: char,
+    pub hook: Hook,
+}
+
+/// The registered panels, in the order of registration.
+static PANELS: Mutex<Vec<Panel>> = Mutex::new(Vec::new());
+
+/// The names of the overlays being drawn.
+static OVERLAYS: LazyLock<Mutex<HashSet<&'static str>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Register `panel`, unless its name or its key is taken already.
+pub fn register(panel: Panel) {
+    let mut panels = PANELS.lock().unwrap();
+
+    if let Some(other) =
+        panels.iter().find(|other| other.name == panel.name || other.key == panel.key)
+    {
+        warn!(
+            "The debug panel `{}` ({}) conflicts with `{}` ({}), it's not registered",
+            panel.name, panel.key, other.name, other.key
+        );
+
+        return;
+    }
+
+    panels.push(panel);
+}
+
+pub fn panels() -> Vec<Panel> {
+    PANELS.lock().unwrap().clone()
+}
+
+pub fn is_overlay_visible(name: &str) -> bool {
+    OVERLAYS.lock().unwrap().contains(name)
+}
+
+/// Open the panel named `name`: return its message, or toggle its overlay.
+pub fn open(name: &str) -> Option<app::Message> {
+    let panel = PANELS.lock().unwrap().iter().find(|panel| panel.name == name).copied()?;
+
+    match panel.hook {
+        Hook::Update(update) => Some(update()),
+        Hook::Render(_) => {
+            let mut overlays = OVERLAYS.lock().unwrap();
+
+            if !overlays.remove(panel.name) {
+                overlays.insert(panel.name);
+            }
+
+            Some(app::Message::Mode(app::Mode::None))
+        }
+    }
+}
+
+/// Draw the visible overlays, in the order of registration.
+pub fn render(area: Rect, buffer: &mut Buffer) {
+    let overlays = OVERLAYS.lock().unwrap().clone();
+
+    for panel in panels().into_iter().filter(|panel| overlays.contains(panel.name)) {
+        if let Hook::Render(render) = panel.hook {
+            render(area, buffer);
+        }
+    }
+}
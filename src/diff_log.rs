@@ -0,0 +1,293 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    mem,
+    ops::Not,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use matrix_sdk::ruma::OwnedEventId;
+use matrix_sdk_ui::eyeball_im::{Vector, VectorDiff};
+use serde_json::{Value, json};
+
+use crate::Error;
+
+const MAXIMUM_NUMBER_OF_ENTRIES: usize = 500;
+
+/// A diff applied to the timeline items, as recorded in the diff log.
+pub struct Entry {
+    pub time: DateTime<Local>,
+    /// The kind of the diff, with its index or its length, e.g. `Insert 12`.
+    pub kind: String,
+    /// The items added, updated or removed by the diff.
+    pub items: Vec<String>,
+    /// The events present more than once in the items once the diff is
+    /// applied, i.e. a duplicate insertion.
+    pub duplicated_event_ids: Vec<OwnedEventId>,
+    /// The diff, in the format printed by `--tail-room`, to be exported.
+    pub diff: Value,
+}
+
+/// The last diffs applied to the items of a timeline, the most recent last.
+#[derive(Default)]
+pub struct Log {
+    entries: VecDeque<Entry>,
+    /// The items before the oldest entry, i.e. once the entries dropped from
+    /// the log are applied, so that the export can be replayed from an empty
+    /// timeline.
+    base: Vector<Value>,
+}
+
+impl Log {
+    pub fn push(&mut self, entry: Entry) {
+        let excess = (self.entries.len() + 1).saturating_sub(MAXIMUM_NUMBER_OF_ENTRIES);
+
+        for oldest_entry in self.entries.drain(..excess) {
+            // The diff has been applied to the real items already, it can't be
+            // out of bounds.
+            let _ = apply(&mut self.base, &oldest_entry.diff);
+        }
+
+        self.entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The estimated size of the entries, in bytes.
+    pub fn estimated_size(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|entry| {
+                mem::size_of_val(entry)
+                    + entry.kind.len()
+                    + entry.items.iter().map(String::len).sum::<usize>()
+                    + entry.diff.to_string().len()
+            })
+            .sum()
+    }
+
+    /// Write the diffs in a timestamped JSON Lines file, preceded by a reset to
+    /// the items before the oldest one. Return the path of the file.
+    pub fn export(&self) -> io::Result<PathBuf> {
+        let path = PathBuf::from(format!(
+            "multiverse-diff-log-{}.jsonl",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let mut diffs = String::new();
+
+        if self.base.is_empty().not() {
+            diffs.push_str(
+                &json!({ "diff": "reset", "items": self.base.iter().collect::<Vec<_>>() })
+                    .to_string(),
+            );
+            diffs.push('\n');
+        }
+
+        for entry in &self.entries {
+            diffs.push_str(&entry.diff.to_string());
+            diffs.push('\n');
+        }
+
+        fs::write(&path, diffs)?;
+
+        Ok(path.canonicalize().unwrap_or(path))
+    }
+}
+
+/// Apply the JSON `diff` to `items`, like the timeline would. Return the new
+/// items of the diff, or why it can't be applied, e.g. an index out of bounds.
+fn apply(items: &mut Vector<Value>, diff: &Value) -> Result<Vec<Value>, String> {
+    let field = |name: &str| diff.get(name).cloned().ok_or_else(|| format!("no `{name}`"));
+    let index = |name: &str| {
+        field(name)?
+            .as_u64()
+            .map(|index| index as usize)
+            .ok_or_else(|| format!("`{name}` isn't an index"))
+    };
+    let values = || match field("items")? {
+        Value::Array(values) => Ok(values.into_iter().collect::<Vector<_>>()),
+        _ => Err("`items` isn't an array".to_owned()),
+    };
+    let out_of_bounds = |index: usize, length: usize| {
+        format!("index {index} is out of bounds, there are {length} items")
+    };
+
+    let diff = match diff.get("diff").and_then(Value::as_str) {
+        Some("append") => VectorDiff::Append { values: values()? },
+        Some("clear") => VectorDiff::Clear,
+        Some("push_front") => VectorDiff::PushFront { value: field("item")? },
+        Some("push_back") => VectorDiff::PushBack { value: field("item")? },
+        Some("pop_front" | "pop_back") if items.is_empty() => {
+            return Err("there is no item to pop".to_owned());
+        }
+        Some("pop_front") => VectorDiff::PopFront,
+        Some("pop_back") => VectorDiff::PopBack,
+        Some("insert") => match index("index")? {
+            index if index > items.len() => return Err(out_of_bounds(index, items.len())),
+            index => VectorDiff::Insert { index, value: field("item")? },
+        },
+        Some("set") => match index("index")? {
+            index if index >= items.len() => return Err(out_of_bounds(index, items.len())),
+            index => VectorDiff::Set { index, value: field("item")? },
+        },
+        Some("remove") => match index("index")? {
+            index if index >= items.len() => return Err(out_of_bounds(index, items.len())),
+            index => VectorDiff::Remove { index },
+        },
+        Some("truncate") => match index("length")? {
+            length if length > items.len() => return Err(out_of_bounds(length, items.len())),
+            length => VectorDiff::Truncate { length },
+        },
+        Some("reset") => VectorDiff::Reset { values: values()? },
+        Some(kind) => return Err(format!("unknown diff `{kind}`")),
+        None => return Err("no `diff`".to_owned()),
+    };
+
+    let mut new_items = Vec::new();
+    diff.clone().map(|item| new_items.push(item));
+    diff.apply(items);
+
+    Ok(new_items)
+}
+
+/// An unexpected diff met during a replay.
+pub struct Anomaly {
+    /// The line of the diff, from 1.
+    pub line: usize,
+    pub reason: String,
+}
+
+/// The outcome of a replay.
+#[derive(Default)]
+pub struct Replay {
+    /// The items once all the diffs are applied.
+    pub items: Vector<Value>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Apply the diffs read from `reader`, as exported from the diff log or printed
+/// by `--tail-room`, to an empty list of items. The items are the JSON values of
+/// the diffs: neither the timeline nor its rendering are involved. The diffs
+/// that can't be applied are skipped, and reported as anomalies, like the
+/// duplicated events.
+pub fn replay(reader: impl BufRead) -> io::Result<Replay> {
+    let mut replay = Replay::default();
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut anomaly = |reason| replay.anomalies.push(Anomaly { line: line_index + 1, reason });
+
+        let diff = match serde_json::from_str::<Value>(&line) {
+            Ok(diff) => diff,
+            Err(error) => {
+                anomaly(format!("invalid JSON: {error}"));
+
+                continue;
+            }
+        };
+
+        match apply(&mut replay.items, &diff) {
+            Ok(new_items) => {
+                let new_event_ids = new_items.iter().filter_map(event_id).collect::<HashSet<_>>();
+
+                for new_event_id in new_event_ids {
+                    let count = replay
+                        .items
+                        .iter()
+                        .filter(|item| event_id(item) == Some(new_event_id))
+                        .count();
+
+                    if count > 1 {
+                        anomaly(format!("{new_event_id} is present {count} times"));
+                    }
+                }
+            }
+            Err(reason) => anomaly(reason),
+        }
+    }
+
+    Ok(replay)
+}
+
+fn event_id(item: &Value) -> Option<&str> {
+    item.get("event_id").and_then(Value::as_str)
+}
+
+/// Replay the diff log at `path`, for `--replay-diff-log`, and print the items
+/// and the anomalies on stdout. It fails if there is any anomaly, to be used
+/// in a test.
+pub fn run(path: &Path) -> Result<(), Error> {
+    let replay = replay(BufReader::new(fs::File::open(path)?))?;
+    let mut stdout = io::stdout().lock();
+
+    for (index, item) in replay.items.iter().enumerate() {
+        let kind = item.get("kind").and_then(Value::as_str).unwrap_or("???");
+        let id = event_id(item)
+            .or_else(|| item.get("transaction_id").and_then(Value::as_str))
+            .unwrap_or_default();
+        let content = item.get("content").and_then(Value::as_str).unwrap_or_default();
+
+        writeln!(stdout, "{index:>5} {kind:<14} {id} {content}")?;
+    }
+
+    for anomaly in &replay.anomalies {
+        writeln!(stdout, "line {}: {}", anomaly.line, anomaly.reason)?;
+    }
+
+    stdout.flush()?;
+
+    if replay.anomalies.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::DiffLog(format!("{} anomalies", replay.anomalies.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replay;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/diff-log-anomalies.jsonl");
+
+    #[test]
+    fn test_replay_skips_an_out_of_bounds_diff() {
+        let replay = replay(FIXTURE.as_bytes()).unwrap();
+
+        let anomaly = &replay.anomalies[0];
+        assert_eq!(anomaly.line, 3);
+        assert_eq!(anomaly.reason, "index 9 is out of bounds, there are 3 items");
+
+        // The date divider has been removed, and `$c` never inserted.
+        let event_ids =
+            replay.items.iter().map(|item| item["event_id"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(event_ids, ["$a:example.org", "$b:example.org", "$a:example.org"]);
+    }
+
+    #[test]
+    fn test_replay_reports_a_duplicated_event() {
+        let replay = replay(FIXTURE.as_bytes()).unwrap();
+
+        assert_eq!(replay.anomalies.len(), 2);
+
+        let anomaly = &replay.anomalies[1];
+        assert_eq!(anomaly.line, 4);
+        assert_eq!(anomaly.reason, "$a:example.org is present 2 times");
+    }
+}
@@ -0,0 +1,77 @@
+use matrix_sdk::{Client, ClientBuildError};
+
+/// Print what has been discovered about the homeserver, so that a failing login
+/// can be understood.
+pub async fn print_report(client: &Client, server_name: &str) {
+    println!("Homeserver discovery for `{server_name}`");
+    print_row("homeserver", client.homeserver().to_string());
+
+    print_row(
+        "identity server",
+        match client.fetch_client_well_known().await {
+            Some(well_known) => well_known
+                .identity_server
+                .map(|identity_server| identity_server.base_url)
+                .unwrap_or_else(|| "none".to_owned()),
+            None => "no `.well-known` file".to_owned(),
+        },
+    );
+
+    print_row(
+        "login types",
+        match client.matrix_auth().get_login_types().await {
+            Ok(response) => response
+                .flows
+                .iter()
+                .map(|login_type| login_type.login_type())
+                .collect::<Vec<_>>()
+                .join(", "),
+            Err(error) => format!("failed: {error}"),
+        },
+    );
+
+    print_row(
+        "spec versions",
+        match client.supported_versions().await {
+            Ok(supported_versions) => supported_versions
+                .versions
+                .iter()
+                .filter_map(|version| version.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            Err(error) => format!("failed: {error}"),
+        },
+    );
+
+    println!();
+}
+
+fn print_row(label: &str, value: String) {
+    println!("  {label:<16} {value}");
+}
+
+/// Explain why the client can't be built for `server_name`, with some hints
+/// to fix it.
+pub fn explain_build_error(server_name: &str, error: &ClientBuildError) -> String {
+    match error {
+        ClientBuildError::MissingHomeserver => {
+            "No homeserver has been given, use `--server-name`.".to_owned()
+        }
+        ClientBuildError::InvalidServerName => {
+            format!("`{server_name}` is neither a valid server name nor a homeserver URL.")
+        }
+        ClientBuildError::AutoDiscovery(error) => format!(
+            "The discovery of the homeserver of `{server_name}` has failed: {error}.\n\
+             Check that `https://{server_name}/.well-known/matrix/client` is reachable, or \
+             give the URL of the homeserver directly, e.g. `--server-name https://…`."
+        ),
+        ClientBuildError::Url(error) => {
+            format!("`{server_name}` is not a valid URL: {error}.")
+        }
+        ClientBuildError::Http(error) => format!(
+            "The homeserver of `{server_name}` can't be reached: {error}.\n\
+             Behind a proxy, or with a self-signed certificate, see `--proxy` and `--insecure`."
+        ),
+        error => format!("The client can't be built: {error}."),
+    }
+}
@@ -0,0 +1,75 @@
+use std::{borrow::Cow, fmt, str::FromStr, sync::Mutex};
+
+/// The text of the marker at the start of the timeline.
+pub const DEFAULT_START_MARKER: &str = "Beginning of the room";
+
+static DIVIDERS: Mutex<Dividers> = Mutex::new(Dividers::Date);
+static START_MARKER: Mutex<Cow<'static, str>> = Mutex::new(Cow::Borrowed(DEFAULT_START_MARKER));
+
+/// The dividers between the events of the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dividers {
+    /// A divider between each day.
+    Date,
+
+    /// A compact divider every given number of messages, counted from the most
+    /// recent one.
+    Every(usize),
+
+    /// No divider.
+    None,
+}
+
+impl FromStr for Dividers {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let every = value
+            .strip_prefix("every-")
+            .and_then(|number| number.parse::<usize>().ok())
+            .filter(|number| *number > 0);
+
+        Ok(match (value, every) {
+            ("date", _) => Self::Date,
+            ("none", _) => Self::None,
+            (_, Some(number)) => Self::Every(number),
+            _ => {
+                return Err(format!(
+                    "unknown dividers `{value}`, expected `date`, `none` or `every-N`"
+                ));
+            }
+        })
+    }
+}
+
+impl fmt::Display for Dividers {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Date => formatter.write_str("date"),
+            Self::Every(number) => write!(formatter, "every-{number}"),
+            Self::None => formatter.write_str("none"),
+        }
+    }
+}
+
+pub fn dividers() -> Dividers {
+    *DIVIDERS.lock().unwrap()
+}
+
+pub fn set_dividers(dividers: Dividers) {
+    *DIVIDERS.lock().unwrap() = dividers;
+}
+
+/// The text of the marker at the start of the timeline, `None` if it is
+/// disabled.
+pub fn start_marker() -> Option<String> {
+    let start_marker = START_MARKER.lock().unwrap();
+
+    if start_marker.is_empty() { None } else { Some(start_marker.to_string()) }
+}
+
+/// Set the text of the marker at the start of the timeline. An empty text
+/// disables it.
+pub fn set_start_marker(start_marker: String) {
+    *START_MARKER.lock().unwrap() = Cow::Owned(start_marker);
+}
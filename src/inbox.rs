@@ -0,0 +1,218 @@
+use std::sync::Mutex;
+
+use futures::{StreamExt, pin_mut};
+use matrix_sdk::{
+    Client,
+    encryption::verification::{VerificationRequest, VerificationRequestState},
+    ruma::{
+        OwnedDeviceId, OwnedRoomId, OwnedTransactionId,
+        events::{
+            key::verification::request::ToDeviceKeyVerificationRequestEvent,
+            room::message::{MessageType, OriginalSyncRoomMessageEvent},
+            room_key_request::{Action, ToDeviceRoomKeyRequestEvent},
+        },
+    },
+};
+use tokio::{
+    spawn,
+    sync::mpsc::{Sender, unbounded_channel},
+};
+use tracing::info;
+
+use crate::input::Input;
+
+/// A request addressed to this device, waiting to be accepted or declined.
+#[derive(Clone)]
+pub enum Request {
+    /// A verification request, from another user, or from another device of
+    /// the own user.
+    Verification {
+        request: VerificationRequest,
+        /// The device of the other user, for the requests sent as to-device
+        /// events.
+        from_device: Option<OwnedDeviceId>,
+    },
+    /// A room key request from another device of the own user. The SDK only
+    /// answers to the verified devices.
+    RoomKey { device_id: OwnedDeviceId, room_id: OwnedRoomId, request_id: OwnedTransactionId },
+}
+
+impl Request {
+    /// The flow ID of the verification, or the ID of the room key request.
+    fn id(&self) -> &str {
+        match self {
+            Self::Verification { request, .. } => request.flow_id(),
+            Self::RoomKey { request_id, .. } => request_id.as_str(),
+        }
+    }
+}
+
+/// The pending requests, the oldest first. They stay in the inbox until they
+/// are accepted, declined or cancelled.
+static REQUESTS: Mutex<Vec<Request>> = Mutex::new(Vec::new());
+
+pub fn requests() -> Vec<Request> {
+    REQUESTS.lock().unwrap().clone()
+}
+
+/// Remove the request with this ID from the inbox, e.g. once it's declined.
+pub fn remove(id: &str) {
+    REQUESTS.lock().unwrap().retain(|request| request.id() != id);
+}
+
+fn push(request: Request) {
+    let mut requests = REQUESTS.lock().unwrap();
+
+    if requests.iter().all(|other| other.id() != request.id()) {
+        requests.push(request);
+    }
+}
+
+/// The badge of the status line, e.g. `1 verification request, 2 room key
+/// requests`, as long as the inbox isn't empty.
+pub fn badge() -> Option<String> {
+    let requests = REQUESTS.lock().unwrap();
+    let number_of_verifications =
+        requests.iter().filter(|request| matches!(request, Request::Verification { .. })).count();
+    let number_of_room_keys = requests.len() - number_of_verifications;
+
+    let count = |number: usize, noun: &str| match number {
+        0 => None,
+        1 => Some(format!("1 {noun}")),
+        number => Some(format!("{number} {noun}s")),
+    };
+
+    let badges = [
+        count(number_of_verifications, "verification request"),
+        count(number_of_room_keys, "room key request"),
+    ];
+    let badges = badges.into_iter().flatten().collect::<Vec<_>>();
+
+    (!badges.is_empty()).then(|| badges.join(", "))
+}
+
+/// Fill the inbox with the verification requests, and the room key requests,
+/// received by this device, and redraw when it changes.
+pub async fn requests_task(client: Client, input_sender: Sender<Input>) {
+    let (request_sender, mut request_receiver) = unbounded_channel();
+
+    let _to_device_verification_handler = client.event_handler_drop_guard({
+        let request_sender = request_sender.clone();
+
+        client.add_event_handler(
+            move |event: ToDeviceKeyVerificationRequestEvent, client: Client| {
+                let request_sender = request_sender.clone();
+
+                async move {
+                    if let Some(request) = client
+                        .encryption()
+                        .get_verification_request(&event.sender, &event.content.transaction_id)
+                        .await
+                    {
+                        let from_device = Some(event.content.from_device);
+                        let _ = request_sender.send(Request::Verification { request, from_device });
+                    }
+                }
+            },
+        )
+    });
+
+    let _in_room_verification_handler = client.event_handler_drop_guard({
+        let request_sender = request_sender.clone();
+
+        client.add_event_handler(move |event: OriginalSyncRoomMessageEvent, client: Client| {
+            let request_sender = request_sender.clone();
+
+            async move {
+                let MessageType::VerificationRequest(content) = &event.content.msgtype else {
+                    return;
+                };
+
+                if client.user_id() != Some(&*content.to) {
+                    return;
+                }
+
+                if let Some(request) = client
+                    .encryption()
+                    .get_verification_request(&event.sender, &event.event_id)
+                    .await
+                {
+                    let _ =
+                        request_sender.send(Request::Verification { request, from_device: None });
+                }
+            }
+        })
+    });
+
+    let _room_key_request_handler = client.event_handler_drop_guard({
+        let request_sender = request_sender.clone();
+        let input_sender = input_sender.clone();
+
+        client.add_event_handler(move |event: ToDeviceRoomKeyRequestEvent, client: Client| {
+            let request_sender = request_sender.clone();
+            let input_sender = input_sender.clone();
+
+            async move {
+                let content = event.content;
+
+                // Only the other devices of the own user ask for the room keys.
+                if client.user_id() != Some(&*event.sender)
+                    || client.device_id() == Some(&*content.requesting_device_id)
+                {
+                    return;
+                }
+
+                match (content.action, content.body) {
+                    (Action::Request, Some(body)) => {
+                        let _ = request_sender.send(Request::RoomKey {
+                            device_id: content.requesting_device_id,
+                            room_id: body.room_id,
+                            request_id: content.request_id,
+                        });
+                    }
+                    (Action::CancelRequest, _) => {
+                        remove(content.request_id.as_str());
+                        let _ = input_sender.send(Input::Redraw).await;
+                    }
+                    _ => {}
+                }
+            }
+        })
+    });
+
+    while let Some(request) = request_receiver.recv().await {
+        info!("New request in the inbox: {}", request.id());
+
+        push(request.clone());
+
+        if let Request::Verification { request, .. } = request {
+            spawn(remove_when_finished_task(request, input_sender.clone()));
+        }
+
+        let _ = input_sender.send(Input::Redraw).await;
+    }
+}
+
+/// Remove the verification request from the inbox once it's done or cancelled,
+/// from whichever device.
+async fn remove_when_finished_task(request: VerificationRequest, input_sender: Sender<Input>) {
+    let changes = request.changes();
+    pin_mut!(changes);
+
+    if !request.is_done() && !request.is_cancelled() {
+        while let Some(state) = changes.next().await {
+            if matches!(
+                state,
+                VerificationRequestState::Done | VerificationRequestState::Cancelled(_)
+            ) {
+                break;
+            }
+
+            let _ = input_sender.send(Input::Redraw).await;
+        }
+    }
+
+    remove(request.flow_id());
+
+    let _ = input_sender.send(Input::Redraw).await;
+}
@@ -1,20 +1,39 @@
 use std::sync::Arc;
 
-use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use futures::{FutureExt, StreamExt};
+use matrix_sdk::{
+    event_cache::RoomPaginationStatus,
+    ruma::{OwnedEventId, OwnedRoomId},
+};
 use matrix_sdk_ui::{
-    eyeball_im::VectorDiff, room_list_service::RoomListItem, timeline as sdk_timeline,
+    eyeball_im::VectorDiff,
+    room_list_service::{RoomListItem, RoomListLoadingState},
+    sync_service, timeline as sdk_timeline,
 };
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc::Sender;
 
-use crate::{app, mode, room, timeline};
+use crate::{app, bus, mode, room, timeline, transcript, url_preview::UrlPreview};
 
 #[derive(Debug)]
 pub enum Input {
     Redraw,
     KeyPress(KeyEvent),
+    Paste(String),
     RoomListUpdate(Vec<VectorDiff<RoomListItem>>),
-    TimelineUpdate(Vec<VectorDiff<Arc<sdk_timeline::TimelineItem>>>),
+    RoomListLoadingStateUpdate(RoomListLoadingState),
+    RoomPreviewReady(OwnedRoomId),
+    TimelineUpdate(OwnedRoomId, Vec<VectorDiff<Arc<sdk_timeline::TimelineItem>>>),
+    OwnReadReceiptUpdate(OwnedRoomId, Option<OwnedEventId>),
+    PaginationStatusUpdate(OwnedRoomId, RoomPaginationStatus),
+    Bus(bus::Command),
+    UrlPreview(OwnedRoomId, String, Option<UrlPreview>),
+    RoomInfoUpdate(OwnedRoomId),
+    UnknownToken { soft_logout: bool },
+    NumberOfQueuedMessagesUpdate(usize),
+    SyncServiceStateUpdate(sync_service::State),
+    SyncServiceRestart,
+    DelayedSendElapsed(OwnedRoomId),
 }
 
 pub async fn handle_terminal_events_task(input_sender: Sender<Input>) {
@@ -27,6 +46,12 @@ pub async fn handle_terminal_events_task(input_sender: Sender<Input>) {
                     let _ = input_sender.send(Input::KeyPress(key_event)).await;
                 }
 
+                // Bracketed paste: the pasted text at once, rather than one key
+                // press per character.
+                Event::Paste(text) => {
+                    let _ = input_sender.send(Input::Paste(text)).await;
+                }
+
                 Event::Resize(..) => {
                     let _ = input_sender.send(Input::Redraw).await;
                 }
@@ -38,18 +63,53 @@ pub async fn handle_terminal_events_task(input_sender: Sender<Input>) {
     }
 }
 
-pub async fn map_input_to_message(
-    input_receiver: &mut Receiver<Input>,
-    app_model: &app::Model,
-) -> Option<app::Message> {
-    match input_receiver.recv().await? {
+pub fn map_input_to_message(input: Input, app_model: &app::Model) -> Option<app::Message> {
+    match input {
         Input::Redraw => None,
         Input::KeyPress(key_event) => map_key_event_to_message(key_event, app_model),
+        Input::Paste(text) => match app_model.mode {
+            app::Mode::Insert => Some(app::Message::Room(room::Message::PasteMessage(text))),
+            _ => None,
+        },
         Input::RoomListUpdate(diffs) => {
             Some(app::Message::RoomList(mode::room_list::Message::UpdateRoomList(diffs)))
         }
-        Input::TimelineUpdate(diffs) => {
-            Some(app::Message::Room(room::Message::Timeline(timeline::Message::Update(diffs))))
+        Input::RoomListLoadingStateUpdate(loading_state) => Some(app::Message::RoomList(
+            mode::room_list::Message::UpdateLoadingState(loading_state),
+        )),
+        Input::RoomPreviewReady(room_id) => {
+            Some(app::Message::RoomList(mode::room_list::Message::PreviewReady(room_id)))
+        }
+        Input::TimelineUpdate(room_id, diffs) => Some(app::Message::RoomWithId(
+            room_id,
+            room::Message::Timeline(timeline::Message::Update(diffs)),
+        )),
+        Input::OwnReadReceiptUpdate(room_id, event_id) => Some(app::Message::RoomWithId(
+            room_id,
+            room::Message::Timeline(timeline::Message::UpdateOwnReadReceipt(event_id)),
+        )),
+        Input::PaginationStatusUpdate(room_id, status) => Some(app::Message::RoomWithId(
+            room_id,
+            room::Message::Timeline(timeline::Message::UpdatePaginationStatus(status)),
+        )),
+        Input::Bus(command) => Some(app::Message::Bus(command)),
+        Input::UrlPreview(room_id, url, preview) => Some(app::Message::RoomWithId(
+            room_id,
+            room::Message::Timeline(timeline::Message::UpdateUrlPreview(url, preview)),
+        )),
+        Input::RoomInfoUpdate(room_id) => {
+            Some(app::Message::RoomWithId(room_id, room::Message::UpdateInfo))
+        }
+        Input::UnknownToken { soft_logout } => {
+            Some(app::Message::OpenReauthentication { soft_logout })
+        }
+        Input::NumberOfQueuedMessagesUpdate(number_of_queued_messages) => {
+            Some(app::Message::UpdateNumberOfQueuedMessages(number_of_queued_messages))
+        }
+        Input::SyncServiceStateUpdate(state) => Some(app::Message::UpdateSyncServiceState(state)),
+        Input::SyncServiceRestart => Some(app::Message::RestartSyncService),
+        Input::DelayedSendElapsed(room_id) => {
+            Some(app::Message::RoomWithId(room_id, room::Message::SendDelayedMessage))
         }
     }
 }
@@ -62,38 +122,128 @@ fn map_key_event_to_message(key_event: KeyEvent, app_model: &app::Model) -> Opti
 
         code => match mode {
             app::Mode::None => match code {
+                KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::Room(room::Message::Timeline(timeline::Message::Scroll(
+                        timeline::Scroll::HalfPageUp,
+                    )))
+                }
+                KeyCode::Char('d') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::Room(room::Message::Timeline(timeline::Message::Scroll(
+                        timeline::Scroll::HalfPageDown,
+                    )))
+                }
+                KeyCode::Char('k') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::Mode(app::Mode::Switcher(mode::switcher::Model::new(
+                        &app_model.client,
+                    )))
+                }
+                KeyCode::Left if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    app::Message::GoBack
+                }
+                KeyCode::Right if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    app::Message::GoForward
+                }
+                // The terminals without the keyboard enhancement report
+                // <Ctrl-Tab> as <Tab>: <Alt-1> to <Alt-9> work everywhere.
+                KeyCode::Char(digit @ '1'..='9')
+                    if key_event.modifiers.contains(KeyModifiers::ALT) =>
+                {
+                    app::Message::SelectTab(digit as usize - '1' as usize)
+                }
+                KeyCode::Char('z') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::Room(room::Message::UndoSend)
+                }
+                KeyCode::Char('h') => app::Message::Mode(app::Mode::History(
+                    mode::history::Model::new(app_model.recent_rooms()),
+                )),
+                KeyCode::Tab if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::NextTab
+                }
+                KeyCode::BackTab if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app::Message::PreviousTab
+                }
                 KeyCode::Char('q') => app::Message::Quit,
                 KeyCode::Char(' ') => {
                     app::Message::Mode(app::Mode::Space(mode::space::Model::new(
                         app_model.client.clone(),
                         app_model.sync_service.clone(),
-                        app_model.input_sender.clone(),
                     )))
                 }
                 KeyCode::Char('r') => app::Message::Mode(app::Mode::Room(mode::room::Model::new(
-                    app_model.room.is_some(),
+                    app_model.room().is_some(),
                 ))),
                 KeyCode::Char('i') => app::Message::Mode(app::Mode::Insert),
+                KeyCode::Char(':') => {
+                    app::Message::Mode(app::Mode::Command(mode::command::Model::new()))
+                }
                 KeyCode::Up => app::Message::Room(room::Message::Timeline(
                     timeline::Message::Scroll(timeline::Scroll::Up),
                 )),
                 KeyCode::Down => app::Message::Room(room::Message::Timeline(
                     timeline::Message::Scroll(timeline::Scroll::Down),
                 )),
+                KeyCode::PageUp => app::Message::Room(room::Message::Timeline(
+                    timeline::Message::Scroll(timeline::Scroll::PageUp),
+                )),
+                KeyCode::PageDown => app::Message::Room(room::Message::Timeline(
+                    timeline::Message::Scroll(timeline::Scroll::PageDown),
+                )),
+                // Back to the most recent messages, e.g. after the room has
+                // been opened at the read marker.
+                KeyCode::End => app::Message::Room(room::Message::Timeline(
+                    timeline::Message::Scroll(timeline::Scroll::End),
+                )),
                 _ => return None,
             },
 
+            app::Mode::Insert
+                if code == KeyCode::Char('z')
+                    && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                app::Message::Room(room::Message::UndoSend)
+            }
+            app::Mode::Insert
+                if code == KeyCode::Char('s')
+                    && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                app::Message::Room(room::Message::SuggestSpellings)
+            }
             app::Mode::Insert => app::Message::Room(room::Message::UpdateMessage(key_event)),
 
             app::Mode::Space(_) => app::Message::Space(match code {
                 KeyCode::Char('f') => mode::space::Message::OpenRoomList,
+                KeyCode::Char('D') => mode::space::Message::OpenDirectory,
+                KeyCode::Char('X') => mode::space::Message::OpenCrossSigning,
+                KeyCode::Char('Z') => mode::space::Message::OpenSecretStorage,
+                KeyCode::Char('R') => mode::space::Message::OpenInbox,
+                KeyCode::Char('I') => mode::space::Message::OpenThirdPartyIds,
+                KeyCode::Char('N') => mode::space::Message::OpenNetwork,
                 KeyCode::Char('S') => mode::space::Message::StartSyncService,
+                KeyCode::Char('o') => mode::space::Message::ToggleOffline,
+                KeyCode::Char('b') => mode::space::Message::ToggleLowBandwidth,
                 KeyCode::Char('s') => mode::space::Message::StopSyncService,
                 KeyCode::Char('c') => mode::space::Message::EmptyEventCache,
                 KeyCode::Char('l') => mode::space::Message::OpenLogger,
+                KeyCode::Char('d') => mode::space::Message::ToggleLayoutDebug,
+                KeyCode::Char('H') => mode::space::Message::TogglePerformanceHud,
+                KeyCode::Char('P') => mode::space::Message::OpenDebugPanels,
+                KeyCode::Char('M') => mode::space::Message::OpenMemory,
+                KeyCode::Char('T') => mode::space::Message::OpenTasks,
+                KeyCode::Char('v') => mode::space::Message::ToggleSplitLogger,
+                KeyCode::Char('p') => mode::space::Message::Screenshot,
                 _ => return None,
             }),
 
+            app::Mode::RoomList(_) if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                app::Message::RoomList(match code {
+                    KeyCode::Left => mode::room_list::Message::GrowPreview,
+                    KeyCode::Right => mode::room_list::Message::ShrinkPreview,
+                    KeyCode::Char('p') => mode::room_list::Message::TogglePreview,
+                    KeyCode::Char('t') => mode::room_list::Message::ToggleDensity,
+                    _ => mode::room_list::Message::UpdateFilter(key_event),
+                })
+            }
+
             app::Mode::RoomList(_) => app::Message::RoomList(match code {
                 KeyCode::Up => mode::room_list::Message::MoveCursorUp,
                 KeyCode::Down => mode::room_list::Message::MoveCursorDown,
@@ -101,14 +251,23 @@ fn map_key_event_to_message(key_event: KeyEvent, app_model: &app::Model) -> Opti
                 _ => mode::room_list::Message::UpdateFilter(key_event),
             }),
 
+            app::Mode::Room(_) if code == KeyCode::Char('w') => app::Message::CloseTab,
+
+            // The commands of the command prompt are available from the panels as
+            // well.
+            app::Mode::Room(_) if code == KeyCode::Char('C') => {
+                app::Message::Bus(bus::Command::RunCommand("check event-cache".to_owned()))
+            }
+
             app::Mode::Room(_) => app::Message::Room(match code {
                 KeyCode::Char('b') => room::Message::Timeline(timeline::Message::PaginateBackwards),
-                KeyCode::Char('r') => {
-                    room::Message::Timeline(timeline::Message::ToggleReactionOnLastMessage)
-                }
+                KeyCode::Char('r') => room::Message::Timeline(
+                    timeline::Message::ToggleReactionOnLastMessage("👍".to_owned()),
+                ),
                 KeyCode::Char('s') => {
                     room::Message::Timeline(timeline::Message::Scroll(timeline::Scroll::Start))
                 }
+                KeyCode::Char('D') => room::Message::OpenJumpToDate,
                 KeyCode::Char('e') => {
                     room::Message::Timeline(timeline::Message::Scroll(timeline::Scroll::End))
                 }
@@ -124,11 +283,49 @@ fn map_key_event_to_message(key_event: KeyEvent, app_model: &app::Model) -> Opti
                 KeyCode::Char('l') => room::Message::Timeline(timeline::Message::ShowDetails(
                     timeline::Details::LinkedChunk,
                 )),
+                KeyCode::Char('E') => room::Message::Timeline(timeline::Message::ShowDetails(
+                    timeline::Details::LocalEchoes,
+                )),
+                KeyCode::Char('L') => room::Message::Timeline(timeline::Message::ShowDetails(
+                    timeline::Details::DiffLog,
+                )),
+                KeyCode::Char('X') => room::Message::Timeline(timeline::Message::ExportDiffLog),
+                KeyCode::Char('O') => {
+                    room::Message::Timeline(timeline::Message::ToggleOrderingValidation)
+                }
+                KeyCode::Char('u') => room::Message::Timeline(timeline::Message::DecryptWithBackup),
+                KeyCode::Char('R') => room::Message::Timeline(timeline::Message::ShowReadReceipts),
+                KeyCode::Char('N') => room::Message::Timeline(timeline::Message::ShowUnreadBy),
+                KeyCode::Char('F') => room::Message::Timeline(timeline::Message::ShowReadMarkers),
+                KeyCode::Char('G') => room::Message::Timeline(timeline::Message::ShowRelations),
+                KeyCode::Char('B') => {
+                    room::Message::Timeline(timeline::Message::TogglePaginationBar)
+                }
+                KeyCode::Char('V') => room::Message::Timeline(timeline::Message::ShowShield),
+                KeyCode::Char('S') => room::Message::Timeline(timeline::Message::ToggleSpoiler),
+                KeyCode::Char('a') => room::Message::ToggleReply,
                 KeyCode::Char('m') => room::Message::MarkAsRead,
                 KeyCode::Char('c') => room::Message::EmptyEventCache,
+                KeyCode::Char('P') => room::Message::OpenPowerLevels,
+                KeyCode::Char('M') => room::Message::OpenModeration,
+                KeyCode::Char('K') => room::Message::OpenCrypto,
+                KeyCode::Char('k') => room::Message::OpenInboundSessions,
+                KeyCode::Char('d') => room::Message::OpenDevices,
+                KeyCode::Char('A') => room::Message::OpenAuditLog,
+                KeyCode::Char('f') => room::Message::OpenFederation,
+                KeyCode::Char('J') => room::Message::JoinSuccessorRoom,
+                KeyCode::Char('U') => room::Message::Upgrade,
+                KeyCode::Char('x') => room::Message::OpenExport,
                 _ => return None,
             }),
 
+            app::Mode::Logger(logger_model) if logger_model.search_is_focused() => {
+                app::Message::Logger(match code {
+                    KeyCode::Enter => mode::logger::Message::UnfocusSearch,
+                    _ => mode::logger::Message::UpdateSearch(key_event),
+                })
+            }
+
             app::Mode::Logger(_) => app::Message::Logger(match code {
                 KeyCode::Char('l') => mode::logger::Message::OpenCommandPanel,
                 KeyCode::Up => mode::logger::Message::Scroll(mode::logger::Scroll::Up),
@@ -137,6 +334,259 @@ fn map_key_event_to_message(key_event: KeyEvent, app_model: &app::Model) -> Opti
                 KeyCode::Left => mode::logger::Message::DecreaseShownLogLevel,
                 KeyCode::Char('s') => mode::logger::Message::FocusFilter,
                 KeyCode::Char('f') => mode::logger::Message::ToggleFilters,
+                KeyCode::Char('/') => mode::logger::Message::FocusSearch,
+                KeyCode::Char('n') => mode::logger::Message::NextMatch,
+                KeyCode::Char('N') => mode::logger::Message::PreviousMatch,
+                KeyCode::Char('e') => mode::logger::Message::Export,
+                _ => return None,
+            }),
+
+            app::Mode::PowerLevels(power_levels_model) if power_levels_model.is_editing() => {
+                app::Message::PowerLevels(match code {
+                    KeyCode::Enter => mode::power_levels::Message::ConfirmValue,
+                    _ => mode::power_levels::Message::UpdateValue(key_event),
+                })
+            }
+
+            app::Mode::PowerLevels(power_levels_model) if power_levels_model.is_previewing() => {
+                app::Message::PowerLevels(match code {
+                    KeyCode::Char('y') => mode::power_levels::Message::Send,
+                    KeyCode::Char('n') => mode::power_levels::Message::CancelPreview,
+                    _ => return None,
+                })
+            }
+
+            app::Mode::PowerLevels(_) => app::Message::PowerLevels(match code {
+                KeyCode::Up => mode::power_levels::Message::MoveCursorUp,
+                KeyCode::Down => mode::power_levels::Message::MoveCursorDown,
+                KeyCode::Enter => mode::power_levels::Message::Edit,
+                KeyCode::Char('s') => mode::power_levels::Message::Preview,
+                _ => return None,
+            }),
+
+            app::Mode::Reauthenticate(_) => app::Message::Reauthenticate(match code {
+                KeyCode::Enter => mode::reauthenticate::Message::Submit,
+                _ => mode::reauthenticate::Message::UpdatePassword(key_event),
+            }),
+
+            app::Mode::Command(command_model) if command_model.is_confirming() => {
+                app::Message::Command(match code {
+                    KeyCode::Char('y') => mode::command::Message::Confirm,
+                    KeyCode::Char('n') => mode::command::Message::Cancel,
+                    _ => return None,
+                })
+            }
+
+            app::Mode::Command(_) => app::Message::Command(match code {
+                KeyCode::Enter => mode::command::Message::Execute,
+                _ => mode::command::Message::UpdateCommand(key_event),
+            }),
+
+            app::Mode::DebugPanels(_) => app::Message::DebugPanels(match code {
+                KeyCode::Up => mode::debug_panels::Message::MoveCursorUp,
+                KeyCode::Down => mode::debug_panels::Message::MoveCursorDown,
+                KeyCode::Enter => mode::debug_panels::Message::Open,
+                KeyCode::Char(key) => mode::debug_panels::Message::OpenWithKey(key),
+                _ => return None,
+            }),
+
+            app::Mode::Switcher(_) => app::Message::Switcher(match code {
+                KeyCode::Up => mode::switcher::Message::MoveCursorUp,
+                KeyCode::Down => mode::switcher::Message::MoveCursorDown,
+                KeyCode::Enter => mode::switcher::Message::Select,
+                _ => mode::switcher::Message::UpdateSearch(key_event),
+            }),
+
+            app::Mode::JumpToDate(_) => app::Message::JumpToDate(match code {
+                KeyCode::Enter => mode::jump_to_date::Message::Jump,
+                _ => mode::jump_to_date::Message::UpdateDate(key_event),
+            }),
+
+            app::Mode::Account(account_model) if account_model.is_typing() => {
+                app::Message::Account(match code {
+                    KeyCode::Enter => mode::account::Message::Submit,
+                    _ => mode::account::Message::UpdateInput(key_event),
+                })
+            }
+
+            app::Mode::Account(_) => return None,
+
+            app::Mode::RoomKeys(room_keys_model) if room_keys_model.is_typing() => {
+                app::Message::RoomKeys(match code {
+                    KeyCode::Enter => mode::room_keys::Message::Submit,
+                    _ => mode::room_keys::Message::UpdateInput(key_event),
+                })
+            }
+
+            app::Mode::RoomKeys(_) => return None,
+
+            app::Mode::Tasks(_) => return None,
+
+            app::Mode::Memory(_) => match code {
+                KeyCode::Char('r') => app::Message::OpenMemory,
+                _ => return None,
+            },
+
+            app::Mode::History(_) => app::Message::History(match code {
+                KeyCode::Up => mode::history::Message::MoveCursorUp,
+                KeyCode::Down => mode::history::Message::MoveCursorDown,
+                KeyCode::Enter => mode::history::Message::Select,
+                _ => return None,
+            }),
+
+            app::Mode::Directory(directory_model) if directory_model.input_is_focused() => {
+                app::Message::Directory(match code {
+                    KeyCode::Enter => mode::directory::Message::Search,
+                    KeyCode::Tab => mode::directory::Message::FocusNext,
+                    _ => mode::directory::Message::UpdateInput(key_event),
+                })
+            }
+
+            app::Mode::Directory(_) => app::Message::Directory(match code {
+                KeyCode::Up => mode::directory::Message::MoveCursorUp,
+                KeyCode::Down => mode::directory::Message::MoveCursorDown,
+                KeyCode::Enter => mode::directory::Message::Join,
+                KeyCode::Tab => mode::directory::Message::FocusNext,
+                KeyCode::Char('n') => mode::directory::Message::NextPage,
+                KeyCode::Char('p') => mode::directory::Message::PreviousPage,
+                _ => return None,
+            }),
+
+            app::Mode::CrossSigning(cross_signing_model)
+                if cross_signing_model.is_authenticating() =>
+            {
+                app::Message::CrossSigning(match code {
+                    KeyCode::Enter => mode::cross_signing::Message::SubmitPassword,
+                    _ => mode::cross_signing::Message::UpdatePassword(key_event),
+                })
+            }
+
+            app::Mode::CrossSigning(cross_signing_model) if cross_signing_model.is_confirming() => {
+                app::Message::CrossSigning(match code {
+                    KeyCode::Char('y') => mode::cross_signing::Message::ConfirmReset,
+                    KeyCode::Char('n') => mode::cross_signing::Message::CancelReset,
+                    _ => return None,
+                })
+            }
+
+            app::Mode::CrossSigning(_) => app::Message::CrossSigning(match code {
+                KeyCode::Char('b') => mode::cross_signing::Message::Bootstrap,
+                KeyCode::Char('r') => mode::cross_signing::Message::Reset,
+                _ => return None,
+            }),
+
+            app::Mode::SecretStorage(secret_storage_model)
+                if secret_storage_model.is_entering_key() =>
+            {
+                app::Message::SecretStorage(match code {
+                    KeyCode::Enter => mode::secret_storage::Message::SubmitKey,
+                    _ => mode::secret_storage::Message::UpdateKey(key_event),
+                })
+            }
+
+            app::Mode::SecretStorage(_) => app::Message::SecretStorage(match code {
+                KeyCode::Up => mode::secret_storage::Message::ScrollUp,
+                KeyCode::Down => mode::secret_storage::Message::ScrollDown,
+                KeyCode::Char('k') => mode::secret_storage::Message::EnterKey,
+                _ => return None,
+            }),
+
+            app::Mode::Inbox(_) => app::Message::Inbox(match code {
+                KeyCode::Up => mode::inbox::Message::MoveCursorUp,
+                KeyCode::Down => mode::inbox::Message::MoveCursorDown,
+                KeyCode::Char('a') => mode::inbox::Message::Accept,
+                KeyCode::Char('d') => mode::inbox::Message::Decline,
+                _ => return None,
+            }),
+
+            app::Mode::Moderation(moderation_model) if moderation_model.is_confirming() => {
+                app::Message::Moderation(match code {
+                    KeyCode::Char('y') => mode::moderation::Message::ConfirmRedact,
+                    KeyCode::Char('n') => mode::moderation::Message::CancelRedact,
+                    _ => return None,
+                })
+            }
+
+            app::Mode::Moderation(_) => app::Message::Moderation(match code {
+                KeyCode::Up => mode::moderation::Message::MoveCursorUp,
+                KeyCode::Down => mode::moderation::Message::MoveCursorDown,
+                KeyCode::Enter => mode::moderation::Message::Redact,
+                _ => mode::moderation::Message::UpdateNumberOfMessages(key_event),
+            }),
+
+            app::Mode::ThirdPartyIds(third_party_ids_model)
+                if third_party_ids_model.is_prompting() =>
+            {
+                app::Message::ThirdPartyIds(match code {
+                    KeyCode::Enter => mode::third_party_ids::Message::Submit,
+                    _ => mode::third_party_ids::Message::UpdateInput(key_event),
+                })
+            }
+
+            app::Mode::ThirdPartyIds(third_party_ids_model)
+                if third_party_ids_model.is_confirming() =>
+            {
+                app::Message::ThirdPartyIds(match code {
+                    KeyCode::Char('y') => mode::third_party_ids::Message::ConfirmRemove,
+                    KeyCode::Char('n') => mode::third_party_ids::Message::CancelRemove,
+                    _ => return None,
+                })
+            }
+
+            app::Mode::ThirdPartyIds(_) => app::Message::ThirdPartyIds(match code {
+                KeyCode::Up => mode::third_party_ids::Message::MoveCursorUp,
+                KeyCode::Down => mode::third_party_ids::Message::MoveCursorDown,
+                KeyCode::Char('e') => mode::third_party_ids::Message::AddEmail,
+                KeyCode::Char('p') => mode::third_party_ids::Message::AddPhoneNumber,
+                KeyCode::Char('d') => mode::third_party_ids::Message::Remove,
+                _ => return None,
+            }),
+
+            app::Mode::Network(_) => app::Message::Network(match code {
+                KeyCode::Char('l') => mode::network::Message::NextLatency,
+                KeyCode::Char('f') => mode::network::Message::NextFailureRate,
+                _ => return None,
+            }),
+
+            app::Mode::Crypto(_) => app::Message::Crypto(match code {
+                KeyCode::Up => mode::crypto::Message::ScrollUp,
+                KeyCode::Down => mode::crypto::Message::ScrollDown,
+                _ => return None,
+            }),
+
+            app::Mode::Devices(_) => app::Message::Devices(match code {
+                KeyCode::Up => mode::devices::Message::MoveCursorUp,
+                KeyCode::Down => mode::devices::Message::MoveCursorDown,
+                KeyCode::Enter => mode::devices::Message::Select,
+                KeyCode::PageUp => mode::devices::Message::ScrollUp,
+                KeyCode::PageDown => mode::devices::Message::ScrollDown,
+                _ => return None,
+            }),
+
+            app::Mode::InboundSessions(_) => app::Message::InboundSessions(match code {
+                KeyCode::Up => mode::inbound_sessions::Message::MoveCursorUp,
+                KeyCode::Down => mode::inbound_sessions::Message::MoveCursorDown,
+                KeyCode::Char('e') => mode::inbound_sessions::Message::Export,
+                _ => return None,
+            }),
+
+            app::Mode::AuditLog(_) => app::Message::AuditLog(match code {
+                KeyCode::Up => mode::audit_log::Message::ScrollUp,
+                KeyCode::Down => mode::audit_log::Message::ScrollDown,
+                _ => return None,
+            }),
+
+            app::Mode::Federation(_) => app::Message::Federation(match code {
+                KeyCode::Up => mode::federation::Message::ScrollUp,
+                KeyCode::Down => mode::federation::Message::ScrollDown,
+                _ => return None,
+            }),
+
+            app::Mode::Export(_) => app::Message::Export(match code {
+                KeyCode::Char('c') => mode::export::Message::ToggleSource,
+                KeyCode::Char('t') => mode::export::Message::Export(transcript::Format::Text),
+                KeyCode::Char('m') => mode::export::Message::Export(transcript::Format::Markdown),
+                KeyCode::Char('j') => mode::export::Message::Export(transcript::Format::JsonLines),
                 _ => return None,
             }),
         },
@@ -0,0 +1,121 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use matrix_sdk::ruma::exports::serde_json;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// The step, in percent, used to grow or shrink the room preview.
+const ROOM_PREVIEW_STEP: u16 = 5;
+
+/// The bounds, in percent, of the room preview width.
+const ROOM_PREVIEW_MIN_PERCENTAGE: u16 = 20;
+const ROOM_PREVIEW_MAX_PERCENTAGE: u16 = 80;
+
+/// The layout preferences, persisted across sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The width of the room preview, in percent of the room list popup.
+    pub room_preview_percentage: u16,
+
+    /// Whether the room preview has been collapsed by the user.
+    pub room_preview_is_collapsed: bool,
+
+    /// The minimum width of the room list popup, in columns, to show the room
+    /// preview.
+    pub room_preview_min_width: u16,
+
+    /// How many lines each room of the room list takes.
+    pub room_list_density: RoomListDensity,
+}
+
+/// The density of the room list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoomListDensity {
+    /// One line per room: its name and its number of unread messages.
+    Compact,
+
+    /// Two lines per room: its name and the time of its latest event, then a
+    /// preview of the latest event.
+    #[default]
+    Comfortable,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            room_preview_percentage: 50,
+            room_preview_is_collapsed: false,
+            room_preview_min_width: 80,
+            room_list_density: RoomListDensity::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the layout from `path`, or fall back to the default layout.
+    pub fn load(path: &Path) -> Self {
+        let Ok(serialized) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        serde_json::from_str(&serialized).unwrap_or_else(|error| {
+            warn!("Failed to parse the layout from `{}`: {error}", path.display());
+
+            Self::default()
+        })
+    }
+
+    /// Save the layout into `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Return the path of the layout file inside the session directory.
+    pub fn path(session_path: &Path) -> PathBuf {
+        session_path.join("layout.json")
+    }
+
+    /// Whether the room preview must be shown in an area of width `width`.
+    pub fn room_preview_is_visible(&self, width: u16) -> bool {
+        !self.room_preview_is_collapsed && width >= self.room_preview_min_width
+    }
+
+    /// Return the width, in percent, of the room list and of the room preview.
+    pub fn room_list_split(&self) -> (u16, u16) {
+        let preview = self
+            .room_preview_percentage
+            .clamp(ROOM_PREVIEW_MIN_PERCENTAGE, ROOM_PREVIEW_MAX_PERCENTAGE);
+
+        (100 - preview, preview)
+    }
+
+    pub fn grow_room_preview(&mut self) {
+        self.room_preview_is_collapsed = false;
+        self.room_preview_percentage =
+            (self.room_preview_percentage + ROOM_PREVIEW_STEP).min(ROOM_PREVIEW_MAX_PERCENTAGE);
+    }
+
+    pub fn shrink_room_preview(&mut self) {
+        self.room_preview_is_collapsed = false;
+        self.room_preview_percentage = self
+            .room_preview_percentage
+            .saturating_sub(ROOM_PREVIEW_STEP)
+            .max(ROOM_PREVIEW_MIN_PERCENTAGE);
+    }
+
+    pub fn toggle_room_preview(&mut self) {
+        self.room_preview_is_collapsed = !self.room_preview_is_collapsed;
+    }
+
+    pub fn toggle_room_list_density(&mut self) {
+        self.room_list_density = match self.room_list_density {
+            RoomListDensity::Compact => RoomListDensity::Comfortable,
+            RoomListDensity::Comfortable => RoomListDensity::Compact,
+        };
+    }
+}
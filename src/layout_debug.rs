@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::block::block_with_title;
+
+/// The areas computed during the current frame.
+static AREAS: Mutex<Vec<(&'static str, Rect)>> = Mutex::new(Vec::new());
+
+const STYLE: Style = Style::new().fg(Color::Magenta);
+
+/// Register an area computed by a `Layout`, so that it can be drawn by the
+/// overlay.
+pub fn register(name: &'static str, area: Rect) {
+    AREAS.lock().unwrap().push((name, area));
+}
+
+/// Take all the areas registered since the last call.
+fn take() -> Vec<(&'static str, Rect)> {
+    std::mem::take(&mut *AREAS.lock().unwrap())
+}
+
+/// Forget the registered areas, without drawing them.
+pub fn clear() {
+    take();
+}
+
+/// Draw the bounds and the label of all the registered areas, plus a panel
+/// listing their computed `Rect`.
+pub fn render(area: Rect, buffer: &mut Buffer) {
+    let areas = take();
+
+    for (name, rect) in &areas {
+        Block::new()
+            .borders(Borders::ALL)
+            .border_style(STYLE)
+            .title(Line::styled(format!("{name} {}", format_rect(rect)), STYLE))
+            .render(*rect, buffer);
+    }
+
+    let [_, panel_area] =
+        Layout::vertical([Constraint::Percentage(100), Constraint::Min(areas.len() as u16 + 2)])
+            .areas(area);
+    let [panel_area, _] =
+        Layout::horizontal([Constraint::Min(40), Constraint::Percentage(100)]).areas(panel_area);
+
+    Clear.render(panel_area, buffer);
+
+    Paragraph::new(
+        areas
+            .iter()
+            .map(|(name, rect)| Line::from(format!("{name:<16} {}", format_rect(rect))))
+            .collect::<Vec<_>>(),
+    )
+    .block(block_with_title("Layout"))
+    .render(panel_area, buffer);
+}
+
+fn format_rect(rect: &Rect) -> String {
+    format!("{}×{}+{}+{}", rect.width, rect.height, rect.x, rect.y)
+}
@@ -0,0 +1,447 @@
+use std::path::PathBuf;
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use matrix_sdk::Client;
+use matrix_sdk_ui::{room_list_service::RoomListLoadingState, sync_service::SyncService};
+use ratatui::{
+    DefaultTerminal,
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+use tokio::{
+    select, spawn,
+    sync::mpsc::{Sender, channel},
+    time::interval,
+};
+
+use crate::{
+    Error, TextArea,
+    block::block_with_title,
+    clipboard, config, discovery, layout_debug, profile,
+    task_ext::{AbortOnDrop, JoinHandleExt},
+    timeline::{SPINNER, SPINNER_INTERVAL},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Homeserver,
+    Username,
+    Password,
+}
+
+impl Field {
+    fn next(self) -> Self {
+        match self {
+            Self::Homeserver => Self::Username,
+            Self::Username => Self::Password,
+            Self::Password => Self::Homeserver,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Homeserver => Self::Password,
+            Self::Username => Self::Homeserver,
+            Self::Password => Self::Username,
+        }
+    }
+}
+
+/// The steps of a login, once submitted.
+enum Progress {
+    Connecting,
+    LoggingIn,
+    /// Waiting for the login in the browser, at this URL.
+    Sso(String),
+    Syncing,
+}
+
+/// An update from the login task.
+enum Update {
+    Progress(Progress),
+    Failed(String),
+    /// Logged in, with the session saved in the session path.
+    LoggedIn(Client, PathBuf),
+}
+
+/// The login screen, shown when there is no session to restore.
+struct Model {
+    homeserver_textarea: TextArea,
+    username_textarea: TextArea,
+    password_textarea: TextArea,
+    focus: Field,
+    /// The field that failed the validation, if any.
+    invalid_field: Option<Field>,
+    error: Option<String>,
+    progress: Option<Progress>,
+    /// The number of spinner frames since the login has been submitted.
+    spinner_frame: usize,
+    login_task: Option<AbortOnDrop<()>>,
+}
+
+/// Log in from the TUI: ask for the homeserver and the credentials, log in,
+/// save the session, and wait for the first sync. `None` if the user has quit.
+pub async fn run(
+    terminal: &mut DefaultTerminal,
+    config: &config::Config,
+) -> Result<Option<(Client, PathBuf)>, Error> {
+    let mut model = Model::new(&config.server_name);
+    let (update_sender, mut update_receiver) = channel(16);
+    let mut event_reader = EventStream::new();
+    let mut spinner = interval(SPINNER_INTERVAL);
+
+    loop {
+        terminal.draw(|frame| model.render(frame.area(), frame.buffer_mut()))?;
+
+        select! {
+            Some(Ok(event)) = event_reader.next() => match event {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    if model.handle_key_event(key_event, config, &update_sender) {
+                        return Ok(None);
+                    }
+                }
+                Event::Paste(text) => model.paste(&text),
+                _ => {}
+            },
+            Some(update) = update_receiver.recv() => match update {
+                // Unless the login has been cancelled meanwhile.
+                Update::Progress(progress) if model.login_task.is_some() => {
+                    model.progress = Some(progress);
+                }
+                Update::Progress(_) => {}
+                Update::Failed(error) => {
+                    model.progress = None;
+                    model.login_task = None;
+                    model.error = Some(error);
+                }
+                Update::LoggedIn(client, session_path) => {
+                    return Ok(Some((client, session_path)));
+                }
+            },
+            _ = spinner.tick(), if model.progress.is_some() => {
+                model.spinner_frame += 1;
+            }
+        }
+    }
+}
+
+impl Model {
+    fn new(server_name: &str) -> Self {
+        let mut homeserver_textarea = TextArea::new_with_border();
+        homeserver_textarea.set_input(server_name);
+
+        let mut model = Self {
+            homeserver_textarea,
+            username_textarea: TextArea::new_with_border(),
+            password_textarea: TextArea::new_masked(),
+            focus: Field::Homeserver,
+            invalid_field: None,
+            error: None,
+            progress: None,
+            spinner_frame: 0,
+            login_task: None,
+        };
+        model.focus(Field::Username);
+
+        model
+    }
+
+    fn focus(&mut self, field: Field) {
+        self.focus = field;
+        self.homeserver_textarea.set_focus(field == Field::Homeserver);
+        self.username_textarea.set_focus(field == Field::Username);
+        self.password_textarea.set_focus(field == Field::Password);
+    }
+
+    fn focused_textarea(&mut self) -> &mut TextArea {
+        match self.focus {
+            Field::Homeserver => &mut self.homeserver_textarea,
+            Field::Username => &mut self.username_textarea,
+            Field::Password => &mut self.password_textarea,
+        }
+    }
+
+    /// Handle `key_event`, and return whether the user has quit.
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        config: &config::Config,
+        update_sender: &Sender<Update>,
+    ) -> bool {
+        let is_ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key_event.code {
+            // Cancel the login, to edit the fields again.
+            KeyCode::Esc if self.login_task.is_some() => {
+                self.login_task = None;
+                self.progress = None;
+            }
+            KeyCode::Esc => return true,
+            _ if self.login_task.is_some() => {}
+            KeyCode::Tab | KeyCode::Down => self.focus(self.focus.next()),
+            KeyCode::BackTab | KeyCode::Up => self.focus(self.focus.previous()),
+            KeyCode::Enter => self.submit(true, config, update_sender),
+            KeyCode::Char('o') if is_ctrl => self.submit(false, config, update_sender),
+            _ => {
+                self.focused_textarea().handle_input(key_event);
+            }
+        }
+
+        false
+    }
+
+    fn paste(&mut self, text: &str) {
+        if self.login_task.is_none() {
+            // The fields are single lines.
+            self.focused_textarea().paste(text.trim());
+        }
+    }
+
+    /// Validate the fields, and start the login, with the password or else
+    /// with SSO.
+    fn submit(
+        &mut self,
+        with_password: bool,
+        config: &config::Config,
+        update_sender: &Sender<Update>,
+    ) {
+        let homeserver = self.homeserver_textarea.input().trim().to_owned();
+        let username = self.username_textarea.input().trim().to_owned();
+        let password = self.password_textarea.input();
+
+        let invalid = if homeserver.is_empty() {
+            Some((Field::Homeserver, "the homeserver is required"))
+        } else if with_password && username.is_empty() {
+            Some((Field::Username, "the username is required, or press <Ctrl-o> for SSO"))
+        } else if with_password && password.is_empty() {
+            Some((Field::Password, "the password is required"))
+        } else {
+            None
+        };
+
+        if let Some((field, error)) = invalid {
+            self.invalid_field = Some(field);
+            self.error = Some(error.to_owned());
+            self.focus(field);
+
+            return;
+        }
+
+        self.invalid_field = None;
+        self.error = None;
+        self.progress = Some(Progress::Connecting);
+        self.spinner_frame = 0;
+        self.login_task = Some(
+            spawn(login_task(
+                config.clone(),
+                homeserver,
+                with_password.then_some((username, password)),
+                update_sender.clone(),
+            ))
+            .abort_on_drop(),
+        );
+    }
+
+    fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(20)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("login", area);
+
+        let block = block_with_title("Welcome to multiverse");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [
+            explanation_area,
+            homeserver_label_area,
+            homeserver_area,
+            username_label_area,
+            username_area,
+            password_label_area,
+            password_area,
+            status_area,
+            hints_area,
+        ] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        Paragraph::new("Log in to a homeserver. The session is saved, so that it's restored on the next start.")
+            .wrap(Wrap { trim: true })
+            .dark_gray()
+            .render(explanation_area, buffer);
+
+        for (field, label, label_area, textarea, textarea_area) in [
+            (
+                Field::Homeserver,
+                "Homeserver",
+                homeserver_label_area,
+                &self.homeserver_textarea,
+                homeserver_area,
+            ),
+            (
+                Field::Username,
+                "Username",
+                username_label_area,
+                &self.username_textarea,
+                username_area,
+            ),
+            (
+                Field::Password,
+                "Password",
+                password_label_area,
+                &self.password_textarea,
+                password_area,
+            ),
+        ] {
+            let label = if self.invalid_field == Some(field) {
+                Line::from(label).red().bold()
+            } else if self.focus == field {
+                Line::from(label).bold()
+            } else {
+                Line::from(label)
+            };
+
+            label.render(label_area, buffer);
+            textarea.render(textarea_area, buffer);
+        }
+
+        let status = match (&self.progress, &self.error) {
+            (Some(progress), _) => {
+                let spinner = SPINNER[self.spinner_frame % SPINNER.len()];
+                let step = match progress {
+                    Progress::Connecting => "connecting to the homeserver…".to_owned(),
+                    Progress::LoggingIn => "logging in…".to_owned(),
+                    Progress::Sso(url) => {
+                        format!("log in in the browser, the URL is in the clipboard: {url}")
+                    }
+                    Progress::Syncing => "syncing for the first time…".to_owned(),
+                };
+
+                Paragraph::new(format!("{spinner} {step}")).yellow()
+            }
+            (None, Some(error)) => Paragraph::new(error.as_str()).red(),
+            (None, None) => Paragraph::new(""),
+        };
+
+        status.wrap(Wrap { trim: true }).render(status_area, buffer);
+
+        let hints = if self.login_task.is_some() {
+            "<Esc> cancel"
+        } else {
+            "<Tab> next field · <Enter> log in · <Ctrl-o> SSO · <Esc> quit"
+        };
+
+        Line::styled(hints, Style::default().dark_gray()).centered().render(hints_area, buffer);
+    }
+}
+
+async fn login_task(
+    config: config::Config,
+    homeserver: String,
+    credentials: Option<(String, String)>,
+    update_sender: Sender<Update>,
+) {
+    let update = match login(&config, &homeserver, credentials, &update_sender).await {
+        Ok((client, session_path)) => Update::LoggedIn(client, session_path),
+        Err(error) => Update::Failed(error),
+    };
+
+    let _ = update_sender.send(update).await;
+}
+
+async fn login(
+    config: &config::Config,
+    homeserver: &str,
+    credentials: Option<(String, String)>,
+    update_sender: &Sender<Update>,
+) -> Result<(Client, PathBuf), String> {
+    // The homeserver may have been changed on the login screen: the session
+    // goes in its profile, unless the session path has been given.
+    let session_path = if config.has_explicit_session_path {
+        config.session_path.clone()
+    } else {
+        profile::session_path(config.profile.clone(), homeserver)?
+    };
+    let config = &config::Config { session_path, ..config.clone() };
+
+    let client = match crate::client(config, homeserver).await {
+        Ok(client) => client,
+        Err(Error::ClientError(error)) => {
+            return Err(discovery::explain_build_error(homeserver, &error));
+        }
+        Err(error) => return Err(error.to_string()),
+    };
+
+    let _ = update_sender.send(Update::Progress(Progress::LoggingIn)).await;
+
+    let matrix_auth = client.matrix_auth();
+    let login = match credentials {
+        Some((username, password)) => {
+            matrix_auth.login_username(&username, &password).request_refresh_token().await
+        }
+        None => {
+            let update_sender = update_sender.clone();
+
+            matrix_auth
+                .login_sso(|url| async move {
+                    let _ = clipboard::copy(&url);
+                    let _ = update_sender.send(Update::Progress(Progress::Sso(url))).await;
+
+                    Ok(())
+                })
+                .request_refresh_token()
+                .await
+        }
+    };
+
+    login.map_err(|error| format!("failed to log in: {error}"))?;
+
+    crate::save_session(&client, &config.session_path)
+        .map_err(|error| format!("failed to save the session: {error}"))?;
+
+    let _ = update_sender.send(Update::Progress(Progress::Syncing)).await;
+
+    wait_for_first_sync(&client).await.map_err(|error| format!("failed to sync: {error}"))?;
+
+    Ok((client, config.session_path.clone()))
+}
+
+/// Sync until the room list is loaded, so that the app starts with the rooms
+/// from the store.
+async fn wait_for_first_sync(client: &Client) -> Result<(), String> {
+    client.event_cache().subscribe().map_err(|error| error.to_string())?;
+
+    let sync_service =
+        SyncService::builder(client.clone()).build().await.map_err(|error| error.to_string())?;
+    sync_service.start().await;
+
+    let all_rooms =
+        sync_service.room_list_service().all_rooms().await.map_err(|error| error.to_string())?;
+    let mut loading_state = all_rooms.loading_state();
+
+    while !matches!(loading_state.get(), RoomListLoadingState::Loaded { .. }) {
+        if loading_state.next().await.is_none() {
+            break;
+        }
+    }
+
+    sync_service.stop().await;
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Local};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+/// Maximum number of logs kept in memory.
+const CAPACITY: usize = 10_000;
+
+static LOGS: Mutex<VecDeque<Log>> = Mutex::new(VecDeque::new());
+
+/// A captured log.
+#[derive(Debug, Clone)]
+pub struct Log {
+    pub timestamp: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{timestamp} {level:<5} {target}: {message}",
+            timestamp = self.timestamp.format("%H:%M:%S%.3f"),
+            level = self.level,
+            target = self.target,
+            message = self.message,
+        )
+    }
+}
+
+/// A `tracing` layer keeping the last logs in memory.
+///
+/// `tui_logger` has its own buffer, but it is private. This one can be
+/// searched or exported.
+pub struct BufferLayer;
+
+impl<S> Layer<S> for BufferLayer
+where
+    S: Subscriber,
+{
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+
+        let log = Log {
+            timestamp: Local::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_owned(),
+            message: visitor.0,
+        };
+
+        let mut logs = LOGS.lock().unwrap();
+
+        if logs.len() == CAPACITY {
+            logs.pop_front();
+        }
+
+        logs.push_back(log);
+    }
+}
+
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {name}={value:?}", name = field.name());
+        }
+    }
+}
+
+/// Run `f` over all the captured logs, from the oldest to the newest.
+pub fn with_logs<F, T>(f: F) -> T
+where
+    F: FnOnce(&VecDeque<Log>) -> T,
+{
+    f(&LOGS.lock().unwrap())
+}
@@ -1,29 +1,98 @@
 mod app;
+mod avatar;
+mod bandwidth;
 mod bin;
 mod block;
+mod bot;
+mod bug_report;
+mod bus;
+mod clipboard;
+mod completion;
+mod config;
+mod crypto_store;
+mod debug_panel;
+mod diff_log;
+mod discovery;
+mod divider;
+mod inbox;
 mod input;
+mod layout;
+mod layout_debug;
+mod login;
+mod logs;
 mod mode;
+mod network;
+mod performance;
+mod profile;
+mod rate_limit;
 mod room;
+mod screenshot;
 mod scrollbar;
+mod secret_prompt;
+mod slash_command;
+mod spell_check;
+mod status;
+mod summary;
+mod tail;
 mod task_ext;
 mod textarea;
+mod time;
 mod timeline;
+mod toast;
+mod transcript;
+mod url_preview;
 
-use std::io::{self, Write};
+use std::{
+    fs,
+    io::{self, Write},
+    panic,
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
 
+use crossterm::{
+    event::{
+        DisableBracketedPaste, EnableBracketedPaste, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::supports_keyboard_enhancement,
+};
 use matrix_sdk::{
     AuthSession, Client, ClientBuildError, SqliteCryptoStore, SqliteEventCacheStore,
     SqliteStateStore,
     authentication::matrix::MatrixSession,
+    config::RequestConfig,
     encryption::{BackupDownloadStrategy, EncryptionSettings},
     ruma::exports::serde_json,
     store::StoreConfig,
 };
+use matrix_sdk_crypto::store::{IntoCryptoStore, MemoryStore};
 use matrix_sdk_sqlite::OpenStoreError;
 use textarea::TextArea;
+use tracing::{error, warn};
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+
+/// The files and the directories of a session, relative to the session path.
+const SESSION_FILE: &str = "session.json";
+const CRYPTO_STORE_DIRECTORY: &str = "crypto";
+const STATE_STORE_DIRECTORY: &str = "state";
+const EVENT_CACHE_STORE_DIRECTORY: &str = "cache";
+const AVATAR_CACHE_DIRECTORY: &str = "avatars";
+/// Kept when the session is removed, to not lose the reports about it.
+const BUG_REPORTS_DIRECTORY: &str = "bug-reports";
+
+/// Whether the session is restored from, and saved into, the session file.
+static SESSION_IS_PERSISTENT: AtomicBool = AtomicBool::new(true);
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
     #[error(transparent)]
     Io(#[from] io::Error),
 
@@ -41,60 +110,209 @@ enum Error {
 
     #[error(transparent)]
     MatrixSyncService(#[from] matrix_sdk_ui::sync_service::Error),
+
+    #[error(transparent)]
+    Timeline(#[from] matrix_sdk_ui::timeline::Error),
+
+    #[error(transparent)]
+    LogDirectives(#[from] tracing_subscriber::filter::ParseError),
+
+    #[error("unexpected diff log: {0}")]
+    DiffLog(String),
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    logger();
+    let config = config::Config::load().map_err(Error::Config)?;
+    let _log_guard = logger(&config)?;
+    config.apply();
 
-    let options = argh::from_env();
-    let client = client(&options).await?;
-    let client = session(client, &options).await?;
+    // A diff log is replayed without any client.
+    if let Some(path) = &config.replay_diff_log {
+        return diff_log::run(path);
+    }
 
-    let event_cache = client.event_cache();
-    event_cache.subscribe().unwrap();
+    if config.fresh {
+        remove_session(&config.session_path)?;
+    }
+
+    match config.tail_room.clone() {
+        // Without the TUI, the login is prompted on the standard input.
+        Some(room_id) => {
+            let client = session(client_or_exit(&config).await?, &config).await?;
+            client.event_cache().subscribe().unwrap();
+
+            tail::run(client, room_id).await?
+        }
+        // Without a session to restore, the login happens in the TUI.
+        None => {
+            let client = match saved_session(&config)? {
+                Some(session) => {
+                    let client = client_or_exit(&config).await?;
+                    client.restore_session(session).await?;
+
+                    Some(client)
+                }
+                None => None,
+            };
 
-    app(client).await?;
+            app(client, &config).await?
+        }
+    }
 
     Ok(())
 }
 
-fn logger() {
-    use tracing_subscriber::prelude::*;
+fn logger(config: &config::Config) -> Result<Option<WorkerGuard>, Error> {
+    use tracing_subscriber::{EnvFilter, prelude::*};
+
+    let filter = EnvFilter::try_new(&config.log)?;
+
+    // Logs are always captured by `tui_logger`. If a log directory is given,
+    // they are also written to files, so that they survive a crash or a long
+    // session.
+    let (file_layer, guard) = match &config.log_dir {
+        Some(log_dir) => {
+            let (writer, guard) =
+                tracing_appender::non_blocking(rolling::daily(log_dir, "multiverse.log"));
 
-    tracing_subscriber::registry().with(tui_logger::TuiTracingSubscriberLayer).init();
+            (
+                Some(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(writer)),
+                Some(guard),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tui_logger::TuiTracingSubscriberLayer)
+        .with(logs::BufferLayer)
+        .with(file_layer)
+        .init();
     tui_logger::init_logger(tui_logger::LevelFilter::Trace).unwrap();
+
+    Ok(guard)
 }
 
-async fn client(options: &bin::Options) -> Result<Client, Error> {
-    let bin::Options { server_name, session_path } = options;
-
-    let client_builder = Client::builder()
-        .store_config(
-            StoreConfig::new("multiverse".to_owned())
-                .crypto_store(SqliteCryptoStore::open(session_path.join("crypto"), None).await?)
-                .state_store(SqliteStateStore::open(session_path.join("state"), None).await?)
-                .event_cache_store(
-                    SqliteEventCacheStore::open(session_path.join("cache"), None).await?,
-                ),
-        )
-        .server_name_or_homeserver_url(&server_name)
+/// Build the client for the configured homeserver, or explain why it can't be
+/// and exit.
+async fn client_or_exit(config: &config::Config) -> Result<Client, Error> {
+    match client(config, &config.server_name).await {
+        Ok(client) => Ok(client),
+        Err(Error::ClientError(error)) => {
+            eprintln!("{}", discovery::explain_build_error(&config.server_name, &error));
+
+            process::exit(1);
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Build the client for `server_name`, with the stores and the network options
+/// of `config`.
+async fn client(config: &config::Config, server_name: &str) -> Result<Client, Error> {
+    let config::Config {
+        session_path,
+        store,
+        proxy,
+        simulate_network,
+        low_bandwidth,
+        user_agent,
+        request_timeout,
+        insecure,
+        ..
+    } = config;
+
+    // The crypto store is always explicit, to be inspected by the crypto debug
+    // panel.
+    let crypto_store = match store {
+        bin::Store::Sqlite => {
+            SqliteCryptoStore::open(session_path.join(CRYPTO_STORE_DIRECTORY), None)
+                .await?
+                .into_crypto_store()
+        }
+        bin::Store::Memory => MemoryStore::new().into_crypto_store(),
+    };
+    crypto_store::set(crypto_store.clone());
+
+    // Without any explicit store, the other stores are in memory.
+    let store_config = StoreConfig::new("multiverse".to_owned()).crypto_store(crypto_store);
+    let store_config = match store {
+        bin::Store::Sqlite => store_config
+            .state_store(
+                SqliteStateStore::open(session_path.join(STATE_STORE_DIRECTORY), None).await?,
+            )
+            .event_cache_store(
+                SqliteEventCacheStore::open(session_path.join(EVENT_CACHE_STORE_DIRECTORY), None)
+                    .await?,
+            ),
+        bin::Store::Memory => store_config,
+    };
+
+    let mut client_builder = Client::builder()
+        .store_config(store_config)
+        .server_name_or_homeserver_url(server_name)
+        .handle_refresh_tokens()
         .with_encryption_settings(EncryptionSettings {
             auto_enable_cross_signing: true,
             backup_download_strategy: BackupDownloadStrategy::AfterDecryptionFailure,
             auto_enable_backups: true,
         });
 
+    if let Some(proxy) = proxy {
+        client_builder = client_builder.proxy(proxy);
+    }
+
+    if *simulate_network {
+        client_builder = client_builder.proxy(network::start_proxy().await?);
+    }
+
+    if let Some(user_agent) = user_agent {
+        client_builder = client_builder.user_agent(user_agent);
+    }
+
+    // The sync long-polls are not configurable, but their requests must not
+    // time out on a slow connection.
+    let request_timeout =
+        request_timeout.or(low_bandwidth.then_some(bandwidth::LOW_BANDWIDTH_REQUEST_TIMEOUT));
+
+    if let Some(request_timeout) = request_timeout {
+        client_builder = client_builder
+            .request_config(RequestConfig::new().timeout(Duration::from_secs(request_timeout)));
+    }
+
+    if *insecure {
+        warn!("TLS certificates are not verified");
+
+        client_builder = client_builder.disable_ssl_verification();
+    }
+
     Ok(client_builder.build().await?)
 }
 
-async fn session(client: Client, options: &bin::Options) -> Result<Client, Error> {
-    let session_path = options.session_path.join("session.json");
+/// The session of the session file, if there is one to restore.
+fn saved_session(config: &config::Config) -> Result<Option<MatrixSession>, Error> {
+    // A session can't be restored without the crypto store it was created with.
+    SESSION_IS_PERSISTENT.store(config.store == bin::Store::Sqlite, Ordering::Relaxed);
+
+    if !SESSION_IS_PERSISTENT.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
 
-    if let Ok(serialized) = std::fs::read_to_string(&session_path) {
-        let session: MatrixSession = serde_json::from_str(&serialized)?;
+    match fs::read_to_string(config.session_path.join(SESSION_FILE)) {
+        Ok(serialized) => Ok(Some(serde_json::from_str(&serialized)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Restore the session, or else log in with prompts on the standard input.
+async fn session(client: Client, config: &config::Config) -> Result<Client, Error> {
+    if let Some(session) = saved_session(config)? {
         client.restore_session(session).await?;
     } else {
+        discovery::print_report(&client, &config.server_name).await;
+
         println!("Logging in with username and password…");
 
         loop {
@@ -106,7 +324,12 @@ async fn session(client: Client, options: &bin::Options) -> Result<Client, Error
 
             let password = rpassword::prompt_password("Password: ")?;
 
-            match client.matrix_auth().login_username(&username, password.trim()).await {
+            match client
+                .matrix_auth()
+                .login_username(&username, password.trim())
+                .request_refresh_token()
+                .await
+            {
                 Ok(_) => {
                     println!("Logged in as {username}");
                     break;
@@ -119,10 +342,8 @@ async fn session(client: Client, options: &bin::Options) -> Result<Client, Error
         }
 
         // Immediately save the session to disk.
-        if let Some(session) = client.session() {
-            let AuthSession::Matrix(session) = session else { panic!("unexpected oidc session") };
-            let serialized = serde_json::to_string(&session)?;
-            std::fs::write(session_path, serialized)?;
+        if SESSION_IS_PERSISTENT.load(Ordering::Relaxed) {
+            save_session(&client, &config.session_path)?;
 
             println!("Session saved");
         }
@@ -131,10 +352,116 @@ async fn session(client: Client, options: &bin::Options) -> Result<Client, Error
     Ok(client)
 }
 
-async fn app(client: Client) -> Result<(), Error> {
+/// Save the session of `client` into the session file, e.g. after a login or
+/// when its tokens have been refreshed. Nothing is saved if the stores are in
+/// memory.
+fn save_session(client: &Client, session_path: &Path) -> Result<(), Error> {
+    if !SESSION_IS_PERSISTENT.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    if let Some(session) = client.session() {
+        let AuthSession::Matrix(session) = session else { panic!("unexpected oidc session") };
+        let serialized = serde_json::to_string(&session)?;
+        fs::write(session_path.join(SESSION_FILE), serialized)?;
+    }
+
+    Ok(())
+}
+
+/// Remove the session file and the stores, so that the next start logs in
+/// again.
+fn remove_session(session_path: &Path) -> io::Result<()> {
+    match fs::remove_file(session_path.join(SESSION_FILE)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        _ => {}
+    }
+
+    for directory in [
+        CRYPTO_STORE_DIRECTORY,
+        STATE_STORE_DIRECTORY,
+        EVENT_CACHE_STORE_DIRECTORY,
+        AVATAR_CACHE_DIRECTORY,
+    ] {
+        match fs::remove_dir_all(session_path.join(directory)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the TUI, after logging in if there is no `client` yet.
+async fn app(client: Option<Client>, config: &config::Config) -> Result<(), Error> {
+    let deep_link = config.room.clone().map(|room_id| (room_id, config.event.clone()));
+
     let mut terminal = ratatui::init();
-    let app_result = app::App::new(client).await?.run(&mut terminal).await;
+    // The terminal keeps working without it, with one key press per pasted
+    // character.
+    let _ = execute!(io::stdout(), EnableBracketedPaste);
+    // The modifiers of some keys, e.g. <Ctrl-Tab>, are reported only with the
+    // keyboard enhancement. <Alt-1> to <Alt-9> switch the tabs without it.
+    let keyboard_is_enhanced = supports_keyboard_enhancement().unwrap_or(false)
+        && execute!(
+            io::stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )
+        .is_ok();
+
+    // The app sets it again with its session path, which differs when logging
+    // in to another homeserver.
+    bug_report::init(config.session_path.join(BUG_REPORTS_DIRECTORY));
+
+    // Write a bug report on panic, once the terminal has been restored by the
+    // hook installed by `ratatui`. The panics of the spawned tasks, which run on
+    // the other threads, are caught by `tokio`, and reported by their supervisor
+    // if any: the app keeps running, so the terminal must be kept as is.
+    let app_thread_id = thread::current().id();
+    let previous_panic_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        if thread::current().id() != app_thread_id {
+            error!("A task has panicked: {panic_info}");
+
+            return;
+        }
+
+        if keyboard_is_enhanced {
+            let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+        }
+
+        let _ = execute!(io::stdout(), DisableBracketedPaste);
+        previous_panic_hook(panic_info);
+
+        let report =
+            bug_report::Report { panic: Some(panic_info.to_string()), ..Default::default() };
+
+        match bug_report::write(&report) {
+            Ok(path) => eprintln!("A bug report has been written to `{}`", path.display()),
+            Err(error) => eprintln!("Failed to write the bug report: {error}"),
+        }
+    }));
+
+    let app_result = async {
+        let (client, session_path) = match client {
+            Some(client) => (client, config.session_path.clone()),
+            // The user has quit without logging in.
+            None => match login::run(&mut terminal, config).await? {
+                Some(logged_in) => logged_in,
+                None => return Ok(()),
+            },
+        };
+        client.event_cache().subscribe().unwrap();
+
+        app::App::new(client, session_path, deep_link).await?.run(&mut terminal).await
+    }
+    .await;
+
+    if keyboard_is_enhanced {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
 
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
     ratatui::restore();
 
     app_result
@@ -0,0 +1,202 @@
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Client,
+    ruma::api::client::uiaa::{AuthData, Password, UserIdentifier},
+};
+use ratatui::{buffer::Buffer, layout::Rect};
+use tracing::info;
+
+use crate::{app, secret_prompt::SecretPrompt};
+
+pub enum Message {
+    UpdateInput(KeyEvent),
+    Submit,
+}
+
+/// What to do with the account.
+pub enum Action {
+    ChangePassword,
+    Deactivate {
+        /// Whether the content of the user must be erased as much as possible.
+        erase: bool,
+    },
+}
+
+/// What the password prompt is for.
+enum Step {
+    NewPassword,
+    /// The password of the user-interactive authentication.
+    Authenticate {
+        session: Option<String>,
+    },
+    Done,
+}
+
+/// Change the password, or deactivate the account, with the user-interactive
+/// authentication.
+pub struct Model {
+    client: Client,
+    action: Action,
+    step: Step,
+    new_password: Option<String>,
+    password_prompt: SecretPrompt,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(client: Client, action: Action) -> Self {
+        Self {
+            client,
+            action,
+            step: Step::Done,
+            new_password: None,
+            password_prompt: SecretPrompt::new(),
+            notice: None,
+        }
+    }
+
+    /// Ask for the new password, or send the deactivation request to know
+    /// which authentication the homeserver wants, if any.
+    pub async fn start(&mut self) -> Option<app::Message> {
+        match self.action {
+            Action::ChangePassword => {
+                self.ask_for_new_password();
+
+                None
+            }
+            Action::Deactivate { .. } => self.request(None).await,
+        }
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.password_prompt.is_open()
+    }
+
+    fn ask_for_new_password(&mut self) {
+        self.step = Step::NewPassword;
+        self.password_prompt.ask_new("the new password");
+    }
+
+    fn ask_for_current_password(&mut self, session: Option<String>) {
+        self.step = Step::Authenticate { session };
+        self.password_prompt.ask("your current password");
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateInput(key_event) => self.password_prompt.handle_input(key_event),
+            Message::Submit => {
+                let input = match self.password_prompt.submit() {
+                    Ok(Some(input)) => input,
+                    Ok(None) => return None,
+                    Err(notice) => {
+                        self.notice = Some(notice);
+
+                        return None;
+                    }
+                };
+                self.notice = None;
+
+                match std::mem::replace(&mut self.step, Step::Done) {
+                    Step::NewPassword => {
+                        self.new_password = Some(input);
+
+                        return self.request(None).await;
+                    }
+                    Step::Authenticate { session } => {
+                        let user_id = self.client.user_id()?.to_string();
+                        let mut password =
+                            Password::new(UserIdentifier::UserIdOrLocalpart(user_id), input);
+                        password.session = session;
+
+                        return self.request(Some(AuthData::Password(password))).await;
+                    }
+                    Step::Done => {}
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Send the request, and ask for the password if the homeserver wants the
+    /// user-interactive authentication, again if it was wrong.
+    async fn request(&mut self, auth_data: Option<AuthData>) -> Option<app::Message> {
+        let account = self.client.account();
+        let result = match &self.action {
+            Action::ChangePassword => account
+                .change_password(self.new_password.as_deref().unwrap_or_default(), auth_data)
+                .await
+                .map(|_| ()),
+            Action::Deactivate { erase } => {
+                account.deactivate(None, auth_data, *erase).await.map(|_| ())
+            }
+        };
+
+        match result {
+            Ok(()) => match self.action {
+                Action::ChangePassword => {
+                    self.notice = Some("password changed".to_owned());
+                    self.step = Step::Done;
+                }
+                Action::Deactivate { .. } => {
+                    info!("Account deactivated");
+
+                    // The session is useless now.
+                    return Some(app::Message::Logout);
+                }
+            },
+            Err(error) => match error.as_uiaa_response() {
+                Some(uiaa_info) => {
+                    self.notice = uiaa_info.auth_error.as_ref().map(|auth_error| {
+                        format!("failed to authenticate: {}", auth_error.message)
+                    });
+                    self.ask_for_current_password(uiaa_info.session.clone());
+                }
+                // E.g. the new password is too weak.
+                None => {
+                    self.notice = Some(match self.action {
+                        Action::ChangePassword => format!("failed to change the password: {error}"),
+                        Action::Deactivate { .. } => {
+                            format!("failed to deactivate the account: {error}")
+                        }
+                    });
+                    match self.action {
+                        Action::ChangePassword => self.ask_for_new_password(),
+                        Action::Deactivate { .. } => self.step = Step::Done,
+                    }
+                }
+            },
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let (title, explanation) = match self.action {
+            Action::ChangePassword => (
+                "Change the password",
+                "The other devices are logged out; this one stays logged in. The current \
+                 password may be asked to confirm it's you.",
+            ),
+            Action::Deactivate { erase: false } => (
+                "Deactivate the account",
+                "The account can't be used anymore, and can't be reactivated.",
+            ),
+            Action::Deactivate { erase: true } => (
+                "Deactivate the account",
+                "The account can't be used anymore, and its content is erased as much as \
+                 possible.",
+            ),
+        };
+
+        self.password_prompt.render_popup(
+            "account",
+            title,
+            explanation,
+            self.notice.as_deref(),
+            area,
+            buffer,
+        );
+    }
+}
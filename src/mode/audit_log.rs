@@ -0,0 +1,293 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use matrix_sdk::{
+    Room,
+    deserialized_responses::RawAnySyncOrStrippedState,
+    ruma::{
+        MilliSecondsSinceUnixEpoch, OwnedUserId, events::StateEventType, exports::serde_json::Value,
+    },
+};
+use matrix_sdk_ui::Timeline;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, layout_debug, room::display_name, time};
+
+pub enum Message {
+    ScrollUp,
+    ScrollDown,
+}
+
+/// A field of the content of a state event, as a dotted path, with its value
+/// before and after a change. `None` if the field is absent.
+type FieldChange = (String, Option<String>, Option<String>);
+
+/// A change of the state of the room, from a state event of the timeline.
+struct Change {
+    sender: OwnedUserId,
+    timestamp: MilliSecondsSinceUnixEpoch,
+    event_type: String,
+    state_key: String,
+    /// Whether the content before the change is known, from the
+    /// `prev_content` of the event or from a previous change.
+    has_before: bool,
+    fields: Vec<FieldChange>,
+}
+
+/// A state whose current value in the state store isn't the result of the
+/// last change of the timeline, e.g. because it has been changed in a gap.
+struct Drift {
+    event_type: String,
+    state_key: String,
+    fields: Vec<FieldChange>,
+}
+
+/// An audit log of the state changes of a room, for the moderators.
+pub struct Model {
+    name: String,
+    changes: Vec<Change>,
+    drifts: Vec<Drift>,
+    scroll_position: u16,
+}
+
+impl Model {
+    /// Collect the state changes of the loaded timeline of `timeline`, and
+    /// check them against the state store.
+    pub async fn new(timeline: &Timeline) -> Self {
+        let room = timeline.room();
+        let changes = collect_changes(timeline).await;
+        let drifts = find_drifts(room, &changes).await;
+
+        Self { name: display_name(room), changes, drifts, scroll_position: 0 }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::ScrollUp => self.scroll_position = self.scroll_position.saturating_sub(1),
+            Message::ScrollDown => self.scroll_position = self.scroll_position.saturating_add(1),
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("audit log", area);
+
+        let block = block_with_title(&format!("State changes of {}", self.name));
+        let mut text = Text::default();
+
+        if self.changes.is_empty() {
+            text.push_line(
+                Line::from("no state change in the loaded timeline, paginate backwards")
+                    .dark_gray(),
+            );
+        }
+
+        for change in &self.changes {
+            let at =
+                change.timestamp.to_system_time().map_or_else(|| "???".to_owned(), time::format);
+            let mut header = vec![
+                Span::raw(format!("{at} ")).dark_gray(),
+                Span::raw(change.event_type.clone()).bold(),
+            ];
+
+            if !change.state_key.is_empty() {
+                header.push(Span::raw(format!(" {}", change.state_key)).bold());
+            }
+
+            header.push(Span::raw(format!(" by {}", change.sender)).dark_gray());
+            text.push_line(Line::from(header));
+
+            if !change.has_before {
+                text.push_line(Line::from("  the content before is unknown").dark_gray());
+            }
+
+            push_fields(&mut text, &change.fields);
+        }
+
+        if !self.drifts.is_empty() {
+            text.push_line("");
+            text.push_line(Line::from("Changed outside of the loaded timeline").bold().yellow());
+
+            for drift in &self.drifts {
+                text.push_line(Line::from(
+                    format!("{} {}", drift.event_type, drift.state_key).trim_end().to_owned(),
+                ));
+                push_fields(&mut text, &drift.fields);
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_position, 0))
+            .block(block)
+            .render(area, buffer);
+    }
+}
+
+/// Render the changed fields as a diff.
+fn push_fields(text: &mut Text<'_>, fields: &[FieldChange]) {
+    if fields.is_empty() {
+        text.push_line(Line::from("  no change of the content").dark_gray());
+    }
+
+    for (path, before, after) in fields {
+        if let Some(before) = before {
+            text.push_line(Line::from(format!("  - {path}: {before}")).red());
+        }
+
+        if let Some(after) = after {
+            text.push_line(Line::from(format!("  + {path}: {after}")).green());
+        }
+    }
+}
+
+/// The state changes of the loaded timeline, from the oldest to the most
+/// recent.
+async fn collect_changes(timeline: &Timeline) -> Vec<Change> {
+    // The last known content of each state, by event type and state key.
+    let mut contents = HashMap::<(String, String), Value>::new();
+    let mut changes = Vec::new();
+
+    for item in timeline.items().await {
+        let Some(event_item) = item.as_event() else {
+            continue;
+        };
+        let Some(event) =
+            event_item.original_json().and_then(|json| json.deserialize_as::<Value>().ok())
+        else {
+            continue;
+        };
+        // Only the state events have a state key.
+        let (Some(event_type), Some(state_key)) =
+            (event["type"].as_str(), event.get("state_key").and_then(Value::as_str))
+        else {
+            continue;
+        };
+
+        let key = (event_type.to_owned(), state_key.to_owned());
+        let after = event["content"].clone();
+        let before = match event["unsigned"].get("prev_content") {
+            Some(prev_content) => Some(prev_content.clone()),
+            None => contents.get(&key).cloned(),
+        };
+
+        changes.push(Change {
+            sender: event_item.sender().to_owned(),
+            timestamp: event_item.timestamp(),
+            event_type: key.0.clone(),
+            state_key: key.1.clone(),
+            has_before: before.is_some(),
+            fields: diff(before.as_ref(), &after),
+        });
+
+        contents.insert(key, after);
+    }
+
+    changes
+}
+
+/// Compare the content after the last change of each state with its current
+/// content in the state store.
+async fn find_drifts(room: &Room, changes: &[Change]) -> Vec<Drift> {
+    let mut last_changes = BTreeMap::<(&str, &str), &Change>::new();
+
+    for change in changes {
+        last_changes.insert((&change.event_type, &change.state_key), change);
+    }
+
+    let mut drifts = Vec::new();
+
+    for ((event_type, state_key), change) in last_changes {
+        let current = match room.get_state_event(StateEventType::from(event_type), state_key).await
+        {
+            Ok(Some(RawAnySyncOrStrippedState::Sync(raw))) => {
+                raw.deserialize_as::<Value>().ok().map(|event| event["content"].clone())
+            }
+            _ => None,
+        };
+        let Some(current) = current else {
+            continue;
+        };
+
+        // The content after the last change, rebuilt from its fields.
+        let fields = change
+            .fields
+            .iter()
+            .filter_map(|(path, _, after)| Some((path.clone(), after.clone()?)))
+            .collect::<BTreeMap<_, _>>();
+        let mut current_fields = BTreeMap::new();
+        flatten(String::new(), &current, &mut current_fields);
+
+        // Only the fields changed by the last change are known.
+        let drifted = fields
+            .iter()
+            .filter(|(path, after)| current_fields.get(*path) != Some(*after))
+            .map(|(path, after)| {
+                (path.clone(), Some(after.clone()), current_fields.get(path).cloned())
+            })
+            .collect::<Vec<_>>();
+
+        if !drifted.is_empty() {
+            drifts.push(Drift {
+                event_type: event_type.to_owned(),
+                state_key: state_key.to_owned(),
+                fields: drifted,
+            });
+        }
+    }
+
+    drifts
+}
+
+/// The fields whose values differ between `before` and `after`.
+fn diff(before: Option<&Value>, after: &Value) -> Vec<FieldChange> {
+    let mut before_fields = BTreeMap::new();
+    let mut after_fields = BTreeMap::new();
+
+    if let Some(before) = before {
+        flatten(String::new(), before, &mut before_fields);
+    }
+
+    flatten(String::new(), after, &mut after_fields);
+
+    let paths = before_fields.keys().chain(after_fields.keys()).cloned().collect::<BTreeSet<_>>();
+
+    paths
+        .into_iter()
+        .filter(|path| before_fields.get(path) != after_fields.get(path))
+        .map(|path| {
+            let before = before_fields.get(&path).cloned();
+            let after = after_fields.get(&path).cloned();
+
+            (path, before, after)
+        })
+        .collect()
+}
+
+/// Flatten the objects of `value` into dotted paths, e.g. `users.@alice:x`;
+/// the other values are kept as JSON.
+fn flatten(path: String, value: &Value, fields: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                let path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+
+                flatten(path, value, fields);
+            }
+        }
+        _ => {
+            fields.insert(path, value.to_string());
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::{path::PathBuf, str::FromStr};
+
+use crossterm::event::KeyEvent;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Widget},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug, mode, room};
+
+pub enum Message {
+    UpdateCommand(KeyEvent),
+    Execute,
+    Confirm,
+    Cancel,
+}
+
+/// A command typed in the command prompt, after `:`.
+enum Command {
+    Logout,
+    ReloadConfig,
+    BugReport { with_linked_chunk: bool },
+    ChangePassword,
+    Deactivate { erase: bool },
+    CheckEventCache,
+    ExportRoomKeys { path: Option<PathBuf> },
+    ImportRoomKeys { path: PathBuf },
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(command: &str) -> Result<Self, Self::Err> {
+        let words = command.split_whitespace().collect::<Vec<_>>();
+
+        match words.as_slice() {
+            ["logout"] => Ok(Self::Logout),
+            ["config", "reload"] => Ok(Self::ReloadConfig),
+            ["bug-report"] => Ok(Self::BugReport { with_linked_chunk: false }),
+            ["bug-report", "room"] => Ok(Self::BugReport { with_linked_chunk: true }),
+            ["password"] => Ok(Self::ChangePassword),
+            ["deactivate"] => Ok(Self::Deactivate { erase: false }),
+            ["deactivate", "erase"] => Ok(Self::Deactivate { erase: true }),
+            ["check", "event-cache"] => Ok(Self::CheckEventCache),
+            ["keys", "export"] => Ok(Self::ExportRoomKeys { path: None }),
+            ["keys", "export", path] => Ok(Self::ExportRoomKeys { path: Some(path.into()) }),
+            ["keys", "import", path] => Ok(Self::ImportRoomKeys { path: path.into() }),
+            [] => Err("no command".to_owned()),
+            _ => Err(format!("unknown command `{}`", words.join(" "))),
+        }
+    }
+}
+
+impl Command {
+    /// The question to ask before running the command, if it must be confirmed.
+    fn confirmation(&self) -> Option<&'static str> {
+        match self {
+            Self::Logout => Some("Log out, and delete the session and the stores?"),
+            Self::ReloadConfig
+            | Self::BugReport { with_linked_chunk: false }
+            | Self::ChangePassword
+            | Self::CheckEventCache
+            | Self::ExportRoomKeys { .. }
+            | Self::ImportRoomKeys { .. } => None,
+            Self::BugReport { with_linked_chunk: true } => Some(
+                "Include the linked chunk of the opened room, with its event IDs, senders and \
+                 types?",
+            ),
+            Self::Deactivate { erase: false } => {
+                Some("Deactivate the account, irreversibly? It can't be used to log in anymore.")
+            }
+            Self::Deactivate { erase: true } => Some(
+                "Deactivate the account, irreversibly, and erase its messages for the users \
+                 joining later?",
+            ),
+        }
+    }
+
+    fn run(self) -> app::Message {
+        match self {
+            Self::Logout => app::Message::Logout,
+            Self::ReloadConfig => app::Message::ReloadConfig,
+            Self::BugReport { with_linked_chunk } => app::Message::BugReport { with_linked_chunk },
+            Self::ChangePassword => {
+                app::Message::OpenAccount(mode::account::Action::ChangePassword)
+            }
+            Self::Deactivate { erase } => {
+                app::Message::OpenAccount(mode::account::Action::Deactivate { erase })
+            }
+            Self::CheckEventCache => app::Message::Room(room::Message::CheckEventCache),
+            Self::ExportRoomKeys { path } => {
+                app::Message::OpenRoomKeys(mode::room_keys::Action::Export { path })
+            }
+            Self::ImportRoomKeys { path } => {
+                app::Message::OpenRoomKeys(mode::room_keys::Action::Import { path })
+            }
+        }
+    }
+}
+
+/// Run `command`, as if it was typed in the command prompt. The prompt is
+/// opened if the command must be confirmed.
+pub fn run(command: &str) -> Result<app::Message, String> {
+    let command = command.parse::<Command>()?;
+
+    Ok(if command.confirmation().is_some() {
+        app::Message::Mode(app::Mode::Command(Model {
+            command_to_confirm: Some(command),
+            ..Model::new()
+        }))
+    } else {
+        command.run()
+    })
+}
+
+pub struct Model {
+    command_textarea: TextArea,
+    command_to_confirm: Option<Command>,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new() -> Self {
+        Self { command_textarea: TextArea::new(), command_to_confirm: None, notice: None }
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.command_to_confirm.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateCommand(key_event) => {
+                self.command_textarea.handle_input(key_event);
+                self.notice = None;
+            }
+            Message::Execute => match self.command_textarea.input().parse::<Command>() {
+                Ok(command) if command.confirmation().is_some() => {
+                    self.command_to_confirm = Some(command);
+                }
+                Ok(command) => return Some(command.run()),
+                Err(error) => {
+                    self.command_textarea.clear();
+                    self.notice = Some(error);
+                }
+            },
+            Message::Confirm => return self.command_to_confirm.take().map(Command::run),
+            Message::Cancel => return Some(app::Message::Mode(app::Mode::None)),
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [_, area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Length(3)]).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("command prompt", area);
+
+        let block = block_with_title("Command");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        if let Some(confirmation) =
+            self.command_to_confirm.as_ref().and_then(|command| command.confirmation())
+        {
+            Line::from(format!("{confirmation} y/n"))
+                .style(Style::default().yellow())
+                .render(inner_area, buffer);
+
+            return;
+        }
+
+        let [prompt_area, command_area] =
+            Layout::horizontal([Constraint::Length(1), Constraint::Percentage(100)])
+                .areas(inner_area);
+
+        Line::from(":").render(prompt_area, buffer);
+
+        match &self.notice {
+            Some(notice) => Line::from(notice.as_str())
+                .style(Style::default().red())
+                .render(command_area, buffer),
+            None => self.command_textarea.render(command_area, buffer),
+        }
+    }
+}
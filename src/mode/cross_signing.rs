@@ -0,0 +1,306 @@
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Client,
+    encryption::{CrossSigningResetAuthType, CrossSigningResetHandle, CrossSigningStatus},
+    ruma::api::client::uiaa::{AuthData, Password, UserIdentifier},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+use tokio::spawn;
+use tracing::{error, info};
+
+use crate::{
+    TextArea, app,
+    block::block_with_title,
+    layout_debug,
+    task_ext::{AbortOnDrop, JoinHandleExt},
+};
+
+pub enum Message {
+    Bootstrap,
+    Reset,
+    ConfirmReset,
+    CancelReset,
+    UpdatePassword(KeyEvent),
+    SubmitPassword,
+}
+
+/// The cross-signing state of the own user and device.
+struct Status {
+    /// Whether the own user identity has been published.
+    has_identity: bool,
+    /// Whether the own device is signed by the own user identity.
+    device_is_cross_signed: bool,
+    /// The private cross-signing keys available locally.
+    private_keys: Option<CrossSigningStatus>,
+}
+
+/// What is waiting for the password of the user-interactive authentication.
+enum Authentication {
+    Bootstrap { session: Option<String> },
+    Reset { handle: CrossSigningResetHandle, session: Option<String> },
+}
+
+/// Show the cross-signing status, and bootstrap or reset the cross-signing.
+pub struct Model {
+    client: Client,
+    status: Result<Status, String>,
+    is_confirming_reset: bool,
+    authentication: Option<Authentication>,
+    password_textarea: TextArea,
+    notice: Option<String>,
+    /// The reset waiting for the approval on the OAuth 2.0 server.
+    _oauth_reset_handle: Option<AbortOnDrop<()>>,
+}
+
+impl Model {
+    pub async fn new(client: Client) -> Self {
+        let status = load_status(&client).await;
+
+        Self {
+            client,
+            status,
+            is_confirming_reset: false,
+            authentication: None,
+            password_textarea: TextArea::new_masked(),
+            notice: None,
+            _oauth_reset_handle: None,
+        }
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.is_confirming_reset
+    }
+
+    pub fn is_authenticating(&self) -> bool {
+        self.authentication.is_some()
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::Bootstrap => {
+                let result = self.client.encryption().bootstrap_cross_signing(None).await;
+
+                self.handle_bootstrap_result(result);
+            }
+            Message::Reset => {
+                self.is_confirming_reset = true;
+
+                return None;
+            }
+            Message::CancelReset => {
+                self.is_confirming_reset = false;
+
+                return None;
+            }
+            Message::ConfirmReset => {
+                self.is_confirming_reset = false;
+
+                match self.client.encryption().reset_cross_signing().await {
+                    Ok(Some(handle)) => match handle.auth_type() {
+                        CrossSigningResetAuthType::Uiaa(uiaa_info) => {
+                            let session = uiaa_info.session.clone();
+
+                            self.authentication = Some(Authentication::Reset { handle, session });
+                        }
+                        CrossSigningResetAuthType::OAuth(oauth_info) => {
+                            self.notice = Some(format!(
+                                "approve the reset at {}, it completes once approved",
+                                oauth_info.approval_url
+                            ));
+                            self._oauth_reset_handle = Some(
+                                spawn(async move {
+                                    match handle.auth(None).await {
+                                        Ok(()) => info!("Cross-signing reset"),
+                                        Err(error) => {
+                                            error!("Failed to reset the cross-signing: {error}")
+                                        }
+                                    }
+                                })
+                                .abort_on_drop(),
+                            );
+                        }
+                    },
+                    Ok(None) => self.notice = Some("cross-signing reset".to_owned()),
+                    Err(error) => {
+                        self.notice = Some(format!("failed to reset the cross-signing: {error}"))
+                    }
+                }
+            }
+            Message::UpdatePassword(key_event) => {
+                self.password_textarea.handle_input(key_event);
+
+                return None;
+            }
+            Message::SubmitPassword => {
+                let authentication = self.authentication.take()?;
+                let user_id = self.client.user_id()?.to_string();
+                let password = self.password_textarea.input();
+                self.password_textarea = TextArea::new_masked();
+
+                match authentication {
+                    Authentication::Bootstrap { session } => {
+                        let mut password =
+                            Password::new(UserIdentifier::UserIdOrLocalpart(user_id), password);
+                        password.session = session;
+
+                        let result = self
+                            .client
+                            .encryption()
+                            .bootstrap_cross_signing(Some(AuthData::Password(password)))
+                            .await;
+
+                        self.handle_bootstrap_result(result);
+                    }
+                    Authentication::Reset { handle, session } => {
+                        let mut password =
+                            Password::new(UserIdentifier::UserIdOrLocalpart(user_id), password);
+                        password.session = session.clone();
+
+                        match handle.auth(Some(AuthData::Password(password))).await {
+                            Ok(()) => self.notice = Some("cross-signing reset".to_owned()),
+                            Err(error) => {
+                                self.notice = Some(format!("failed to authenticate: {error}"));
+                                // Let the user type the password again.
+                                self.authentication =
+                                    Some(Authentication::Reset { handle, session });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // The bootstrap or the reset may have changed the status.
+        self.status = load_status(&self.client).await;
+
+        None
+    }
+
+    fn handle_bootstrap_result(&mut self, result: matrix_sdk::Result<()>) {
+        match result {
+            Ok(()) => self.notice = Some("cross-signing bootstrapped".to_owned()),
+            Err(error) => match error.as_uiaa_response() {
+                // The homeserver asks for the password, again if it was wrong.
+                Some(uiaa_info) => {
+                    self.notice = uiaa_info.auth_error.as_ref().map(|auth_error| {
+                        format!("failed to authenticate: {}", auth_error.message)
+                    });
+                    self.authentication =
+                        Some(Authentication::Bootstrap { session: uiaa_info.session.clone() });
+                }
+                None => {
+                    self.notice = Some(format!("failed to bootstrap the cross-signing: {error}"))
+                }
+            },
+        }
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(14)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("cross-signing", area);
+
+        let block = block_with_title("Cross-signing");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [status_area, prompt_area, notice_area] = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        let mut text = Text::default();
+
+        match &self.status {
+            Ok(status) => {
+                let yes_or_no = |value: bool| {
+                    if value { Span::raw("yes").green() } else { Span::raw("no").red() }
+                };
+                let private_keys = status.private_keys.as_ref();
+
+                for (label, value) in [
+                    ("identity published", status.has_identity),
+                    ("own device self-verified", status.device_is_cross_signed),
+                    (
+                        "master key available locally",
+                        private_keys.is_some_and(|keys| keys.has_master),
+                    ),
+                    (
+                        "self-signing key available locally",
+                        private_keys.is_some_and(|keys| keys.has_self_signing),
+                    ),
+                    (
+                        "user-signing key available locally",
+                        private_keys.is_some_and(|keys| keys.has_user_signing),
+                    ),
+                ] {
+                    text.push_line(Line::from(vec![
+                        Span::raw(format!("{label:<36}")),
+                        yes_or_no(value),
+                    ]));
+                }
+            }
+            Err(error) => {
+                text.push_line(Line::from(format!("failed to load the status: {error}")).red())
+            }
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("b: bootstrap · r: reset").dark_gray());
+
+        Paragraph::new(text).render(status_area, buffer);
+
+        if self.is_confirming_reset {
+            Paragraph::new(
+                "Reset the cross-signing? The other users and devices will have to verify this \
+                 device again. y/n",
+            )
+            .wrap(Wrap { trim: true })
+            .style(Style::default().yellow())
+            .render(prompt_area, buffer);
+        } else if self.authentication.is_some() {
+            let [label_area, password_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(3)]).areas(prompt_area);
+
+            Line::from("Type your password, and press <Enter>:").render(label_area, buffer);
+            self.password_textarea.render(password_area, buffer);
+        }
+
+        if let Some(notice) = &self.notice {
+            Line::styled(notice.as_str(), Style::default().yellow()).render(notice_area, buffer);
+        }
+    }
+}
+
+async fn load_status(client: &Client) -> Result<Status, String> {
+    let encryption = client.encryption();
+    let own_user_id = client.user_id().ok_or_else(|| "not logged in".to_owned())?;
+
+    let has_identity = encryption
+        .get_user_identity(own_user_id)
+        .await
+        .map_err(|error| error.to_string())?
+        .is_some();
+    let device_is_cross_signed = encryption
+        .get_own_device()
+        .await
+        .map_err(|error| error.to_string())?
+        .is_some_and(|device| device.is_cross_signed_by_owner());
+
+    Ok(Status {
+        has_identity,
+        device_is_cross_signed,
+        private_keys: encryption.cross_signing_status().await,
+    })
+}
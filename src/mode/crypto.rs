@@ -0,0 +1,260 @@
+use std::collections::BTreeSet;
+
+use matrix_sdk::{
+    Room,
+    ruma::{MilliSecondsSinceUnixEpoch, OwnedUserId},
+};
+use matrix_sdk_crypto::{
+    GossipRequest, SecretInfo,
+    store::{
+        CryptoStore,
+        types::{RoomKeyWithheldEntry, StoredRoomKeyBundleData},
+    },
+    types::events::room_key_request::{MegolmV1AesSha2Content, SupportedKeyInfo},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, crypto_store, layout_debug, room::display_name, time};
+
+pub enum Message {
+    ScrollUp,
+    ScrollDown,
+}
+
+/// What the crypto store knows about the room keys of a room.
+struct Inspection {
+    /// The key request of each session, `None` if the key has not been
+    /// requested.
+    key_requests: Vec<(String, Option<GossipRequest>)>,
+    /// The `m.room_key.withheld` received for the sessions of the room.
+    withheld: Vec<RoomKeyWithheldEntry>,
+    /// The historical key bundle, if the room has been joined from an invite
+    /// on this client.
+    key_bundle: Option<KeyBundle>,
+}
+
+/// The keys of the history of a room, shared by the inviter (MSC4268).
+struct KeyBundle {
+    inviter: OwnedUserId,
+    invite_accepted_at: MilliSecondsSinceUnixEpoch,
+    /// The bundle received from the inviter, `None` if none has been received.
+    data: Option<StoredRoomKeyBundleData>,
+    /// The number of sessions of the room imported with the shared history
+    /// flag, e.g. from the bundle.
+    number_of_shared_history_sessions: usize,
+}
+
+/// A panel to investigate why the events of a room can't be decrypted.
+pub struct Model {
+    name: String,
+    inspection: Result<Inspection, String>,
+    scroll_position: u16,
+}
+
+impl Model {
+    /// Inspect the room keys of `room`, and the key requests of the sessions of
+    /// the UTDs of its timeline.
+    pub async fn new(room: Room, utd_session_ids: Vec<String>) -> Self {
+        Self {
+            name: display_name(&room),
+            inspection: inspect(&room, utd_session_ids).await,
+            scroll_position: 0,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::ScrollUp => self.scroll_position = self.scroll_position.saturating_sub(1),
+            Message::ScrollDown => self.scroll_position = self.scroll_position.saturating_add(1),
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("crypto", area);
+
+        let block = block_with_title(&format!("Room keys of {}", self.name));
+
+        let inspection = match &self.inspection {
+            Ok(inspection) => inspection,
+            Err(error) => {
+                Paragraph::new(format!("Failed to inspect the crypto store: {error}"))
+                    .red()
+                    .block(block)
+                    .render(area, buffer);
+
+                return;
+            }
+        };
+
+        let mut text = Text::default();
+
+        text.push_line(Line::from("Room key requests").bold());
+
+        if inspection.key_requests.is_empty() {
+            text.push_line(Line::from("  no undecryptable event").dark_gray());
+        }
+
+        for (session_id, key_request) in &inspection.key_requests {
+            let state = match key_request {
+                Some(key_request) if key_request.sent_out => Span::raw(format!(
+                    "sent to the devices of {}, request {}",
+                    key_request.request_recipient, key_request.request_id
+                ))
+                .green(),
+                Some(key_request) => Span::raw(format!(
+                    "not sent yet to the devices of {}, request {}",
+                    key_request.request_recipient, key_request.request_id
+                ))
+                .yellow(),
+                None => Span::raw("not requested").dark_gray(),
+            };
+
+            text.push_line(Line::from(vec![Span::raw(format!("  {session_id} ")), state]));
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("Withheld room keys").bold());
+
+        if inspection.withheld.is_empty() {
+            text.push_line(Line::from("  no m.room_key.withheld received").dark_gray());
+        }
+
+        for entry in &inspection.withheld {
+            let code = entry.content.withheld_code();
+
+            text.push_line(Line::from(vec![
+                Span::raw(format!(
+                    "  {} ",
+                    entry.content.megolm_session_id().unwrap_or("unknown session")
+                )),
+                Span::raw(code.as_str().to_owned()).red(),
+                Span::raw(format!(" from {}: {code}", entry.sender)),
+            ]));
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("Historical key bundle").bold());
+
+        match &inspection.key_bundle {
+            None => text.push_line(
+                Line::from("  the room has not been joined from an invite on this client")
+                    .dark_gray(),
+            ),
+            Some(key_bundle) => {
+                let accepted_at = key_bundle
+                    .invite_accepted_at
+                    .to_system_time()
+                    .map_or_else(|| "???".to_owned(), time::format);
+
+                text.push_line(format!(
+                    "  invited by {}, accepted at {accepted_at}",
+                    key_bundle.inviter
+                ));
+                text.push_line(match &key_bundle.data {
+                    Some(data) => Line::from(format!(
+                        "  bundle received from {} with the device key {}",
+                        data.sender_user,
+                        data.sender_key.to_base64()
+                    ))
+                    .green(),
+                    None => Line::from("  no bundle received").yellow(),
+                });
+                text.push_line(format!(
+                    "  {} sessions imported with the shared history flag",
+                    key_bundle.number_of_shared_history_sessions
+                ));
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_position, 0))
+            .block(block)
+            .render(area, buffer);
+    }
+}
+
+async fn inspect(room: &Room, utd_session_ids: Vec<String>) -> Result<Inspection, String> {
+    let crypto_store = crypto_store::get().ok_or_else(|| "no crypto store".to_owned())?;
+    let room_id = room.room_id().to_owned();
+
+    // The sessions of the UTDs, and the sessions whose key requests are still
+    // to be sent.
+    let mut session_ids = utd_session_ids.into_iter().collect::<BTreeSet<_>>();
+
+    session_ids.extend(
+        crypto_store
+            .get_unsent_secret_requests()
+            .await
+            .map_err(|error| error.to_string())?
+            .into_iter()
+            .filter_map(|key_request| match key_request.info {
+                SecretInfo::KeyRequest(SupportedKeyInfo::MegolmV1AesSha2(info))
+                    if info.room_id == room_id =>
+                {
+                    Some(info.session_id)
+                }
+                _ => None,
+            }),
+    );
+
+    let mut key_requests = Vec::new();
+
+    for session_id in session_ids {
+        let info = SecretInfo::from(MegolmV1AesSha2Content {
+            room_id: room_id.clone(),
+            sender_key: None,
+            session_id: session_id.clone(),
+        });
+        let key_request = crypto_store
+            .get_secret_request_by_info(&info)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        key_requests.push((session_id, key_request));
+    }
+
+    let withheld = crypto_store
+        .get_withheld_sessions_by_room_id(&room_id)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let key_bundle = match room.invite_acceptance_details() {
+        Some(invite_acceptance_details) => {
+            let data = crypto_store
+                .get_received_room_key_bundle_data(&room_id, &invite_acceptance_details.inviter)
+                .await
+                .map_err(|error| error.to_string())?;
+            let number_of_shared_history_sessions = crypto_store
+                .get_inbound_group_sessions_by_room_id(&room_id)
+                .await
+                .map_err(|error| error.to_string())?
+                .iter()
+                .filter(|session| session.has_been_imported() && session.shared_history())
+                .count();
+
+            Some(KeyBundle {
+                inviter: invite_acceptance_details.inviter,
+                invite_accepted_at: invite_acceptance_details.invite_accepted_at,
+                data,
+                number_of_shared_history_sessions,
+            })
+        }
+        None => None,
+    };
+
+    Ok(Inspection { key_requests, withheld, key_bundle })
+}
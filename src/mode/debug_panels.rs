@@ -0,0 +1,92 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    app,
+    block::block_with_title,
+    debug_panel::{self, Hook, Panel},
+    layout_debug,
+};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Open,
+    OpenWithKey(char),
+}
+
+/// A popup listing the registered debug panels, to open one.
+pub struct Model {
+    panels: Vec<Panel>,
+    list_state: ListState,
+}
+
+impl Model {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        Self { panels: debug_panel::panels(), list_state }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        let panel = match message {
+            Message::MoveCursorUp => {
+                self.list_state.select_previous();
+
+                return None;
+            }
+            Message::MoveCursorDown => {
+                self.list_state.select_next();
+
+                return None;
+            }
+            Message::Open => self.panels.get(self.list_state.selected().unwrap_or(0))?,
+            Message::OpenWithKey(key) => self.panels.iter().find(|panel| panel.key == key)?,
+        };
+
+        debug_panel::open(panel.name)
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(50)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(self.panels.len() as u16 + 4)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("debug panels", area);
+
+        let block = block_with_title("Debug panels");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        StatefulWidget::render(
+            List::new(self.panels.iter().map(|panel| {
+                let mut spans =
+                    vec![Span::raw(format!("{}  ", panel.key)).bold(), Span::raw(panel.name)];
+
+                if let Hook::Render(_) = panel.hook {
+                    spans.push(if debug_panel::is_overlay_visible(panel.name) {
+                        Span::raw(" (on)").green()
+                    } else {
+                        Span::raw(" (off)").dark_gray()
+                    });
+                }
+
+                ListItem::new(Line::from(spans))
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            inner_area.inner(Margin::new(0, 1)),
+            buffer,
+            &mut self.list_state,
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use matrix_sdk::{
+    Client,
+    encryption::identities::Device,
+    ruma::{OwnedDeviceId, OwnedUserId, SecondsSinceUnixEpoch, UserId},
+};
+use matrix_sdk_crypto::store::CryptoStore;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, crypto_store, layout_debug, time};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Select,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// An Olm session held with a device.
+struct OlmSession {
+    session_id: String,
+    created_at: SecondsSinceUnixEpoch,
+    last_used_at: SecondsSinceUnixEpoch,
+}
+
+struct DeviceKeys {
+    device_id: OwnedDeviceId,
+    display_name: Option<String>,
+    ed25519_key: Option<String>,
+    curve25519_key: Option<String>,
+    trust: Vec<Span<'static>>,
+    /// The Olm sessions with the device, the most recently used first.
+    olm_sessions: Vec<OlmSession>,
+}
+
+/// What the crypto store knows about the devices of a user.
+struct Dump {
+    user_id: OwnedUserId,
+    /// Whether the user identity is verified, `None` if the user has no
+    /// identity.
+    identity_is_verified: Option<bool>,
+    devices: Vec<DeviceKeys>,
+}
+
+/// Dump the devices of a member, their keys, their trust, and the Olm sessions
+/// held with them.
+pub struct Model {
+    client: Client,
+    members: Vec<(OwnedUserId, Option<String>)>,
+    list_state: ListState,
+    dump: Option<Result<Dump, String>>,
+    scroll_position: u16,
+}
+
+impl Model {
+    pub fn new(client: Client, mut members: Vec<(OwnedUserId, Option<String>)>) -> Self {
+        members.sort_by_cached_key(|(user_id, display_name)| {
+            display_name.as_deref().unwrap_or(user_id.as_str()).to_lowercase()
+        });
+
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        Self { client, members, list_state, dump: None, scroll_position: 0 }
+    }
+
+    fn selected(&self) -> Option<&OwnedUserId> {
+        self.members.get(self.list_state.selected()?).map(|(user_id, _)| user_id)
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::Select => {
+                let user_id = self.selected()?.clone();
+
+                self.dump = Some(dump(&self.client, user_id).await);
+                self.scroll_position = 0;
+            }
+            Message::ScrollUp => self.scroll_position = self.scroll_position.saturating_sub(1),
+            Message::ScrollDown => self.scroll_position = self.scroll_position.saturating_add(1),
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(90)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("devices", area);
+
+        let block = block_with_title("Devices and Olm sessions");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [content_area, help_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner_area);
+        let [members_area, dump_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .spacing(1)
+                .areas(content_area);
+
+        StatefulWidget::render(
+            List::new(self.members.iter().map(|(user_id, display_name)| {
+                ListItem::new(Line::from(
+                    display_name.clone().unwrap_or_else(|| user_id.to_string()),
+                ))
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            members_area,
+            buffer,
+            &mut self.list_state,
+        );
+
+        let text = match &self.dump {
+            None => Text::from(Line::from("Pick a member, and press <Enter>").dark_gray()),
+            Some(Err(error)) => {
+                Text::from(Line::from(format!("Failed to read the crypto store: {error}")).red())
+            }
+            Some(Ok(dump)) => dump_text(dump),
+        };
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_position, 0))
+            .render(dump_area, buffer);
+
+        Line::from("↑↓: pick the member · <Enter>: dump · <PageUp> <PageDown>: scroll")
+            .dark_gray()
+            .render(help_area, buffer);
+    }
+}
+
+fn dump_text(dump: &Dump) -> Text<'_> {
+    let mut text = Text::default();
+
+    text.push_line(Line::from(vec![
+        Span::raw(dump.user_id.as_str()).bold(),
+        Span::raw(" · "),
+        match dump.identity_is_verified {
+            Some(true) => Span::raw("identity verified").green(),
+            Some(false) => Span::raw("identity not verified").yellow(),
+            None => Span::raw("no identity").dark_gray(),
+        },
+    ]));
+
+    if dump.devices.is_empty() {
+        text.push_line(Line::from("  no known device").dark_gray());
+    }
+
+    let format_time = |time: SecondsSinceUnixEpoch| {
+        time.to_system_time().map_or_else(|| "???".to_owned(), time::format)
+    };
+
+    for device in &dump.devices {
+        text.push_line("");
+        text.push_line(Line::from(vec![
+            Span::raw(device.device_id.as_str()).bold(),
+            Span::raw(
+                device.display_name.as_ref().map(|name| format!(" ({name})")).unwrap_or_default(),
+            ),
+        ]));
+
+        let mut trust = vec![Span::raw("  trust: ")];
+        trust.extend(device.trust.iter().cloned());
+        text.push_line(Line::from(trust));
+
+        text.push_line(format!("  ed25519: {}", device.ed25519_key.as_deref().unwrap_or("none")));
+        text.push_line(format!(
+            "  curve25519: {}",
+            device.curve25519_key.as_deref().unwrap_or("none")
+        ));
+
+        if device.olm_sessions.is_empty() {
+            text.push_line(Line::from("  no Olm session").dark_gray());
+        }
+
+        for olm_session in &device.olm_sessions {
+            text.push_line(Line::from(vec![
+                Span::raw(format!("  Olm session {}", olm_session.session_id)),
+                Span::raw(format!(
+                    " · created at {}, last used at {}",
+                    format_time(olm_session.created_at),
+                    format_time(olm_session.last_used_at)
+                ))
+                .dark_gray(),
+            ]));
+        }
+    }
+
+    text
+}
+
+async fn dump(client: &Client, user_id: OwnedUserId) -> Result<Dump, String> {
+    let crypto_store = crypto_store::get().ok_or_else(|| "no crypto store".to_owned())?;
+    let encryption = client.encryption();
+
+    let identity_is_verified = encryption
+        .get_user_identity(&user_id)
+        .await
+        .map_err(|error| error.to_string())?
+        .map(|identity| identity.is_verified());
+
+    let mut devices = Vec::new();
+
+    for device in
+        encryption.get_user_devices(&user_id).await.map_err(|error| error.to_string())?.devices()
+    {
+        // The Olm sessions are indexed by the Curve25519 key of the device.
+        let curve25519_key = device.curve25519_key().map(|key| key.to_base64());
+        let mut olm_sessions = match &curve25519_key {
+            Some(curve25519_key) => crypto_store
+                .get_sessions(curve25519_key)
+                .await
+                .map_err(|error| error.to_string())?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|session| OlmSession {
+                    session_id: session.session_id().to_owned(),
+                    created_at: session.creation_time,
+                    last_used_at: session.last_use_time,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        olm_sessions.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+
+        devices.push(DeviceKeys {
+            device_id: device.device_id().to_owned(),
+            display_name: device.display_name().map(ToOwned::to_owned),
+            ed25519_key: device.ed25519_key().map(|key| key.to_base64()),
+            curve25519_key,
+            trust: trust(&user_id, &device),
+            olm_sessions,
+        });
+    }
+
+    devices.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+
+    Ok(Dump { user_id, identity_is_verified, devices })
+}
+
+/// The trust state of `device`, from the local trust and the cross-signing.
+fn trust(user_id: &UserId, device: &Device) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+
+    if device.is_blacklisted() {
+        spans.push(Span::raw("blacklisted").red());
+    } else if device.is_verified() {
+        spans.push(Span::raw("verified").green());
+    } else {
+        spans.push(Span::raw("not verified").yellow());
+    }
+
+    if device.is_locally_trusted() {
+        spans.push(Span::raw(" · locally trusted"));
+    }
+
+    if device.is_cross_signed_by_owner() {
+        spans.push(Span::raw(format!(" · cross-signed by {user_id}")));
+    } else {
+        spans.push(Span::raw(" · not cross-signed").dark_gray());
+    }
+
+    spans
+}
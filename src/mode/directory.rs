@@ -0,0 +1,263 @@
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Client,
+    ruma::{
+        OwnedServerName, UInt, api::client::directory::get_public_rooms_filtered,
+        directory::PublicRoomsChunk,
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug};
+
+/// The number of rooms fetched per page.
+const PAGE_SIZE: u32 = 20;
+
+pub enum Message {
+    UpdateInput(KeyEvent),
+    FocusNext,
+    Search,
+    MoveCursorUp,
+    MoveCursorDown,
+    NextPage,
+    PreviousPage,
+    Join,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Server,
+    Search,
+    List,
+}
+
+/// Browse the public rooms of the homeserver, or of another server.
+pub struct Model {
+    client: Client,
+    server_textarea: TextArea,
+    search_textarea: TextArea,
+    focus: Focus,
+    /// The server of the rooms, `None` for the homeserver.
+    server: Option<OwnedServerName>,
+    rooms: Vec<PublicRoomsChunk>,
+    list_state: ListState,
+    next_batch: Option<String>,
+    previous_batch: Option<String>,
+    total_room_count_estimate: Option<UInt>,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub async fn new(client: Client) -> Self {
+        let mut new = Self {
+            client,
+            server_textarea: TextArea::new(),
+            search_textarea: TextArea::new(),
+            focus: Focus::Search,
+            server: None,
+            rooms: Vec::new(),
+            list_state: ListState::default(),
+            next_batch: None,
+            previous_batch: None,
+            total_room_count_estimate: None,
+            notice: None,
+        };
+        new.fetch(None).await;
+
+        new
+    }
+
+    pub fn input_is_focused(&self) -> bool {
+        self.focus != Focus::List
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateInput(key_event) => match self.focus {
+                Focus::Server => {
+                    self.server_textarea.handle_input(key_event);
+                }
+                Focus::Search => {
+                    self.search_textarea.handle_input(key_event);
+                }
+                Focus::List => {}
+            },
+            Message::FocusNext => {
+                self.focus = match self.focus {
+                    Focus::Server => Focus::Search,
+                    Focus::Search => Focus::List,
+                    Focus::List => Focus::Server,
+                };
+            }
+            Message::Search => {
+                self.fetch(None).await;
+                self.focus = Focus::List;
+            }
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::NextPage => {
+                if let Some(next_batch) = self.next_batch.clone() {
+                    self.fetch(Some(next_batch)).await;
+                }
+            }
+            Message::PreviousPage => {
+                if let Some(previous_batch) = self.previous_batch.clone() {
+                    self.fetch(Some(previous_batch)).await;
+                }
+            }
+            Message::Join => {
+                let room = self.rooms.get(self.list_state.selected().unwrap_or(0))?;
+
+                // Join via the server of the directory, the homeserver may not
+                // know the room yet.
+                let via = self.server.iter().cloned().collect::<Vec<_>>();
+
+                match self.client.join_room_by_id_or_alias((&*room.room_id).into(), &via).await {
+                    Ok(room) => return Some(app::Message::OpenRoom(room, None)),
+                    Err(error) => self.notice = Some(format!("failed to join: {error}")),
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Fetch a page of the directory, from the token `since`, or the first
+    /// page if `None`.
+    async fn fetch(&mut self, since: Option<String>) {
+        let server = self.server_textarea.input();
+        let server = server.trim();
+        let server = if server.is_empty() {
+            None
+        } else {
+            match OwnedServerName::try_from(server) {
+                Ok(server) => Some(server),
+                Err(error) => {
+                    self.notice = Some(format!("`{server}` is not a server name: {error}"));
+
+                    return;
+                }
+            }
+        };
+
+        let search_term = self.search_textarea.input();
+        let search_term = search_term.trim();
+
+        let mut request = get_public_rooms_filtered::v3::Request::new();
+        request.server = server.clone();
+        request.limit = Some(UInt::from(PAGE_SIZE));
+        request.since = since;
+        request.filter.generic_search_term =
+            (!search_term.is_empty()).then(|| search_term.to_owned());
+
+        match self.client.public_rooms_filtered(request).await {
+            Ok(response) => {
+                self.server = server;
+                self.rooms = response.chunk;
+                self.next_batch = response.next_batch;
+                self.previous_batch = response.prev_batch;
+                self.total_room_count_estimate = response.total_room_count_estimate;
+                self.notice = None;
+                self.list_state.select_first();
+            }
+            Err(error) => self.notice = Some(format!("failed to fetch the rooms: {error}")),
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("directory", area);
+
+        let server = match &self.server {
+            Some(server) => server.to_string(),
+            None => "the homeserver".to_owned(),
+        };
+        let title = match &self.notice {
+            Some(notice) => format!("Room directory of {server} — {notice}"),
+            None => format!("Room directory of {server}"),
+        };
+        let block = block_with_title(&title);
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [server_area, search_area, list_area, help_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Percentage(100),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        for (label, textarea, focus, area) in [
+            ("server ", &self.server_textarea, Focus::Server, server_area),
+            ("search ", &self.search_textarea, Focus::Search, search_area),
+        ] {
+            let [label_area, textarea_area] =
+                Layout::horizontal([Constraint::Length(7), Constraint::Percentage(100)])
+                    .areas(area);
+
+            let label_style = if self.focus == focus {
+                Style::default().yellow()
+            } else {
+                Style::default().dark_gray()
+            };
+
+            Line::styled(label, label_style).render(label_area, buffer);
+            textarea.render(textarea_area, buffer);
+        }
+
+        let width = usize::from(list_area.width).saturating_sub(4);
+
+        StatefulWidget::render(
+            List::new(self.rooms.iter().map(|room| {
+                let name = room
+                    .name
+                    .clone()
+                    .or_else(|| room.canonical_alias.as_ref().map(ToString::to_string))
+                    .unwrap_or_else(|| room.room_id.to_string());
+
+                let mut text = Text::from(Line::from(vec![
+                    Span::raw(name).bold(),
+                    Span::raw(format!(" {} members", room.num_joined_members)).dark_gray(),
+                ]));
+
+                if let Some(topic) = &room.topic {
+                    let topic = topic.lines().next().unwrap_or_default();
+
+                    let topic = textwrap::wrap(topic, width).into_iter().next().unwrap_or_default();
+
+                    text.push_line(Line::from(topic.into_owned()).italic());
+                }
+
+                ListItem::new(text)
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            list_area.inner(Margin::new(0, 1)),
+            buffer,
+            &mut self.list_state,
+        );
+
+        let pages = match self.total_room_count_estimate {
+            Some(total) => format!("~{total} rooms · "),
+            None => String::new(),
+        };
+
+        Line::from(format!(
+            "{pages}Tab: focus · Enter: search, or join · ↑↓: select · n/p: next/previous page"
+        ))
+        .dark_gray()
+        .render(help_area, buffer);
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use matrix_sdk_ui::Timeline;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    widgets::{Cell, Clear, Row, Table, Widget},
+};
+
+use crate::{
+    app,
+    block::block_with_title,
+    clipboard, layout_debug,
+    transcript::{self, Format},
+};
+
+pub enum Message {
+    ToggleSource,
+    Export(Format),
+}
+
+/// The events to export.
+enum Source {
+    /// The items currently loaded in the timeline.
+    Timeline,
+
+    /// All the events of the room stored in the event cache.
+    EventCache,
+}
+
+pub struct Model {
+    timeline: Arc<Timeline>,
+    source: Source,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(timeline: Arc<Timeline>) -> Self {
+        Self { timeline, source: Source::Timeline, notice: None }
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::ToggleSource => {
+                self.source = match self.source {
+                    Source::Timeline => Source::EventCache,
+                    Source::EventCache => Source::Timeline,
+                };
+            }
+            Message::Export(format) => {
+                let entries = match self.source {
+                    Source::Timeline => Ok(transcript::from_timeline(&self.timeline.items().await)),
+                    Source::EventCache => {
+                        let room = self.timeline.room();
+
+                        transcript::from_event_cache(&room.client(), room.room_id()).await
+                    }
+                };
+
+                self.notice = Some(match entries {
+                    Ok(entries) => match transcript::export(&entries, format) {
+                        Ok(path) => {
+                            let path = path.display().to_string();
+
+                            match clipboard::copy(&path) {
+                                Ok(()) => format!(
+                                    "{} events exported to `{path}`, copied to the clipboard",
+                                    entries.len()
+                                ),
+                                Err(_) => format!("{} events exported to `{path}`", entries.len()),
+                            }
+                        }
+                        Err(error) => format!("failed to export: {error}"),
+                    },
+                    Err(error) => format!("failed to read the event cache: {error}"),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let source = match self.source {
+            Source::Timeline => "the loaded timeline",
+            Source::EventCache => "the event cache",
+        };
+
+        let mut rows = vec![
+            Row::new([Cell::new("c"), Cell::new(format!("Toggle the source, now {source}"))]),
+            Row::new([Cell::new("t"), Cell::new(format!("Export as {}", Format::Text))]),
+            Row::new([Cell::new("m"), Cell::new(format!("Export as {}", Format::Markdown))]),
+            Row::new([Cell::new("j"), Cell::new(format!("Export as {}", Format::JsonLines))]),
+        ];
+
+        if let Some(notice) = &self.notice {
+            rows.push(Row::new([Cell::new(""), Cell::new(notice.as_str()).dark_gray()]));
+        }
+
+        let [_, area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(rows.len() as u16 + 2)])
+                .areas(area);
+        let [_, area] =
+            Layout::horizontal([Constraint::Percentage(100), Constraint::Min(50)]).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("export panel", area);
+
+        Table::default()
+            .rows(rows)
+            .widths([Constraint::Length(3), Constraint::Percentage(100)])
+            .block(block_with_title("Export the transcript"))
+            .render(area, buffer);
+    }
+}
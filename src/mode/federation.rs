@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use matrix_sdk::{
+    Room, RoomMemberships,
+    deserialized_responses::RawSyncOrStrippedState,
+    ruma::{OwnedServerName, events::room::server_acl::RoomServerAclEventContent},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, layout_debug, room::display_name};
+
+pub enum Message {
+    ScrollUp,
+    ScrollDown,
+}
+
+/// The federation of a room, to diagnose why some events or members are
+/// missing.
+pub struct Model {
+    name: String,
+    room_version: Option<String>,
+    predecessor: Option<String>,
+    successor: Option<String>,
+    /// The server ACL, `None` if the room has none.
+    server_acl: Result<Option<RoomServerAclEventContent>, String>,
+    /// The servers of the joined members, with their number of members, the
+    /// most represented first.
+    servers: Result<Vec<(OwnedServerName, usize)>, String>,
+    scroll_position: u16,
+}
+
+impl Model {
+    pub async fn new(room: Room) -> Self {
+        let server_acl = match room.get_state_event_static::<RoomServerAclEventContent>().await {
+            Ok(Some(RawSyncOrStrippedState::Sync(raw))) => raw
+                .deserialize()
+                .map(|event| event.as_original().map(|event| event.content.clone()))
+                .map_err(|error| error.to_string()),
+            Ok(_) => Ok(None),
+            Err(error) => Err(error.to_string()),
+        };
+        let servers = match room.members_no_sync(RoomMemberships::JOIN).await {
+            Ok(members) => {
+                let mut servers = BTreeMap::<OwnedServerName, usize>::new();
+
+                for member in &members {
+                    *servers.entry(member.user_id().server_name().to_owned()).or_default() += 1;
+                }
+
+                let mut servers = servers.into_iter().collect::<Vec<_>>();
+                servers.sort_by(|(_, left), (_, right)| right.cmp(left));
+
+                Ok(servers)
+            }
+            Err(error) => Err(error.to_string()),
+        };
+
+        Self {
+            name: display_name(&room),
+            room_version: room.version().map(|version| version.to_string()),
+            predecessor: room.predecessor_room().map(|predecessor| predecessor.room_id.to_string()),
+            successor: room.successor_room().map(|successor| successor.room_id.to_string()),
+            server_acl,
+            servers,
+            scroll_position: 0,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::ScrollUp => self.scroll_position = self.scroll_position.saturating_sub(1),
+            Message::ScrollDown => self.scroll_position = self.scroll_position.saturating_add(1),
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(70)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("federation", area);
+
+        let block = block_with_title(&format!("Federation of {}", self.name));
+        let unknown = || Span::raw("unknown").dark_gray();
+        let none = || Span::raw("none").dark_gray();
+
+        let mut text = Text::default();
+
+        text.push_line(Line::from("Room").bold());
+        text.push_line(Line::from(vec![
+            Span::raw("  version      "),
+            self.room_version.clone().map_or_else(unknown, Span::raw),
+        ]));
+        text.push_line(Line::from(vec![
+            Span::raw("  predecessor  "),
+            self.predecessor.clone().map_or_else(none, Span::raw),
+        ]));
+        text.push_line(Line::from(vec![
+            Span::raw("  successor    "),
+            self.successor.clone().map_or_else(none, |successor| Span::raw(successor).yellow()),
+        ]));
+
+        text.push_line("");
+        text.push_line(Line::from("Server ACL").bold());
+
+        let server_acl = match &self.server_acl {
+            Ok(Some(server_acl)) => {
+                let list = |servers: &[String], style: Style| {
+                    if servers.is_empty() {
+                        none()
+                    } else {
+                        Span::styled(servers.join(", "), style)
+                    }
+                };
+
+                text.push_line(Line::from(vec![
+                    Span::raw("  allow        "),
+                    list(&server_acl.allow, Style::default().green()),
+                ]));
+                text.push_line(Line::from(vec![
+                    Span::raw("  deny         "),
+                    list(&server_acl.deny, Style::default().red()),
+                ]));
+                text.push_line(format!(
+                    "  IP literals  {}",
+                    if server_acl.allow_ip_literals { "allowed" } else { "denied" }
+                ));
+
+                Some(server_acl)
+            }
+            Ok(None) => {
+                text.push_line(
+                    Line::from("  no m.room.server_acl, all the servers are allowed").dark_gray(),
+                );
+
+                None
+            }
+            Err(error) => {
+                text.push_line(
+                    Line::from(format!("  failed to load the server ACL: {error}")).red(),
+                );
+
+                None
+            }
+        };
+
+        text.push_line("");
+
+        match &self.servers {
+            Ok(servers) => {
+                text.push_line(
+                    Line::from(format!("Servers of the joined members ({})", servers.len())).bold(),
+                );
+
+                for (server_name, number_of_members) in servers {
+                    let mut line =
+                        Line::from(format!("  {:<40} {number_of_members}", server_name.as_str()));
+
+                    // The events of a denied server are rejected by the others.
+                    if server_acl.is_some_and(|server_acl| !server_acl.is_allowed(server_name)) {
+                        line.push_span(Span::raw(" denied by the ACL").red());
+                    }
+
+                    text.push_line(line);
+                }
+            }
+            Err(error) => {
+                text.push_line(Line::from("Servers of the joined members").bold());
+                text.push_line(Line::from(format!("  failed to load the members: {error}")).red());
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_position, 0))
+            .block(block)
+            .render(area, buffer);
+    }
+}
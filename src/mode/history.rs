@@ -0,0 +1,93 @@
+use matrix_sdk::Room;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{app, block::block_with_title, layout_debug, room::display_name};
+
+/// The maximum number of rooms listed in the history.
+const MAXIMUM_NUMBER_OF_ROOMS: usize = 10;
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Select,
+}
+
+/// The last visited rooms, the most recent first.
+pub struct Model {
+    rooms: Vec<Room>,
+    list_state: ListState,
+}
+
+impl Model {
+    pub fn new<'a>(recent_rooms: impl Iterator<Item = &'a Room>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        Self { rooms: recent_rooms.take(MAXIMUM_NUMBER_OF_ROOMS).cloned().collect(), list_state }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::Select => {
+                let room = self.rooms.get(self.list_state.selected().unwrap_or(0))?;
+
+                return Some(app::Message::OpenRoom(room.clone(), None));
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let height = u16::try_from(self.rooms.len().max(1)).unwrap_or(u16::MAX) + 2;
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("history", area);
+
+        let block = block_with_title("Recently visited rooms");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        if self.rooms.is_empty() {
+            Line::styled("No room has been visited yet", Style::default().dark_gray())
+                .render(inner_area.inner(Margin::new(1, 0)), buffer);
+
+            return;
+        }
+
+        StatefulWidget::render(
+            List::new(self.rooms.iter().map(|room| {
+                let mut spans = vec![Span::raw(display_name(room)).bold()];
+
+                let unread_messages = room.num_unread_messages();
+                let unread_mentions = room.num_unread_mentions();
+
+                if unread_messages > 0 {
+                    spans.push(Span::raw(format!(" {unread_messages} unread")).yellow());
+                }
+
+                if unread_mentions > 0 {
+                    spans.push(Span::raw(format!(" @{unread_mentions}")).red());
+                }
+
+                ListItem::new(Line::from(spans))
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            inner_area,
+            buffer,
+            &mut self.list_state,
+        );
+    }
+}
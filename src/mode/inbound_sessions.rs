@@ -0,0 +1,160 @@
+use std::{fs, io, path::PathBuf};
+
+use chrono::Local;
+use matrix_sdk::Room;
+use matrix_sdk_crypto::{olm::InboundGroupSession, store::CryptoStore};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, crypto_store, layout_debug, room::display_name};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Export,
+}
+
+/// List the inbound group sessions, i.e. the Megolm room keys, known for a
+/// room, and export them one by one, e.g. to compare them with another client.
+pub struct Model {
+    name: String,
+    sessions: Result<Vec<InboundGroupSession>, String>,
+    list_state: ListState,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub async fn new(room: Room) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        Self {
+            name: display_name(&room),
+            sessions: load_sessions(&room).await,
+            list_state,
+            notice: None,
+        }
+    }
+
+    fn selected(&self) -> Option<&InboundGroupSession> {
+        self.sessions.as_ref().ok()?.get(self.list_state.selected()?)
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::Export => {
+                let session = self.selected()?;
+
+                self.notice = Some(match export(session).await {
+                    Ok(path) => format!(
+                        "exported to `{}`, in plaintext: delete the file once compared",
+                        path.display()
+                    ),
+                    Err(error) => format!("failed to export: {error}"),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(90)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("inbound sessions", area);
+
+        let block = block_with_title(&format!("Inbound group sessions of {}", self.name));
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [list_area, notice_area, help_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(2), Constraint::Length(1)])
+                .areas(inner_area);
+
+        match &self.sessions {
+            Ok(sessions) if sessions.is_empty() => {
+                Line::from("No inbound group session").dark_gray().render(list_area, buffer)
+            }
+            Ok(sessions) => StatefulWidget::render(
+                List::new(sessions.iter().map(|session| ListItem::new(line(session))))
+                    .highlight_style(Style::new().bg(Color::DarkGray))
+                    .highlight_symbol(" > "),
+                list_area,
+                buffer,
+                &mut self.list_state,
+            ),
+            Err(error) => Line::from(format!("Failed to read the crypto store: {error}"))
+                .red()
+                .render(list_area, buffer),
+        }
+
+        if let Some(notice) = &self.notice {
+            Paragraph::new(notice.as_str())
+                .wrap(Wrap { trim: true })
+                .yellow()
+                .render(notice_area, buffer);
+        }
+
+        Line::from("↑↓: pick the session · e: export").dark_gray().render(help_area, buffer);
+    }
+}
+
+/// The line of `session`. A session is flagged as imported when it has been
+/// received from a key export or from the backup, rather than from a
+/// `m.room_key` to-device event: the SDK doesn't tell them apart.
+fn line(session: &InboundGroupSession) -> Line<'static> {
+    let mut spans = vec![
+        Span::raw(session.session_id().to_owned()).bold(),
+        Span::raw(format!(
+            " · sender key {} · first known index {}",
+            session.sender_key().to_base64(),
+            session.first_known_index()
+        )),
+    ];
+
+    if session.has_been_imported() {
+        spans.push(Span::raw(" · imported").yellow());
+    }
+
+    Line::from(spans)
+}
+
+async fn load_sessions(room: &Room) -> Result<Vec<InboundGroupSession>, String> {
+    let crypto_store = crypto_store::get().ok_or_else(|| "no crypto store".to_owned())?;
+
+    let mut sessions = crypto_store
+        .get_inbound_group_sessions_by_room_id(room.room_id())
+        .await
+        .map_err(|error| error.to_string())?;
+
+    sessions.sort_by(|a, b| a.session_id().cmp(b.session_id()));
+
+    Ok(sessions)
+}
+
+/// Write the room key of `session` in a file named after the session ID and
+/// the time, in the format of the decrypted key exports, i.e. a JSON array of
+/// room keys. The file isn't encrypted. Return the path of the file.
+async fn export(session: &InboundGroupSession) -> io::Result<PathBuf> {
+    // The session ID is in base64, which may contain a `/`.
+    let session_id = session.session_id().replace('/', "_");
+    let path = PathBuf::from(format!(
+        "multiverse-room-key-{session_id}-{}.json",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    fs::write(&path, serde_json::to_string_pretty(&[session.export().await])?)?;
+
+    Ok(path.canonicalize().unwrap_or(path))
+}
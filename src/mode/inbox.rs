@@ -0,0 +1,176 @@
+use matrix_sdk::{Client, encryption::verification::VerificationRequestState, ruma::DeviceId};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{
+    app,
+    block::block_with_title,
+    inbox::{self, Request},
+    layout_debug,
+};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Accept,
+    Decline,
+}
+
+/// List the verification requests, and the room key requests, addressed to
+/// this device, to accept or decline them.
+pub struct Model {
+    client: Client,
+    list_state: ListState,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(client: Client) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        Self { client, list_state, notice: None }
+    }
+
+    fn selected(&self) -> Option<Request> {
+        inbox::requests().get(self.list_state.selected()?).cloned()
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::Accept => {
+                self.notice = Some(match self.selected()? {
+                    // The verification itself continues on the other device.
+                    Request::Verification { request, .. } => match request.accept().await {
+                        Ok(()) => format!("verification with {} accepted", request.other_user_id()),
+                        Err(error) => format!("failed to accept the verification: {error}"),
+                    },
+                    // Once the device is verified, the SDK shares the room keys
+                    // it asks for.
+                    Request::RoomKey { device_id, request_id, .. } => {
+                        match self.verify_own_device(&device_id).await {
+                            Ok(()) => {
+                                inbox::remove(request_id.as_str());
+
+                                format!("{device_id} verified, its room key requests are answered")
+                            }
+                            Err(error) => format!("failed to verify {device_id}: {error}"),
+                        }
+                    }
+                });
+            }
+            Message::Decline => {
+                self.notice = match self.selected()? {
+                    // The request is removed from the inbox once it's cancelled.
+                    Request::Verification { request, .. } => match request.cancel().await {
+                        Ok(()) => None,
+                        Err(error) => Some(format!("failed to decline the verification: {error}")),
+                    },
+                    Request::RoomKey { request_id, .. } => {
+                        inbox::remove(request_id.as_str());
+
+                        None
+                    }
+                };
+            }
+        }
+
+        None
+    }
+
+    async fn verify_own_device(&self, device_id: &DeviceId) -> Result<(), String> {
+        let user_id = self.client.user_id().ok_or("not logged in")?;
+        let device = self
+            .client
+            .encryption()
+            .get_device(user_id, device_id)
+            .await
+            .map_err(|error| error.to_string())?
+            .ok_or("unknown device")?;
+
+        device.verify().await.map_err(|error| error.to_string())
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let requests = inbox::requests();
+
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(requests.len().max(1) as u16 + 6)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("inbox", area);
+
+        let block = block_with_title("Inbox");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [list_area, notice_area, help_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(2), Constraint::Length(1)])
+                .areas(inner_area);
+
+        if requests.is_empty() {
+            Line::from("No pending requests").dark_gray().render(list_area, buffer);
+        } else {
+            StatefulWidget::render(
+                List::new(requests.iter().map(|request| ListItem::new(line(request))))
+                    .highlight_style(Style::new().bg(Color::DarkGray))
+                    .highlight_symbol(" > "),
+                list_area,
+                buffer,
+                &mut self.list_state,
+            );
+        }
+
+        if let Some(notice) = &self.notice {
+            Paragraph::new(notice.as_str())
+                .wrap(Wrap { trim: true })
+                .yellow()
+                .render(notice_area, buffer);
+        }
+
+        Line::from("a: accept · d: decline").dark_gray().render(help_area, buffer);
+    }
+}
+
+fn line(request: &Request) -> Line<'_> {
+    match request {
+        Request::Verification { request, from_device } => {
+            let from = match (request.is_self_verification(), from_device) {
+                (true, Some(device_id)) => format!("your device {device_id}"),
+                (_, Some(device_id)) => format!("{} ({device_id})", request.other_user_id()),
+                (_, None) => request.other_user_id().to_string(),
+            };
+            let state = match request.state() {
+                VerificationRequestState::Created { .. }
+                | VerificationRequestState::Requested { .. } => "requested",
+                VerificationRequestState::Ready { .. } => "ready",
+                VerificationRequestState::Transitioned { .. } => "started",
+                VerificationRequestState::Done | VerificationRequestState::Cancelled(_) => {
+                    "finished"
+                }
+            };
+
+            Line::from(vec![
+                Span::raw("verification  ").bold(),
+                Span::raw(from),
+                Span::raw(format!(" · {state}")).dark_gray(),
+            ])
+        }
+        Request::RoomKey { device_id, room_id, .. } => Line::from(vec![
+            Span::raw("room key      ").bold(),
+            Span::raw(format!("your device {device_id}")),
+            Span::raw(format!(" · for {room_id}")).dark_gray(),
+        ]),
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{Local, NaiveDate};
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Room,
+    ruma::{
+        MilliSecondsSinceUnixEpoch, OwnedEventId, UInt,
+        api::{Direction, client::room::get_event_by_timestamp},
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Widget},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug, room::display_name};
+
+pub enum Message {
+    UpdateDate(KeyEvent),
+    Jump,
+}
+
+/// A popup to open the timeline of a room at a date.
+pub struct Model {
+    room: Room,
+    date_textarea: TextArea,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(room: Room) -> Self {
+        Self { room, date_textarea: TextArea::new(), notice: None }
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateDate(key_event) => {
+                self.date_textarea.handle_input(key_event);
+            }
+            Message::Jump => match self.find_first_event_of_the_day().await {
+                Ok(event_id) => {
+                    return Some(app::Message::OpenRoom(self.room.clone(), Some(event_id)));
+                }
+                Err(error) => self.notice = Some(error),
+            },
+        }
+
+        None
+    }
+
+    /// Ask the homeserver for the first event sent on the typed date, or after
+    /// it, with `/timestamp_to_event`.
+    async fn find_first_event_of_the_day(&self) -> Result<OwnedEventId, String> {
+        let date = self.date_textarea.input();
+        let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|error| format!("`{}` is not a YYYY-MM-DD date: {error}", date.trim()))?;
+        let start_of_the_day = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|start_of_the_day| start_of_the_day.and_local_timezone(Local).earliest())
+            .ok_or_else(|| format!("{date} has no midnight in the local time zone"))?;
+        let timestamp = UInt::try_from(start_of_the_day.timestamp_millis())
+            .map(MilliSecondsSinceUnixEpoch)
+            .map_err(|_| format!("{date} is before 1970"))?;
+
+        let request = get_event_by_timestamp::v1::Request::new(
+            self.room.room_id().to_owned(),
+            timestamp,
+            Direction::Forward,
+        );
+
+        match self.room.client().send(request).await {
+            Ok(response) => Ok(response.event_id),
+            Err(error) => Err(format!("no event found from {date}: {error}")),
+        }
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("jump to date", area);
+
+        let block = block_with_title(&format!("Jump to a date in {}", display_name(&self.room)));
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [date_area, help_area, notice_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+                .areas(inner_area);
+
+        self.date_textarea.render(date_area.inner(Margin::new(1, 0)), buffer);
+
+        Line::from("YYYY-MM-DD, then <Enter>").dark_gray().render(help_area, buffer);
+
+        if let Some(notice) = &self.notice {
+            Line::styled(notice.as_str(), Style::default().yellow()).render(notice_area, buffer);
+        }
+    }
+}
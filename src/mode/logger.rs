@@ -1,22 +1,29 @@
-use std::{ops::Not, time::Duration};
+use std::{cmp::min, fs, io, ops::Not, path::PathBuf, time::Duration};
 
+use chrono::Local;
+use crossterm::event::KeyEvent;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::{Modifier, Style, Stylize},
-    widgets::{Cell, Clear, Row, Table, Widget},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Cell, Clear, List, ListItem, ListState, Row, StatefulWidget, Table, Widget},
 };
-use tokio::{spawn, sync::mpsc::Sender, time::sleep};
+use regex::Regex;
+use tokio::{sync::mpsc::Sender, time::sleep};
 use tui_logger::{
     TuiLoggerSmartWidget as LoggerWidget, TuiWidgetEvent as WidgetEvent,
     TuiWidgetState as LoggerState,
 };
 
 use crate::{
-    app,
-    block::{self, block_with_title},
+    TextArea, app,
+    block::{self, block, block_with_title},
+    clipboard,
     input::Input,
-    task_ext::{AbortOnDrop, JoinHandleExt},
+    layout_debug,
+    logs::{self, Log},
+    task_ext::{self, AbortOnDrop},
 };
 
 pub enum Scroll {
@@ -31,12 +38,23 @@ pub enum Message {
     FocusFilter,
     IncreaseShownLogLevel,
     DecreaseShownLogLevel,
+    FocusSearch,
+    UnfocusSearch,
+    UpdateSearch(KeyEvent),
+    NextMatch,
+    PreviousMatch,
+    Export,
 }
 
 pub struct Model {
     state: LoggerState,
     command_panel_is_opened: bool,
     filters_are_visible: bool,
+    search_textarea: TextArea,
+    search_is_focused: bool,
+    search: Option<Regex>,
+    selected_match: usize,
+    notice: Option<String>,
     _log_updates_handle: AbortOnDrop<()>,
 }
 
@@ -45,16 +63,28 @@ impl Model {
         let state = LoggerState::default();
         state.transition(WidgetEvent::HideKey);
 
-        let _log_updates_handle = spawn(log_updates_task(input_sender)).abort_on_drop();
+        let _log_updates_handle =
+            task_ext::supervise_with_restart("logger tick", input_sender.clone(), move || {
+                log_updates_task(input_sender.clone())
+            });
 
         Self {
             state,
             command_panel_is_opened: false,
             filters_are_visible: false,
+            search_textarea: TextArea::new(),
+            search_is_focused: false,
+            search: None,
+            selected_match: 0,
+            notice: None,
             _log_updates_handle,
         }
     }
 
+    pub fn search_is_focused(&self) -> bool {
+        self.search_is_focused
+    }
+
     pub fn update(&mut self, message: Message) -> Option<app::Message> {
         self.command_panel_is_opened = false;
 
@@ -92,12 +122,160 @@ impl Model {
                     self.state.transition(WidgetEvent::LeftKey);
                 }
             }
+            Message::FocusSearch => {
+                self.search_is_focused = true;
+            }
+            Message::UnfocusSearch => {
+                self.search_is_focused = false;
+            }
+            Message::UpdateSearch(key_event) => {
+                if self.search_textarea.handle_input(key_event) {
+                    let pattern = self.search_textarea.input();
+
+                    // The pattern is a regex if it is valid, a substring
+                    // otherwise.
+                    self.search = if pattern.is_empty() {
+                        None
+                    } else {
+                        Regex::new(&pattern).or_else(|_| Regex::new(&regex::escape(&pattern))).ok()
+                    };
+                    self.selected_match = 0;
+                }
+            }
+            Message::NextMatch => {
+                let number_of_matches = self.matching_logs().map_or(0, |logs| logs.len());
+
+                self.selected_match =
+                    min(self.selected_match.saturating_add(1), number_of_matches.saturating_sub(1));
+            }
+            Message::PreviousMatch => {
+                self.selected_match = self.selected_match.saturating_sub(1);
+            }
+            Message::Export => {
+                self.notice = Some(match self.export() {
+                    Ok(path) => {
+                        let path = path.display().to_string();
+
+                        match clipboard::copy(&path) {
+                            Ok(()) => format!("exported to `{path}`, copied to the clipboard"),
+                            Err(_) => format!("exported to `{path}`"),
+                        }
+                    }
+                    Err(error) => format!("failed to export: {error}"),
+                });
+            }
         }
 
         None
     }
 
+    /// Write the logs matching the current search, or all the logs if there is
+    /// no search, in a timestamped file.
+    fn export(&self) -> io::Result<PathBuf> {
+        let logs = self
+            .matching_logs()
+            .unwrap_or_else(|| logs::with_logs(|logs| logs.iter().cloned().collect()));
+        let path =
+            PathBuf::from(format!("multiverse-{}.log", Local::now().format("%Y%m%d-%H%M%S")));
+
+        fs::write(&path, logs.iter().map(|log| format!("{log}\n")).collect::<String>())?;
+
+        Ok(path.canonicalize().unwrap_or(path))
+    }
+
+    fn title(&self, title: &str) -> String {
+        match &self.notice {
+            Some(notice) => format!("{title} — {notice}"),
+            None => title.to_owned(),
+        }
+    }
+
+    /// The logs matching the current search, if any.
+    pub fn matching_logs(&self) -> Option<Vec<Log>> {
+        let search = self.search.as_ref()?;
+
+        Some(logs::with_logs(|logs| {
+            logs.iter().filter(|log| search.is_match(&log.to_string())).cloned().collect()
+        }))
+    }
+
     pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let area = if self.search_is_focused || self.search.is_some() {
+            let [area, search_area] =
+                Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)]).areas(area);
+
+            layout_debug::register("logger search", search_area);
+
+            Span::raw("/").render(search_area, buffer);
+            self.search_textarea.render(
+                Rect {
+                    x: search_area.x + 1,
+                    width: search_area.width.saturating_sub(1),
+                    ..search_area
+                },
+                buffer,
+            );
+
+            area
+        } else {
+            area
+        };
+
+        layout_debug::register("logger", area);
+
+        if let Some(matching_logs) = self.matching_logs() {
+            self.render_matching_logs(matching_logs, area, buffer);
+        } else {
+            self.render_logs(area, buffer);
+        }
+
+        if self.command_panel_is_opened {
+            self.render_command_panel(area, buffer);
+        }
+    }
+
+    fn render_matching_logs(&self, matching_logs: Vec<Log>, area: Rect, buffer: &mut Buffer) {
+        let Some(search) = &self.search else { return };
+
+        let number_of_matches = matching_logs.len();
+        let selected_match = self.selected_match.min(number_of_matches.saturating_sub(1));
+        let match_style = Style::default().fg(Color::Black).bg(Color::Yellow);
+
+        let items = matching_logs.iter().map(|log| {
+            let log = log.to_string().replace('\n', " ");
+            let mut line = Line::default();
+            let mut last_end = 0;
+
+            for found in search.find_iter(&log) {
+                line.push_span(Span::raw(log[last_end..found.start()].to_owned()));
+                line.push_span(Span::styled(found.as_str().to_owned(), match_style));
+                last_end = found.end();
+            }
+
+            line.push_span(Span::raw(log[last_end..].to_owned()));
+
+            ListItem::new(line)
+        });
+
+        let title = if number_of_matches == 0 {
+            self.title("Logs (no match)")
+        } else {
+            self.title(&format!("Logs (match {}/{number_of_matches})", selected_match + 1))
+        };
+
+        let mut state = ListState::default().with_selected(Some(selected_match));
+
+        StatefulWidget::render(
+            List::new(items)
+                .highlight_style(Style::new().bg(Color::DarkGray))
+                .block(block().title(title)),
+            area,
+            buffer,
+            &mut state,
+        );
+    }
+
+    fn render_logs(&self, area: Rect, buffer: &mut Buffer) {
         let maybe_dim = if self.filters_are_visible { Modifier::DIM } else { Modifier::empty() };
 
         let logger = LoggerWidget::default()
@@ -107,42 +285,45 @@ impl Model {
             .style_info(Style::default().magenta().add_modifier(maybe_dim))
             .style_trace(Style::default().green().add_modifier(maybe_dim))
             .style_debug(Style::default().blue().add_modifier(maybe_dim))
-            .title_log("Logs")
+            .title_log(self.title("Logs"))
             .title_target("Filter by targets")
             .state(&self.state);
 
         logger.render(area, buffer);
+    }
 
-        if self.command_panel_is_opened {
-            let rows = [
-                Row::new([Cell::new("f"), Cell::new("Toggle filters")]),
-                Row::new([Cell::new("───"), Cell::new("Focus in on filters ─────────────")]),
-                Row::new([Cell::new("s"), Cell::new("Toggle the target")]),
-                Row::new([Cell::new("→"), Cell::new("Increase log level for the target")]),
-                Row::new([Cell::new("←"), Cell::new("Decrease log level for the target")]),
-                Row::new([Cell::new("↑"), Cell::new("Scroll the targets up")]),
-                Row::new([Cell::new("↓"), Cell::new("Scroll the targets down")]),
-                Row::new([Cell::new("───"), Cell::new("Focus is on logs ─────────────────")]),
-                Row::new([Cell::new("↑"), Cell::new("Scroll the logs up")]),
-                Row::new([Cell::new("↓"), Cell::new("Scroll the logs down")]),
-            ];
-
-            let [_, area] = Layout::vertical([
-                Constraint::Percentage(100),
-                Constraint::Min(rows.len() as u16 + 2),
-            ])
-            .areas(area);
-            let [_, area] =
-                Layout::horizontal([Constraint::Percentage(100), Constraint::Min(41)]).areas(area);
-
-            Clear.render(area, buffer);
-
-            Table::default()
-                .rows(rows)
-                .widths([Constraint::Length(3), Constraint::Percentage(100)])
-                .block(block_with_title("Logger"))
-                .render(area, buffer);
-        }
+    fn render_command_panel(&self, area: Rect, buffer: &mut Buffer) {
+        let rows = [
+            Row::new([Cell::new("f"), Cell::new("Toggle filters")]),
+            Row::new([Cell::new("/"), Cell::new("Search in logs")]),
+            Row::new([Cell::new("n"), Cell::new("Jump to the next match")]),
+            Row::new([Cell::new("N"), Cell::new("Jump to the previous match")]),
+            Row::new([Cell::new("e"), Cell::new("Export the logs to a file")]),
+            Row::new([Cell::new("───"), Cell::new("Focus in on filters ─────────────")]),
+            Row::new([Cell::new("s"), Cell::new("Toggle the target")]),
+            Row::new([Cell::new("→"), Cell::new("Increase log level for the target")]),
+            Row::new([Cell::new("←"), Cell::new("Decrease log level for the target")]),
+            Row::new([Cell::new("↑"), Cell::new("Scroll the targets up")]),
+            Row::new([Cell::new("↓"), Cell::new("Scroll the targets down")]),
+            Row::new([Cell::new("───"), Cell::new("Focus is on logs ─────────────────")]),
+            Row::new([Cell::new("↑"), Cell::new("Scroll the logs up")]),
+            Row::new([Cell::new("↓"), Cell::new("Scroll the logs down")]),
+        ];
+
+        let [_, area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(rows.len() as u16 + 2)])
+                .areas(area);
+        let [_, area] =
+            Layout::horizontal([Constraint::Percentage(100), Constraint::Min(41)]).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("logger panel", area);
+
+        Table::default()
+            .rows(rows)
+            .widths([Constraint::Length(3), Constraint::Percentage(100)])
+            .block(block_with_title("Logger"))
+            .render(area, buffer);
     }
 }
 
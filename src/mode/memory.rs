@@ -0,0 +1,149 @@
+use std::{fs, mem};
+
+use chrono::{DateTime, Local};
+use matrix_sdk::Client;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Cell, Clear, Row, Table, Widget},
+};
+
+use crate::{block::block_with_title, layout_debug, logs};
+
+/// The approximate memory used by a collection.
+pub struct Usage {
+    pub name: &'static str,
+    pub count: usize,
+    /// The estimated size, in bytes: the size of the items, plus the length of
+    /// their JSON, or of their strings.
+    pub size: usize,
+}
+
+/// A snapshot of the memory used by the main collections, to track the leaks.
+/// It's refreshed by opening it again.
+pub struct Model {
+    time: DateTime<Local>,
+    usages: Vec<Usage>,
+    /// The memory used by the whole process, if the system tells it.
+    resident_set_size: Option<usize>,
+}
+
+impl Model {
+    /// `usages` are the ones of the opened room, if any.
+    pub fn new(client: &Client, mut usages: Vec<Usage>) -> Self {
+        usages.push(room_list_usage(client));
+        usages.push(logs_usage());
+
+        Self { time: Local::now(), usages, resident_set_size: resident_set_size() }
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let height = self.usages.len() as u16 + 6;
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("memory", area);
+
+        let block = block_with_title("Memory usage");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [table_area, total_area, help_area] = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        let header = Row::new(["", "Count", "Estimated size"]).bold();
+        let rows = self.usages.iter().map(|usage| {
+            Row::new([
+                Cell::new(usage.name),
+                Cell::new(Line::from(usage.count.to_string()).right_aligned()),
+                Cell::new(Line::from(format_size(usage.size)).right_aligned()),
+            ])
+        });
+
+        Table::new(
+            rows,
+            [Constraint::Percentage(100), Constraint::Length(8), Constraint::Length(14)],
+        )
+        .header(header)
+        .render(table_area, buffer);
+
+        let total = match self.resident_set_size {
+            Some(resident_set_size) => format!(
+                "Whole process: {}, at {}",
+                format_size(resident_set_size),
+                self.time.format("%H:%M:%S")
+            ),
+            None => format!("At {}", self.time.format("%H:%M:%S")),
+        };
+
+        Line::from(total).render(total_area, buffer);
+        Line::from("r: refresh").dark_gray().render(help_area, buffer);
+    }
+}
+
+/// The rooms known by the client, with their info as persisted in the state
+/// store.
+fn room_list_usage(client: &Client) -> Usage {
+    let rooms = client.rooms();
+
+    Usage {
+        name: "rooms",
+        count: rooms.len(),
+        size: rooms
+            .iter()
+            .map(|room| {
+                mem::size_of_val(room)
+                    + serde_json::to_vec(&room.clone_info()).map_or(0, |info| info.len())
+            })
+            .sum(),
+    }
+}
+
+fn logs_usage() -> Usage {
+    logs::with_logs(|logs| Usage {
+        name: "log buffer",
+        count: logs.len(),
+        size: logs
+            .iter()
+            .map(|log| mem::size_of_val(log) + log.target.len() + log.message.len())
+            .sum(),
+    })
+}
+
+/// The resident set size of the process, from `/proc`, on Linux only.
+fn resident_set_size() -> Option<usize> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+/// Format `size`, in bytes, e.g. `12.3 MiB`.
+fn format_size(size: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = size as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 { format!("{size} {}", UNITS[0]) } else { format!("{size:.1} {}", UNITS[unit]) }
+}
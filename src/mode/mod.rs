@@ -1,4 +1,28 @@
+pub mod account;
+pub mod audit_log;
+pub mod command;
+pub mod cross_signing;
+pub mod crypto;
+pub mod debug_panels;
+pub mod devices;
+pub mod directory;
+pub mod export;
+pub mod federation;
+pub mod history;
+pub mod inbound_sessions;
+pub mod inbox;
+pub mod jump_to_date;
 pub mod logger;
+pub mod memory;
+pub mod moderation;
+pub mod network;
+pub mod power_levels;
+pub mod reauthenticate;
 pub mod room;
+pub mod room_keys;
 pub mod room_list;
+pub mod secret_storage;
 pub mod space;
+pub mod switcher;
+pub mod tasks;
+pub mod third_party_ids;
@@ -0,0 +1,401 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Room,
+    room::MessagesOptions,
+    ruma::{
+        OwnedEventId, OwnedUserId, UInt,
+        events::{AnySyncTimelineEvent, MessageLikeEventType},
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Gauge, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+use tokio::{spawn, sync::mpsc::Sender, time::sleep};
+use tracing::{info, warn};
+
+use crate::{
+    TextArea, app,
+    block::block_with_title,
+    input::Input,
+    layout_debug,
+    task_ext::{AbortOnDrop, JoinHandleExt},
+};
+
+/// The number of messages to redact, unless another one is typed.
+const DEFAULT_NUMBER_OF_MESSAGES: usize = 50;
+
+/// The number of events asked per pagination.
+const PAGINATION_LIMIT: u32 = 100;
+
+/// The delay between two redactions, not to hit the rate limit of the
+/// homeserver too often. The rate-limited redactions are retried anyway, after
+/// the delay given by the homeserver.
+const REDACTION_INTERVAL: Duration = Duration::from_millis(500);
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    UpdateNumberOfMessages(KeyEvent),
+    Redact,
+    ConfirmRedact,
+    CancelRedact,
+}
+
+/// The progress of a bulk redaction, updated by its task.
+#[derive(Default)]
+struct Progress {
+    /// Whether the messages to redact are still being looked for.
+    is_paginating: bool,
+    /// The messages of the member found so far.
+    found: usize,
+    redacted: usize,
+    failed: usize,
+    is_done: bool,
+    error: Option<String>,
+}
+
+struct Redaction {
+    user_id: OwnedUserId,
+    progress: Arc<Mutex<Progress>>,
+    _handle: AbortOnDrop<()>,
+}
+
+/// Redact the last messages of a member, e.g. to clean up some spam.
+pub struct Model {
+    room: Room,
+    members: Vec<(OwnedUserId, Option<String>)>,
+    list_state: ListState,
+    number_textarea: TextArea,
+    can_redact: bool,
+    is_confirming: bool,
+    redaction: Option<Redaction>,
+    notice: Option<String>,
+    input_sender: Sender<Input>,
+}
+
+impl Model {
+    pub async fn new(
+        room: Room,
+        mut members: Vec<(OwnedUserId, Option<String>)>,
+        input_sender: Sender<Input>,
+    ) -> Self {
+        let can_redact = match (room.power_levels().await, room.client().user_id()) {
+            (Ok(power_levels), Some(own_user_id)) => {
+                power_levels.user_can_redact_event_of_other(own_user_id)
+            }
+            _ => false,
+        };
+
+        members.sort_by_cached_key(|(user_id, display_name)| {
+            display_name.as_deref().unwrap_or(user_id.as_str()).to_lowercase()
+        });
+
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        let mut number_textarea = TextArea::new_with_border();
+        number_textarea.set_input(&DEFAULT_NUMBER_OF_MESSAGES.to_string());
+
+        Self {
+            room,
+            members,
+            list_state,
+            number_textarea,
+            can_redact,
+            is_confirming: false,
+            redaction: None,
+            notice: None,
+            input_sender,
+        }
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.is_confirming
+    }
+
+    fn is_redacting(&self) -> bool {
+        self.redaction.as_ref().is_some_and(|redaction| !redaction.progress.lock().unwrap().is_done)
+    }
+
+    fn selected(&self) -> Option<&OwnedUserId> {
+        self.members.get(self.list_state.selected()?).map(|(user_id, _)| user_id)
+    }
+
+    fn number_of_messages(&self) -> Result<usize, String> {
+        let input = self.number_textarea.input();
+
+        match input.trim().parse() {
+            Ok(0) | Err(_) => Err(format!("`{input}` isn't a number of messages")),
+            Ok(number) => Ok(number),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::UpdateNumberOfMessages(key_event) => {
+                self.number_textarea.handle_input(key_event);
+                self.notice = None;
+            }
+            Message::Redact => {
+                if self.is_redacting() {
+                    self.notice = Some("a redaction is already running".to_owned());
+                } else if let Err(error) = self.number_of_messages() {
+                    self.notice = Some(error);
+                } else {
+                    self.is_confirming = self.selected().is_some();
+                }
+            }
+            Message::CancelRedact => self.is_confirming = false,
+            Message::ConfirmRedact => {
+                self.is_confirming = false;
+
+                let user_id = self.selected()?.clone();
+                let number_of_messages = self.number_of_messages().ok()?;
+                let progress = Arc::new(Mutex::new(Progress::default()));
+
+                info!("Redacting the last {number_of_messages} messages of {user_id}");
+
+                self.redaction = Some(Redaction {
+                    user_id: user_id.clone(),
+                    progress: progress.clone(),
+                    _handle: spawn(redact_task(
+                        self.room.clone(),
+                        user_id,
+                        number_of_messages,
+                        progress,
+                        self.input_sender.clone(),
+                    ))
+                    .abort_on_drop(),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(70)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(20)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("moderation", area);
+
+        let block = block_with_title("Redact the last messages of a member");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [members_area, number_area, help_area, progress_area, notice_area] =
+            Layout::vertical([
+                Constraint::Percentage(100),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(2),
+                Constraint::Length(2),
+            ])
+            .areas(inner_area);
+
+        StatefulWidget::render(
+            List::new(self.members.iter().map(|(user_id, display_name)| {
+                let mut spans = Vec::new();
+
+                if let Some(display_name) = display_name {
+                    spans.push(Span::raw(format!("{display_name} ")).bold());
+                }
+
+                spans.push(Span::raw(user_id.as_str()).dark_gray());
+
+                ListItem::new(Line::from(spans))
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            members_area,
+            buffer,
+            &mut self.list_state,
+        );
+
+        let [number_label_area, number_area] =
+            Layout::horizontal([Constraint::Length(22), Constraint::Percentage(100)])
+                .areas(number_area);
+
+        Paragraph::new("\nNumber of messages:").render(number_label_area, buffer);
+        self.number_textarea.render(number_area, buffer);
+
+        Line::from("↑↓: pick the member · <Enter>: redact · <Esc>: stop")
+            .dark_gray()
+            .render(help_area, buffer);
+
+        if let Some(redaction) = &self.redaction {
+            let progress = redaction.progress.lock().unwrap();
+
+            let [label_area, gauge_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+                    .areas(progress_area);
+
+            let label = if progress.is_paginating {
+                format!(
+                    "Looking for the messages of {}: {} found",
+                    redaction.user_id, progress.found
+                )
+            } else {
+                format!(
+                    "{} of {} messages of {} redacted, {} failed",
+                    progress.redacted, progress.found, redaction.user_id, progress.failed
+                )
+            };
+
+            Line::from(label).render(label_area, buffer);
+
+            let ratio = if progress.found == 0 {
+                if progress.is_done { 1.0 } else { 0.0 }
+            } else {
+                (progress.redacted + progress.failed) as f64 / progress.found as f64
+            };
+
+            Gauge::default()
+                .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+                .ratio(ratio.min(1.0))
+                .render(gauge_area, buffer);
+        }
+
+        let notice = if self.is_confirming {
+            self.selected().map(|user_id| {
+                Line::from(format!(
+                    "Redact the last {} messages of {user_id}? y/n",
+                    self.number_of_messages().unwrap_or_default()
+                ))
+                .yellow()
+            })
+        } else if let Some(error) = self
+            .redaction
+            .as_ref()
+            .and_then(|redaction| redaction.progress.lock().unwrap().error.clone())
+        {
+            Some(Line::from(error).red())
+        } else if let Some(notice) = &self.notice {
+            Some(Line::from(notice.as_str()).yellow())
+        } else if !self.can_redact {
+            Some(
+                Line::from("Your power level isn't high enough to redact the messages of others")
+                    .red(),
+            )
+        } else {
+            None
+        };
+
+        if let Some(notice) = notice {
+            Paragraph::new(notice).wrap(Wrap { trim: true }).render(notice_area, buffer);
+        }
+    }
+}
+
+/// Paginate backwards from the end of the room, on the homeserver, to find the
+/// last `number_of_messages` messages of `user_id`, then redact them one by
+/// one.
+async fn redact_task(
+    room: Room,
+    user_id: OwnedUserId,
+    number_of_messages: usize,
+    progress: Arc<Mutex<Progress>>,
+    input_sender: Sender<Input>,
+) {
+    progress.lock().unwrap().is_paginating = true;
+    let _ = input_sender.send(Input::Redraw).await;
+
+    let mut event_ids = Vec::<OwnedEventId>::new();
+    let mut from = None;
+
+    while event_ids.len() < number_of_messages {
+        let mut options = MessagesOptions::backward().from(from.as_deref());
+        options.limit = UInt::from(PAGINATION_LIMIT);
+        // Only the events of the member are needed.
+        options.filter.senders = Some(vec![user_id.clone()]);
+
+        let messages = match room.messages(options).await {
+            Ok(messages) => messages,
+            Err(error) => {
+                {
+                    let mut progress = progress.lock().unwrap();
+                    progress.error = Some(format!("failed to paginate: {error}"));
+                    progress.is_paginating = false;
+                    progress.is_done = true;
+                }
+
+                let _ = input_sender.send(Input::Redraw).await;
+
+                return;
+            }
+        };
+
+        for event in &messages.chunk {
+            if event_ids.len() == number_of_messages {
+                break;
+            }
+
+            // The state events, e.g. the memberships, the redactions and the
+            // events already redacted are skipped.
+            let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                continue;
+            };
+
+            if event.sender() == &*user_id
+                && event.event_type() != MessageLikeEventType::RoomRedaction
+                && event.original_content().is_some()
+            {
+                event_ids.push(event.event_id().to_owned());
+            }
+        }
+
+        progress.lock().unwrap().found = event_ids.len();
+        let _ = input_sender.send(Input::Redraw).await;
+
+        // The start of the room has been reached.
+        match messages.end {
+            Some(end) if !messages.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+
+    progress.lock().unwrap().is_paginating = false;
+
+    for event_id in event_ids {
+        let result = room.redact(&event_id, None, None).await;
+
+        {
+            let mut progress = progress.lock().unwrap();
+
+            match result {
+                Ok(_) => progress.redacted += 1,
+                Err(error) => {
+                    warn!("Failed to redact {event_id}: {error}");
+
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        let _ = input_sender.send(Input::Redraw).await;
+
+        sleep(REDACTION_INTERVAL).await;
+    }
+
+    {
+        let mut progress = progress.lock().unwrap();
+        progress.is_done = true;
+
+        info!("{} messages of {user_id} redacted, {} failed", progress.redacted, progress.failed);
+    }
+
+    let _ = input_sender.send(Input::Redraw).await;
+}
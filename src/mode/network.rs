@@ -0,0 +1,85 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{app, block::block_with_title, layout_debug, network};
+
+pub enum Message {
+    NextLatency,
+    NextFailureRate,
+}
+
+/// Change the conditions simulated by the network proxy.
+pub struct Model;
+
+impl Model {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::NextLatency => {
+                network::set_latency(next(&network::LATENCIES, network::latency()))
+            }
+            Message::NextFailureRate => {
+                network::set_failure_rate(next(&network::FAILURE_RATES, network::failure_rate()))
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(50)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(7)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("network", area);
+
+        let block = block_with_title("Simulated network");
+
+        if !network::is_running() {
+            Paragraph::new("Start with `--simulate-network` to simulate the network conditions.")
+                .wrap(Wrap { trim: true })
+                .yellow()
+                .block(block)
+                .render(area, buffer);
+
+            return;
+        }
+
+        let value = |value: String, is_degraded: bool| {
+            if is_degraded { Span::raw(value).red() } else { Span::raw(value).green() }
+        };
+
+        let latency = network::latency();
+        let failure_rate = network::failure_rate();
+
+        let text = Text::from(vec![
+            Line::from(vec![
+                Span::raw("latency       "),
+                value(format!("{latency} ms"), latency > 0),
+            ]),
+            Line::from(vec![
+                Span::raw("failure rate  "),
+                value(format!("{failure_rate}%"), failure_rate > 0),
+            ]),
+            Line::from(""),
+            Line::from("l: next latency · f: next failure rate").dark_gray(),
+        ]);
+
+        Paragraph::new(text).block(block).render(area, buffer);
+    }
+}
+
+/// The value after `current` in `values`, looping.
+fn next<T: Copy + PartialEq>(values: &[T], current: T) -> T {
+    let position = values.iter().position(|value| *value == current).unwrap_or(0);
+
+    values[(position + 1) % values.len()]
+}
@@ -0,0 +1,345 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Room,
+    ruma::{
+        Int, OwnedUserId,
+        events::{
+            StateEventType, TimelineEventType,
+            room::power_levels::{RoomPowerLevels, RoomPowerLevelsEventContent},
+        },
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Cell, Clear, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    Edit,
+    UpdateValue(KeyEvent),
+    ConfirmValue,
+    Preview,
+    CancelPreview,
+    Send,
+}
+
+/// A power level that can be edited.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Entry {
+    UsersDefault,
+    EventsDefault,
+    StateDefault,
+    Ban,
+    Invite,
+    Kick,
+    Redact,
+    Event(TimelineEventType),
+    User(OwnedUserId),
+}
+
+impl Entry {
+    fn label(&self) -> String {
+        match self {
+            Self::UsersDefault => "default for users".to_owned(),
+            Self::EventsDefault => "default for events".to_owned(),
+            Self::StateDefault => "default for state events".to_owned(),
+            Self::Ban => "ban".to_owned(),
+            Self::Invite => "invite".to_owned(),
+            Self::Kick => "kick".to_owned(),
+            Self::Redact => "redact".to_owned(),
+            Self::Event(event_type) => format!("event `{event_type}`"),
+            Self::User(user_id) => format!("user {user_id}"),
+        }
+    }
+
+    fn get(&self, power_levels: &RoomPowerLevels) -> Option<Int> {
+        Some(match self {
+            Self::UsersDefault => power_levels.users_default,
+            Self::EventsDefault => power_levels.events_default,
+            Self::StateDefault => power_levels.state_default,
+            Self::Ban => power_levels.ban,
+            Self::Invite => power_levels.invite,
+            Self::Kick => power_levels.kick,
+            Self::Redact => power_levels.redact,
+            Self::Event(event_type) => *power_levels.events.get(event_type)?,
+            Self::User(user_id) => *power_levels.users.get(user_id)?,
+        })
+    }
+
+    fn set(&self, power_levels: &mut RoomPowerLevels, value: Int) {
+        match self {
+            Self::UsersDefault => power_levels.users_default = value,
+            Self::EventsDefault => power_levels.events_default = value,
+            Self::StateDefault => power_levels.state_default = value,
+            Self::Ban => power_levels.ban = value,
+            Self::Invite => power_levels.invite = value,
+            Self::Kick => power_levels.kick = value,
+            Self::Redact => power_levels.redact = value,
+            Self::Event(event_type) => {
+                power_levels.events.insert(event_type.clone(), value);
+            }
+            Self::User(user_id) => {
+                power_levels.users.insert(user_id.clone(), value);
+            }
+        }
+    }
+}
+
+/// All the entries of `power_levels`, in a stable order.
+fn entries<'a>(power_levels: impl IntoIterator<Item = &'a RoomPowerLevels>) -> Vec<Entry> {
+    let mut entries = BTreeSet::from([
+        Entry::UsersDefault,
+        Entry::EventsDefault,
+        Entry::StateDefault,
+        Entry::Ban,
+        Entry::Invite,
+        Entry::Kick,
+        Entry::Redact,
+    ]);
+
+    for power_levels in power_levels {
+        entries.extend(power_levels.events.keys().cloned().map(Entry::Event));
+        entries.extend(power_levels.users.keys().cloned().map(Entry::User));
+    }
+
+    entries.into_iter().collect()
+}
+
+pub struct Model {
+    room: Room,
+    power_levels: Result<(RoomPowerLevels, RoomPowerLevels), String>,
+    can_edit: bool,
+    table_state: TableState,
+    value_textarea: Option<TextArea>,
+    is_previewing: bool,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub async fn new(room: Room) -> Self {
+        let power_levels = room.power_levels().await.map_err(|error| error.to_string());
+        let can_edit = match (&power_levels, room.client().user_id()) {
+            (Ok(power_levels), Some(own_user_id)) => {
+                power_levels.user_can_send_state(own_user_id, StateEventType::RoomPowerLevels)
+            }
+            _ => false,
+        };
+
+        Self {
+            room,
+            power_levels: power_levels.map(|power_levels| (power_levels.clone(), power_levels)),
+            can_edit,
+            table_state: TableState::default().with_selected(Some(0)),
+            value_textarea: None,
+            is_previewing: false,
+            notice: None,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.value_textarea.is_some()
+    }
+
+    pub fn is_previewing(&self) -> bool {
+        self.is_previewing
+    }
+
+    fn selected_entry(&self) -> Option<Entry> {
+        let (_, edited) = self.power_levels.as_ref().ok()?;
+
+        entries([edited]).into_iter().nth(self.table_state.selected()?)
+    }
+
+    /// The entries whose value has been edited, with their original and their
+    /// edited values.
+    fn changes(&self) -> Vec<(Entry, Option<Int>, Option<Int>)> {
+        let Ok((original, edited)) = &self.power_levels else {
+            return Vec::new();
+        };
+
+        entries([original, edited])
+            .into_iter()
+            .filter_map(|entry| {
+                let (before, after) = (entry.get(original), entry.get(edited));
+
+                (before != after).then_some((entry, before, after))
+            })
+            .collect()
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.table_state.select_previous(),
+            Message::MoveCursorDown => self.table_state.select_next(),
+            Message::Edit => {
+                if !self.can_edit {
+                    self.notice = Some("you are not allowed to change the power levels".to_owned());
+                } else if self.selected_entry().is_some() {
+                    self.value_textarea = Some(TextArea::new());
+                }
+            }
+            Message::UpdateValue(key_event) => {
+                if let Some(value_textarea) = &mut self.value_textarea {
+                    value_textarea.handle_input(key_event);
+                }
+            }
+            Message::ConfirmValue => {
+                let Some(value_textarea) = self.value_textarea.take() else { return None };
+                let value = value_textarea.input();
+                let selected_entry = self.selected_entry();
+
+                match (value.trim().parse::<Int>(), selected_entry, &mut self.power_levels) {
+                    (Ok(value), Some(entry), Ok((_, edited))) => {
+                        entry.set(edited, value);
+                        self.notice = None;
+                    }
+                    (Err(_), ..) => {
+                        self.notice = Some(format!("`{value}` is not a valid power level"));
+                    }
+                    _ => {}
+                }
+            }
+            Message::Preview => {
+                if self.changes().is_empty() {
+                    self.notice = Some("nothing has changed".to_owned());
+                } else {
+                    self.is_previewing = true;
+                }
+            }
+            Message::CancelPreview => {
+                self.is_previewing = false;
+            }
+            Message::Send => {
+                self.is_previewing = false;
+
+                let Ok((original, edited)) = &mut self.power_levels else { return None };
+
+                self.notice = Some(match RoomPowerLevelsEventContent::try_from(edited.clone()) {
+                    Ok(content) => match self.room.send_state_event(content).await {
+                        Ok(_) => {
+                            *original = edited.clone();
+
+                            "power levels sent".to_owned()
+                        }
+                        Err(error) => format!("failed to send the power levels: {error}"),
+                    },
+                    Err(error) => format!("invalid power levels: {error}"),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("power levels", area);
+
+        let title = match &self.notice {
+            Some(notice) => format!("Power levels — {notice}"),
+            None => "Power levels".to_owned(),
+        };
+        let block = block_with_title(&title);
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let (original, edited) = match &self.power_levels {
+            Ok((original, edited)) => (original, edited),
+            Err(error) => {
+                Paragraph::new(format!("Failed to load the power levels: {error}"))
+                    .style(Style::default().red())
+                    .render(inner_area, buffer);
+
+                return;
+            }
+        };
+
+        let [table_area, help_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)])
+                .areas(inner_area);
+
+        let help = if self.is_previewing {
+            "y: send · n: go back"
+        } else if self.is_editing() {
+            "Enter: confirm the new level · Esc: close"
+        } else if self.can_edit {
+            "↑↓: select · Enter: edit · s: preview the changes and send"
+        } else {
+            "↑↓: select · read-only, you are not allowed to change the power levels"
+        };
+
+        Line::styled(help, Style::default().dark_gray()).render(help_area, buffer);
+
+        if self.is_previewing {
+            let changed_style = Style::default().yellow();
+
+            Table::new(
+                self.changes().into_iter().map(|(entry, before, after)| {
+                    let format = |value: Option<Int>| {
+                        value.map_or_else(|| "unset".to_owned(), |value| value.to_string())
+                    };
+
+                    Row::new([
+                        Cell::new(entry.label()),
+                        Cell::new(format(before)),
+                        Cell::new(Span::raw("→")),
+                        Cell::new(Span::styled(format(after), changed_style)),
+                    ])
+                }),
+                [
+                    Constraint::Percentage(100),
+                    Constraint::Length(8),
+                    Constraint::Length(1),
+                    Constraint::Length(8),
+                ],
+            )
+            .header(Row::new(["Changes", "Before", "", "After"]).style(Style::default().bold()))
+            .render(table_area, buffer);
+
+            return;
+        }
+
+        let selected = self.table_state.selected();
+        let rows = entries([edited]).into_iter().enumerate().map(|(nth, entry)| {
+            let is_changed = entry.get(original) != entry.get(edited);
+            let value = match &self.value_textarea {
+                Some(value_textarea) if selected == Some(nth) => {
+                    format!("{}▏", value_textarea.input())
+                }
+                _ => entry.get(edited).map(|value| value.to_string()).unwrap_or_default(),
+            };
+
+            Row::new([
+                Cell::new(entry.label()),
+                Cell::new(value).style(if is_changed {
+                    Style::default().yellow()
+                } else {
+                    Style::default()
+                }),
+            ])
+        });
+
+        StatefulWidget::render(
+            Table::new(rows, [Constraint::Percentage(100), Constraint::Length(10)])
+                .header(Row::new(["Power level", "Value"]).style(Style::default().bold()))
+                .row_highlight_style(Style::new().bg(Color::DarkGray)),
+            table_area,
+            buffer,
+            &mut self.table_state,
+        );
+    }
+}
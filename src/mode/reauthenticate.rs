@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use crossterm::event::KeyEvent;
+use matrix_sdk::Client;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug};
+
+pub enum Message {
+    UpdatePassword(KeyEvent),
+    Submit,
+}
+
+/// Asks for the password when the server has invalidated the access token.
+pub struct Model {
+    client: Client,
+    session_path: PathBuf,
+    soft_logout: bool,
+    password_textarea: TextArea,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(client: Client, session_path: PathBuf, soft_logout: bool) -> Self {
+        Self {
+            client,
+            session_path,
+            soft_logout,
+            password_textarea: TextArea::new_masked(),
+            notice: None,
+        }
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdatePassword(key_event) => {
+                self.password_textarea.handle_input(key_event);
+            }
+            Message::Submit => {
+                // The device is gone, the session can't be restored.
+                if !self.soft_logout {
+                    return Some(app::Message::Logout);
+                }
+
+                let (Some(user_id), Some(device_id)) =
+                    (self.client.user_id(), self.client.device_id())
+                else {
+                    return Some(app::Message::Logout);
+                };
+
+                let password = self.password_textarea.input();
+                self.password_textarea.clear();
+
+                // Log in again with the same device, so that the stores can be
+                // kept.
+                let login = self
+                    .client
+                    .matrix_auth()
+                    .login_username(user_id, &password)
+                    .device_id(device_id.as_str())
+                    .request_refresh_token()
+                    .await;
+
+                if let Err(error) = login {
+                    self.notice = Some(format!("failed to log in: {error}"));
+
+                    return None;
+                }
+
+                if let Err(error) = crate::save_session(&self.client, &self.session_path) {
+                    self.notice = Some(format!("failed to save the session: {error}"));
+
+                    return None;
+                }
+
+                return Some(app::Message::Reauthenticated);
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(9)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("reauthenticate", area);
+
+        let block = block_with_title("Session expired");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [explanation_area, password_area, notice_area] = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        let explanation = if self.soft_logout {
+            "The server has logged this session out. Type your password to log in again with \
+             the same device, and press <Enter>."
+        } else {
+            "The server has removed this device. Press <Enter> to remove the session and quit, \
+             then start again to log in."
+        };
+
+        Paragraph::new(explanation).wrap(Wrap { trim: true }).render(explanation_area, buffer);
+
+        if self.soft_logout {
+            self.password_textarea.render(password_area, buffer);
+        }
+
+        if let Some(notice) = &self.notice {
+            Line::styled(notice.as_str(), Style::default().red()).render(notice_area, buffer);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use ratatui::{
     widgets::{Cell, Clear, Paragraph, Row, Table, Widget},
 };
 
-use crate::block::block_with_title;
+use crate::{block::block_with_title, layout_debug};
 
 pub struct Model {
     has_room_opened: bool,
@@ -19,15 +19,41 @@ impl Model {
     pub fn render(&self, area: Rect, buffer: &mut Buffer) {
         let rows = [
             Row::new([Cell::new("b"), Cell::new("Paginate backwards")]),
+            Row::new([Cell::new("B"), Cell::new("Toggle the pagination debug bar")]),
             Row::new([Cell::new("r"), Cell::new("Toggle reaction to last message")]),
             Row::new([Cell::new("s"), Cell::new("Goto start of timeline")]),
             Row::new([Cell::new("e"), Cell::new("Goto end of timeline")]),
+            Row::new([Cell::new("D"), Cell::new("Jump to a date")]),
             Row::new([Cell::new("t"), Cell::new("View timeline")]),
             Row::new([Cell::new("i"), Cell::new("View event ID")]),
             Row::new([Cell::new("o"), Cell::new("View event origin")]),
             Row::new([Cell::new("l"), Cell::new("View linked chunk")]),
+            Row::new([Cell::new("E"), Cell::new("View local echoes lifecycle")]),
+            Row::new([Cell::new("L"), Cell::new("View the diffs of the timeline")]),
+            Row::new([Cell::new("X"), Cell::new("Export the diffs of the timeline")]),
+            Row::new([Cell::new("O"), Cell::new("Toggle the ordering validation")]),
+            Row::new([Cell::new("C"), Cell::new("Compare the event cache with the timeline")]),
+            Row::new([Cell::new("u"), Cell::new("Decrypt last UTDs with backup")]),
+            Row::new([Cell::new("R"), Cell::new("View read receipts")]),
+            Row::new([Cell::new("N"), Cell::new("View who hasn't read the event")]),
+            Row::new([Cell::new("F"), Cell::new("View positions of read markers")]),
+            Row::new([Cell::new("G"), Cell::new("View relations of the event")]),
+            Row::new([Cell::new("V"), Cell::new("Explain the shield of the event")]),
+            Row::new([Cell::new("S"), Cell::new("Reveal or hide the spoiler")]),
+            Row::new([Cell::new("a"), Cell::new("Reply to the event, or cancel")]),
             Row::new([Cell::new("m"), Cell::new("Mark as read")]),
             Row::new([Cell::new("c"), Cell::new("Empty room event cache")]),
+            Row::new([Cell::new("P"), Cell::new("Edit power levels")]),
+            Row::new([Cell::new("M"), Cell::new("Redact the messages of a member")]),
+            Row::new([Cell::new("K"), Cell::new("Inspect the room keys")]),
+            Row::new([Cell::new("k"), Cell::new("Browse the inbound group sessions")]),
+            Row::new([Cell::new("d"), Cell::new("Dump the devices of a member")]),
+            Row::new([Cell::new("A"), Cell::new("View the state changes")]),
+            Row::new([Cell::new("f"), Cell::new("View the federation info")]),
+            Row::new([Cell::new("J"), Cell::new("Join the replacement room")]),
+            Row::new([Cell::new("U"), Cell::new("Upgrade the room (debug)")]),
+            Row::new([Cell::new("x"), Cell::new("Export the transcript")]),
+            Row::new([Cell::new("w"), Cell::new("Close the tab")]),
         ];
 
         let [_, area] = Layout::vertical([
@@ -39,6 +65,7 @@ impl Model {
             Layout::horizontal([Constraint::Percentage(100), Constraint::Min(39)]).areas(area);
 
         Clear.render(area, buffer);
+        layout_debug::register("room panel", area);
 
         let block = block_with_title("Room");
 
@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+use crossterm::event::KeyEvent;
+use matrix_sdk::Client;
+use ratatui::{buffer::Buffer, layout::Rect};
+use tracing::info;
+
+use crate::{app, secret_prompt::SecretPrompt};
+
+pub enum Message {
+    UpdateInput(KeyEvent),
+    Submit,
+}
+
+/// What to do with the room keys.
+pub enum Action {
+    /// Export all the room keys in the file at `path`, or in a timestamped file
+    /// if `None`.
+    Export {
+        path: Option<PathBuf>,
+    },
+    Import {
+        path: PathBuf,
+    },
+}
+
+/// Export the room keys to, or import them from, a file encrypted with a
+/// passphrase, in the key export format shared with the other clients.
+pub struct Model {
+    client: Client,
+    action: Action,
+    passphrase_prompt: SecretPrompt,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub fn new(client: Client, action: Action) -> Self {
+        let mut model =
+            Self { client, action, passphrase_prompt: SecretPrompt::new(), notice: None };
+        model.ask_for_passphrase();
+
+        model
+    }
+
+    /// Ask for a new passphrase for the export, twice, or for the passphrase
+    /// the file to import has been exported with.
+    fn ask_for_passphrase(&mut self) {
+        match self.action {
+            Action::Export { .. } => self.passphrase_prompt.ask_new("the passphrase"),
+            Action::Import { .. } => self.passphrase_prompt.ask("the passphrase"),
+        }
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.passphrase_prompt.is_open()
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateInput(key_event) => self.passphrase_prompt.handle_input(key_event),
+            Message::Submit => match self.passphrase_prompt.submit() {
+                Ok(Some(passphrase)) => self.run(&passphrase).await,
+                Ok(None) => self.notice = None,
+                Err(notice) => self.notice = Some(notice),
+            },
+        }
+
+        None
+    }
+
+    async fn run(&mut self, passphrase: &str) {
+        let encryption = self.client.encryption();
+
+        self.notice = Some(match &self.action {
+            Action::Export { path } => {
+                let path = path.clone().unwrap_or_else(|| {
+                    PathBuf::from(format!(
+                        "multiverse-room-keys-{}.txt",
+                        Local::now().format("%Y%m%d-%H%M%S")
+                    ))
+                });
+
+                match encryption.export_room_keys(path.clone(), passphrase, |_| true).await {
+                    Ok(()) => {
+                        let path = path.canonicalize().unwrap_or(path);
+
+                        info!("Room keys exported to `{}`", path.display());
+
+                        format!("room keys exported to `{}`", path.display())
+                    }
+                    Err(error) => format!("failed to export the room keys: {error}"),
+                }
+            }
+            Action::Import { path } => {
+                match encryption.import_room_keys(path.clone(), passphrase).await {
+                    Ok(result) => {
+                        info!(
+                            "{} of {} room keys imported from `{}`",
+                            result.imported_count,
+                            result.total_count,
+                            path.display()
+                        );
+
+                        format!(
+                            "{} of {} room keys imported, the others were known already",
+                            result.imported_count, result.total_count
+                        )
+                    }
+                    Err(error) => {
+                        // E.g. the passphrase is wrong: let the user type it again.
+                        self.ask_for_passphrase();
+
+                        format!("failed to import the room keys: {error}")
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let (title, explanation) = match &self.action {
+            Action::Export { .. } => (
+                "Export the room keys",
+                "All the room keys are written in a file encrypted with a passphrase, to be \
+                 imported by another client."
+                    .to_owned(),
+            ),
+            Action::Import { path } => (
+                "Import the room keys",
+                format!(
+                    "The room keys of `{}` are imported, with the passphrase the file has been \
+                     exported with.",
+                    path.display()
+                ),
+            ),
+        };
+
+        self.passphrase_prompt.render_popup(
+            "room keys",
+            title,
+            &explanation,
+            self.notice.as_deref(),
+            area,
+            buffer,
+        );
+    }
+}
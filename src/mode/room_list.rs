@@ -1,13 +1,22 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
 
 use as_variant::as_variant;
-use chrono::{DateTime, Local};
 use crossterm::event::KeyEvent;
 use futures::{StreamExt, pin_mut};
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use itertools::Itertools as _;
+use matrix_sdk::{Room, ruma::OwnedRoomId};
 use matrix_sdk_ui::{
     RoomListService,
     eyeball_im::{Vector, VectorDiff},
-    room_list_service::{RoomListDynamicEntriesController, RoomListItem, filters},
+    room_list_service::{
+        RoomListDynamicEntriesController, RoomListItem, RoomListLoadingState, filters,
+    },
     sync_service::SyncService,
     timeline::{LatestEventValue, LatestEventValueLocalState, RoomExt, TimelineDetails},
 };
@@ -16,52 +25,102 @@ use ratatui::{
     style::{Color, Style, Stylize},
     text::{Line, Span, Text},
     widgets::{
-        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        StatefulWidget, Widget,
     },
 };
 use tokio::{
-    spawn,
+    select, spawn,
     sync::{mpsc::Sender, oneshot},
 };
+use tracing::error;
 
 use crate::{
-    TextArea, app,
+    TextArea, app, avatar,
     block::{BORDER_STYLE, NO_PADDING, PADDING, block_with_title},
     input::Input,
-    task_ext::{AbortOnDrop, JoinHandleExt},
-    timeline::{self, render_timeline_item_content},
+    layout::{self, RoomListDensity},
+    layout_debug,
+    room::{self, display_name},
+    summary,
+    task_ext::{self, AbortOnDrop, JoinHandleExt},
+    time, timeline,
 };
 
 #[derive(Debug)]
 pub enum Message {
     UpdateFilter(KeyEvent),
     UpdateRoomList(Vec<VectorDiff<RoomListItem>>),
+    UpdateLoadingState(RoomListLoadingState),
     MoveCursorUp,
     MoveCursorDown,
     Select,
+    GrowPreview,
+    ShrinkPreview,
+    TogglePreview,
+    ToggleDensity,
+    PreviewReady(OwnedRoomId),
+}
+
+/// The number of placeholder rows shown while the room list is not loaded.
+const NUMBER_OF_SKELETON_ROWS: usize = 3;
+
+/// The number of rooms asked to the room list service at once. Another page is
+/// asked when the cursor gets close to the last loaded room.
+const PAGE_SIZE: usize = 100;
+
+/// The timeline of the room under the cursor, built in the background so that
+/// the cursor moves instantly.
+struct Preview {
+    room_id: OwnedRoomId,
+    /// Filled by the task once the timeline is built.
+    built_timeline: Arc<Mutex<Option<timeline::Model>>>,
+    /// `None` until the task is done.
+    timeline: Option<timeline::Model>,
+    /// The task is cancelled when the cursor moves to another room.
+    _handle: AbortOnDrop<()>,
 }
 
 pub struct Model {
     room_list_controller: RoomListDynamicEntriesController,
     _room_list_updates_handle: AbortOnDrop<()>,
     rooms: Vector<(RoomListItem, Arc<LatestEventValue>)>,
+    loading_state: RoomListLoadingState,
+    /// The indices in `rooms` in the displayed order: the best match first
+    /// when searching, the order of the service otherwise.
+    order: Vec<usize>,
+    matcher: SkimMatcherV2,
     list_state: ListState,
+    /// The number of rooms asked to the service, a multiple of `PAGE_SIZE`.
+    number_of_requested_rooms: usize,
+    /// The number of rooms fitting in the list, as of the last render.
+    number_of_visible_rooms: usize,
     search_textarea: TextArea,
-    selected_room_timeline: Option<timeline::Model>,
+    preview: Option<Preview>,
+    layout: layout::Config,
+    input_sender: Sender<Input>,
 }
 
 impl Model {
-    pub async fn new(sync_service: Arc<SyncService>, input_sender: Sender<Input>) -> Self {
+    pub async fn new(
+        sync_service: Arc<SyncService>,
+        layout: layout::Config,
+        input_sender: Sender<Input>,
+    ) -> Self {
         let room_list_service = sync_service.room_list_service();
 
         let (room_list_controller_sender, room_list_controller_receiver) = oneshot::channel();
 
-        let _room_list_updates_handle = spawn(room_list_updates_task(
-            room_list_service.clone(),
-            room_list_controller_sender,
-            input_sender,
-        ))
-        .abort_on_drop();
+        // Not restarted, the room list controller is sent once.
+        let _room_list_updates_handle = task_ext::supervise(
+            "room list updates",
+            input_sender.clone(),
+            room_list_updates_task(
+                room_list_service.clone(),
+                room_list_controller_sender,
+                input_sender.clone(),
+            ),
+        );
 
         let room_list_controller = room_list_controller_receiver.await.unwrap();
 
@@ -71,9 +130,16 @@ impl Model {
             room_list_controller,
             _room_list_updates_handle,
             rooms: Vector::new(),
+            loading_state: RoomListLoadingState::NotLoaded,
+            order: Vec::new(),
+            matcher: SkimMatcherV2::default().smart_case(),
             list_state: ListState::default(),
+            number_of_requested_rooms: PAGE_SIZE,
+            number_of_visible_rooms: 0,
             search_textarea: TextArea::new(),
-            selected_room_timeline: None,
+            preview: None,
+            layout,
+            input_sender,
         }
     }
 }
@@ -96,6 +162,14 @@ impl Model {
                             ],
                         )));
                     }
+
+                    // The new filter starts from the first page.
+                    self.room_list_controller.reset_to_one_page();
+                    self.number_of_requested_rooms = PAGE_SIZE;
+
+                    self.update_order();
+                    self.list_state.select_first();
+                    self.update_preview();
                 }
 
                 return None;
@@ -153,41 +227,134 @@ impl Model {
                     diff.apply(&mut self.rooms);
                 }
 
+                self.update_order();
+
                 if self.list_state.selected().is_none() {
                     self.list_state.select_first();
                 }
 
-                self.update_selected_room_timeline().await;
+                self.request_more_rooms_if_needed();
+                self.update_preview();
+
+                return None;
+            }
+            Message::UpdateLoadingState(loading_state) => {
+                self.loading_state = loading_state;
 
                 return None;
             }
             Message::MoveCursorUp => {
                 self.list_state.select_previous();
-                self.update_selected_room_timeline().await;
+                self.update_preview();
                 return None;
             }
             Message::MoveCursorDown => {
-                self.list_state.select_next();
-                self.update_selected_room_timeline().await;
+                if self.list_state.selected().is_none_or(|selected| selected + 1 < self.order.len())
+                {
+                    self.list_state.select_next();
+                }
+
+                self.request_more_rooms_if_needed();
+                self.update_preview();
                 return None;
             }
-            Message::Select => {
-                let Some((room, _)) = self.rooms.get(self.list_state.selected().unwrap_or(0))
-                else {
-                    return None;
-                };
+            Message::Select => app::Message::OpenRoom(self.selected_room()?.deref().clone(), None),
+            Message::GrowPreview => {
+                self.layout.grow_room_preview();
+                app::Message::UpdateLayout(self.layout.clone())
+            }
+            Message::ShrinkPreview => {
+                self.layout.shrink_room_preview();
+                app::Message::UpdateLayout(self.layout.clone())
+            }
+            Message::TogglePreview => {
+                self.layout.toggle_room_preview();
+                app::Message::UpdateLayout(self.layout.clone())
+            }
+            Message::ToggleDensity => {
+                self.layout.toggle_room_list_density();
+                app::Message::UpdateLayout(self.layout.clone())
+            }
+            Message::PreviewReady(room_id) => {
+                // The preview of another room may have been asked since.
+                if let Some(preview) =
+                    self.preview.as_mut().filter(|preview| preview.room_id == room_id)
+                {
+                    preview.timeline = preview.built_timeline.lock().unwrap().take();
+                }
 
-                app::Message::OpenRoom(room.deref().clone())
+                return None;
             }
         })
     }
 
-    pub async fn update_selected_room_timeline(&mut self) {
-        self.selected_room_timeline = match self.rooms.get(self.list_state.selected().unwrap_or(0))
-        {
-            Some((room, _)) => Some(timeline::Model::new(room, None).await),
-            None => None,
+    /// Build the preview of the room under the cursor in the background, unless
+    /// it's the one of the current preview. The previous build is cancelled.
+    fn update_preview(&mut self) {
+        let Some(room) = self.selected_room() else {
+            self.preview = None;
+
+            return;
         };
+
+        if self.preview.as_ref().is_some_and(|preview| preview.room_id == room.room_id()) {
+            return;
+        }
+
+        let room = room.deref().clone();
+        let built_timeline = Arc::new(Mutex::new(None));
+
+        self.preview = Some(Preview {
+            room_id: room.room_id().to_owned(),
+            built_timeline: built_timeline.clone(),
+            timeline: None,
+            _handle: spawn(preview_task(room, built_timeline, self.input_sender.clone()))
+                .abort_on_drop(),
+        });
+    }
+
+    /// Ask the next page of rooms to the service when the cursor is less than a
+    /// screen away from the last loaded room. Nothing is asked until the
+    /// previous page is received, or if all the rooms are loaded already.
+    fn request_more_rooms_if_needed(&mut self) {
+        let selected = self.list_state.selected().unwrap_or(0);
+
+        if self.rooms.len() >= self.number_of_requested_rooms
+            && selected + self.number_of_visible_rooms >= self.rooms.len()
+        {
+            self.room_list_controller.add_one_page();
+            self.number_of_requested_rooms += PAGE_SIZE;
+        }
+    }
+
+    /// The room under the cursor.
+    fn selected_room(&self) -> Option<&RoomListItem> {
+        let index = self.order.get(self.list_state.selected().unwrap_or(0))?;
+
+        self.rooms.get(*index).map(|(room, _)| room)
+    }
+
+    /// Sort the rooms by their fuzzy match score on the search term, the best
+    /// first, like fzf. The rooms with the same score, or all of them without
+    /// a search term, keep the order of the service.
+    fn update_order(&mut self) {
+        let search_term = self.search_textarea.input();
+        let mut order = self
+            .rooms
+            .iter()
+            .enumerate()
+            .map(|(index, (room, _))| {
+                let score = (!search_term.is_empty())
+                    .then(|| self.matcher.fuzzy_match(&display_name(room), &search_term))
+                    .flatten();
+
+                (score, index)
+            })
+            .collect::<Vec<_>>();
+
+        order.sort_by_key(|(score, _)| Reverse(*score));
+
+        self.order = order.into_iter().map(|(_, index)| index).collect();
     }
 
     pub fn render(&mut self, area: Rect, buffer: &mut ratatui::buffer::Buffer) {
@@ -197,17 +364,29 @@ impl Model {
 
         Clear.render(area, buffer);
 
-        let (list_area, preview_area) = if area.width < 80 {
+        let (list_area, preview_area) = if !self.layout.room_preview_is_visible(area.width) {
             (area, None)
         } else {
-            let [left, right] =
-                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-                    .areas(area);
+            let (list_percentage, preview_percentage) = self.layout.room_list_split();
+            let [left, right] = Layout::horizontal([
+                Constraint::Percentage(list_percentage),
+                Constraint::Percentage(preview_percentage),
+            ])
+            .areas(area);
 
             (left, Some(right))
         };
 
-        let list_block = block_with_title("Room list").padding(NO_PADDING);
+        let title = match &self.loading_state {
+            RoomListLoadingState::NotLoaded => "Room list — loading…".to_owned(),
+            RoomListLoadingState::Loaded { maximum_number_of_rooms: Some(maximum) } => {
+                format!("Room list — {} of {maximum} rooms", self.rooms.len())
+            }
+            RoomListLoadingState::Loaded { maximum_number_of_rooms: None } => {
+                format!("Room list — {} rooms", self.rooms.len())
+            }
+        };
+        let list_block = block_with_title(&title).padding(NO_PADDING);
 
         let [input_area, table_area] =
             Layout::vertical([Constraint::Length(1), Constraint::Percentage(100)])
@@ -215,10 +394,50 @@ impl Model {
 
         list_block.render(list_area, buffer);
 
+        layout_debug::register("room list", list_area);
+        layout_debug::register("room list search", input_area);
+        layout_debug::register("room list items", table_area);
+
         self.search_textarea.render(input_area.inner(Margin::new(1, 0)), buffer);
+        let search_term = self.search_textarea.input();
         const HIGHLIGHT_SYMBOL: &str = " > ";
+        // Where the avatar is rendered, with a space after it.
+        const AVATAR_MARGIN: &str = "     ";
+        let items_block =
+            Block::new().borders(Borders::TOP).border_style(BORDER_STYLE).padding(PADDING);
+        let is_compact = self.layout.room_list_density == RoomListDensity::Compact;
+        // The compact rooms, of one line, have no avatar.
+        let avatar_margin = if is_compact { "" } else { AVATAR_MARGIN };
+
+        // Only the visible rooms are built: the offset is maintained here, the
+        // way the `List` would, to keep the cursor on screen.
+        let items_area = items_block.inner(table_area);
+        let room_height = if is_compact { 1 } else { 2 };
+        self.number_of_visible_rooms = usize::from(items_area.height / room_height).max(1);
+
+        if let Some(selected) = self.list_state.selected() {
+            self.list_state.select(Some(selected.min(self.order.len().saturating_sub(1))));
+        }
+
+        let selected = self.list_state.selected().unwrap_or(0);
+        *self.list_state.offset_mut() = self
+            .list_state
+            .offset()
+            .min(selected)
+            .max((selected + 1).saturating_sub(self.number_of_visible_rooms));
+
+        let offset = self.list_state.offset();
+        let rooms = self
+            .order
+            .iter()
+            .skip(offset)
+            .take(self.number_of_visible_rooms)
+            .filter_map(|index| self.rooms.get(*index));
+        let mut visible_list_state = ListState::default()
+            .with_selected(self.list_state.selected().map(|selected| selected - offset));
+
         StatefulWidget::render(
-            List::new(self.rooms.iter().map(|(room, latest_event)| {
+            List::new(rooms.map(|(room, latest_event)| {
                 ListItem::new({
                     let mut output = Text::default();
 
@@ -228,105 +447,225 @@ impl Model {
                             .map(|display_name| display_name.to_string())
                             .unwrap_or_else(|| room.room_id().as_str().to_owned());
 
+                        let trailing = match latest_event.deref() {
+                            _ if is_compact => unread_badge(room),
+                            LatestEventValue::None => Span::raw("???"),
+                            LatestEventValue::Remote { timestamp, .. }
+                            | LatestEventValue::Local { timestamp, .. } => Span::raw(
+                                time::format(
+                                    timestamp.to_system_time().expect("invalid system time"),
+                                ),
+                            ),
+                        };
+
                         let spaces = str::repeat(
                             " ",
                             usize::from(table_area.width)
                                 .saturating_sub(room_name.len())
                                 .saturating_sub(HIGHLIGHT_SYMBOL.len())
+                                .saturating_sub(avatar_margin.len())
                                 .saturating_sub(
                                     1 /* borders */
                                         + usize::from(PADDING.left)
                                         + usize::from(PADDING.right),
                                 )
-                                .saturating_sub(5),
+                                .saturating_sub(trailing.width()),
                         );
 
-                        let time = match latest_event.deref() {
-                            LatestEventValue::None => Span::raw("???"),
-                            LatestEventValue::Remote { timestamp, .. }
-                            | LatestEventValue::Local { timestamp, .. } => Span::raw(
-                                DateTime::<Local>::from(
-                                    timestamp.to_system_time().expect("invalid system time"),
-                                )
-                                .format("%H:%M")
-                                .to_string(),
-                            ),
+                        // Highlight the matched characters of the name.
+                        let matched_indices = if search_term.is_empty() {
+                            HashSet::new()
+                        } else {
+                            self.matcher
+                                .fuzzy_indices(&room_name, &search_term)
+                                .map(|(_, indices)| indices.into_iter().collect())
+                                .unwrap_or_default()
                         };
 
-                        [room_name.bold(), spaces.into(), time]
+                        let mut spans = room_name
+                            .chars()
+                            .enumerate()
+                            .chunk_by(|(position, _)| matched_indices.contains(position))
+                            .into_iter()
+                            .map(|(is_matched, characters)| {
+                                let characters =
+                                    characters.map(|(_, character)| character).collect::<String>();
+
+                                if is_matched {
+                                    characters.bold().yellow()
+                                } else {
+                                    characters.bold()
+                                }
+                            })
+                            .collect::<Vec<_>>();
+
+                        spans.insert(0, avatar_margin.into());
+                        spans.extend([spaces.into(), trailing]);
+
+                        spans
                     }));
 
-                    output.push_line(Line::default().spans({
-                        let (sender, decoration, content) = match latest_event.deref() {
-                            LatestEventValue::None => {
-                                (Span::raw(""), "".into(), Span::raw("<none>"))
-                            }
-                            LatestEventValue::Remote { sender, profile, content, .. } => {
-                                let mut sender = as_variant!(profile, TimelineDetails::Ready(profile) => profile)
-                                    .and_then(|profile| profile.display_name.clone())
-                                    .unwrap_or_else(|| sender.localpart().to_owned());
-                                sender.push_str(": ");
-
-                                let content = render_timeline_item_content(content, &area).swap_remove(0);
-
-                                (sender.into(), "".into(), content)
-                            }
-                            LatestEventValue::Local { state, content, .. } => {
-                                let content = render_timeline_item_content(content, &area).swap_remove(0);
-
-                                (
-                                    Span::raw("Me: "),
-                                    Span::raw(match state {
+                    // The preview of the latest event.
+                    if !is_compact {
+                        output.push_line(Line::default().spans({
+                            let (decoration, summary) = match latest_event.deref() {
+                                LatestEventValue::None => ("", "<none>".to_owned()),
+                                LatestEventValue::Remote { sender, profile, content, .. } => {
+                                    let sender = as_variant!(profile, TimelineDetails::Ready(profile) => profile)
+                                        .and_then(|profile| profile.display_name.clone())
+                                        .unwrap_or_else(|| sender.localpart().to_owned());
+
+                                    ("", summary::summarize(&sender, content))
+                                }
+                                LatestEventValue::Local { state, content, .. } => (
+                                    match state {
                                         LatestEventValueLocalState::IsSending => "🕙 ",
                                         LatestEventValueLocalState::CannotBeSent => "❗️ ",
                                         LatestEventValueLocalState::HasBeenSent => "",
-                                    }),
-                                    content.into(),
-                                )
-                            }
-                        };
+                                    },
+                                    summary::summarize("Me", content),
+                                ),
+                            };
 
-                        [decoration, sender, content.italic()]
-                    }));
+                            [AVATAR_MARGIN.into(), decoration.into(), summary.italic()]
+                        }));
+                    }
 
                     output
                 })
             }))
             .highlight_style(Style::new().bg(Color::DarkGray))
             .highlight_symbol(" > ")
-            .block(Block::new().borders(Borders::TOP).border_style(BORDER_STYLE).padding(PADDING)),
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(items_block.clone()),
             table_area,
             buffer,
-            &mut self.list_state,
+            &mut visible_list_state,
         );
 
+        // The avatars are rendered over the margins of the visible rooms, 2
+        // lines each.
+        if !is_compact {
+            let visible_rooms = self
+                .order
+                .iter()
+                .skip(offset)
+                .take(self.number_of_visible_rooms)
+                .filter_map(|index| self.rooms.get(*index));
+
+            for ((room, _), y) in visible_rooms.zip((items_area.y..items_area.bottom()).step_by(2))
+            {
+                avatar::render(
+                    room,
+                    Rect {
+                        x: items_area.x + HIGHLIGHT_SYMBOL.len() as u16,
+                        y,
+                        width: avatar::WIDTH,
+                        height: avatar::HEIGHT.min(items_area.bottom() - y),
+                    },
+                    buffer,
+                );
+            }
+        }
+
+        // Distinguish “no rooms” from “still loading” while the list is empty.
+        if self.rooms.is_empty() {
+            let skeleton_area = table_area.inner(Margin::new(1, 1));
+            let text = match self.loading_state {
+                RoomListLoadingState::NotLoaded => Text::from(
+                    (0..NUMBER_OF_SKELETON_ROWS)
+                        .flat_map(|_| {
+                            [
+                                Line::from("░".repeat(usize::from(skeleton_area.width) / 2)),
+                                Line::from("░".repeat(usize::from(skeleton_area.width) / 3)),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .dark_gray(),
+                RoomListLoadingState::Loaded { .. } => Text::from("No rooms").dark_gray(),
+            };
+
+            Paragraph::new(text).render(skeleton_area.inner(Margin::new(PADDING.left, 0)), buffer);
+        }
+
         if let Some(preview_area) = preview_area {
             Paragraph::new("").block(block_with_title("Room preview")).render(preview_area, buffer);
 
             let preview_area = preview_area.inner(Margin { horizontal: 2, vertical: 1 });
             Clear.render(preview_area, buffer);
+            layout_debug::register("room preview", preview_area);
+
+            // The header of the room, to confirm the selection without opening
+            // the room.
+            let [header_area, timeline_area] =
+                Layout::vertical([Constraint::Length(4), Constraint::Percentage(100)])
+                    .areas(preview_area);
 
-            if let Some(timeline) = &self.selected_room_timeline {
-                timeline.render(preview_area, buffer);
+            if let Some(selected_room) = self.selected_room() {
+                room::render_header(selected_room, header_area, buffer);
+            }
+
+            match self.preview.as_ref().map(|preview| &preview.timeline) {
+                Some(Some(timeline)) => timeline.render(timeline_area, buffer),
+                Some(None) => Paragraph::new("Loading…").dark_gray().render(timeline_area, buffer),
+                None => {}
             }
         }
     }
 }
 
+/// The number of unread messages of `room`, in red if some mention the user.
+fn unread_badge(room: &RoomListItem) -> Span<'static> {
+    match (room.num_unread_messages(), room.num_unread_mentions()) {
+        (0, _) => Span::raw(""),
+        (unread, 0) => Span::raw(format!(" {unread} ")).black().on_gray(),
+        (unread, _) => Span::raw(format!(" {unread} ")).black().on_red().bold(),
+    }
+}
+
+/// Build the timeline of `room`, then tell the room list it's ready.
+async fn preview_task(
+    room: Room,
+    built_timeline: Arc<Mutex<Option<timeline::Model>>>,
+    input_sender: Sender<Input>,
+) {
+    let timeline = match timeline::Model::new(&room, None, None).await {
+        Ok(timeline) => timeline,
+        Err(error) => {
+            error!("Failed to build the timeline of the preview: {error}");
+
+            return;
+        }
+    };
+    *built_timeline.lock().unwrap() = Some(timeline);
+
+    let _ = input_sender.send(Input::RoomPreviewReady(room.room_id().to_owned())).await;
+}
+
 async fn room_list_updates_task(
     room_list_service: Arc<RoomListService>,
     room_list_controller_sender: oneshot::Sender<RoomListDynamicEntriesController>,
     input_sender: Sender<Input>,
 ) {
     let all_rooms = room_list_service.all_rooms().await.unwrap();
-    let (rooms_stream, room_list_controller) =
-        all_rooms.entries_with_dynamic_adapters(u16::MAX as usize);
+    let (rooms_stream, room_list_controller) = all_rooms.entries_with_dynamic_adapters(PAGE_SIZE);
 
     let _ = room_list_controller_sender.send(room_list_controller);
 
+    let mut loading_state = all_rooms.loading_state();
+
     pin_mut!(rooms_stream);
 
-    while let Some(diffs) = rooms_stream.next().await {
-        let _ = input_sender.send(Input::RoomListUpdate(diffs)).await;
+    loop {
+        select! {
+            Some(diffs) = rooms_stream.next() => {
+                let _ = input_sender.send(Input::RoomListUpdate(diffs)).await;
+            }
+            Some(loading_state) = loading_state.next() => {
+                let _ = input_sender.send(Input::RoomListLoadingStateUpdate(loading_state)).await;
+            }
+            else => break,
+        }
     }
 }
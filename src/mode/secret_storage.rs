@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+
+use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD};
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Client,
+    ruma::events::{GlobalAccountDataEventType, secret::request::SecretName},
+};
+use matrix_sdk_crypto::store::CryptoStore;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+use serde::Deserialize;
+
+use crate::{TextArea, app, block::block_with_title, crypto_store, layout_debug};
+
+pub enum Message {
+    ScrollUp,
+    ScrollDown,
+    EnterKey,
+    UpdateKey(KeyEvent),
+    SubmitKey,
+}
+
+/// The secrets the SDK stores in the secret storage.
+const SECRET_NAMES: [SecretName; 4] = [
+    SecretName::CrossSigningMasterKey,
+    SecretName::CrossSigningSelfSigningKey,
+    SecretName::CrossSigningUserSigningKey,
+    SecretName::RecoveryKey,
+];
+
+/// The content of the account data of a secret, i.e. the secret encrypted with
+/// each secret storage key.
+#[derive(Deserialize)]
+struct EncryptedSecret {
+    encrypted: BTreeMap<String, EncryptedData>,
+}
+
+#[derive(Deserialize)]
+struct EncryptedData {
+    ciphertext: String,
+}
+
+/// The content of the account data of a secret storage key.
+#[derive(Deserialize)]
+struct KeyDescription {
+    algorithm: String,
+    passphrase: Option<serde_json::Value>,
+}
+
+/// A secret stored in the account data.
+struct Secret {
+    name: SecretName,
+    /// The size of the encrypted blob, per ID of the key encrypting it, empty
+    /// if the secret is not stored.
+    blobs: BTreeMap<String, usize>,
+    /// Whether the crypto store has the secret, i.e. whether this device has
+    /// received or decrypted it already.
+    is_known_locally: bool,
+    /// Whether the secret can be decrypted with the key typed by the user,
+    /// `None` if no key has been typed.
+    decryption: Option<Result<(), String>>,
+}
+
+/// The secret storage of the account.
+struct Inventory {
+    default_key_id: Option<String>,
+    /// The description of each key encrypting a secret, `None` if the key is
+    /// not in the account data.
+    keys: BTreeMap<String, Option<KeyDescription>>,
+    secrets: Vec<Secret>,
+}
+
+/// List the secrets of the secret storage, a.k.a. 4S, the keys encrypting
+/// them, and whether they can be decrypted.
+pub struct Model {
+    client: Client,
+    inventory: Result<Inventory, String>,
+    is_entering_key: bool,
+    key_textarea: TextArea,
+    notice: Option<String>,
+    scroll_position: u16,
+}
+
+impl Model {
+    pub async fn new(client: Client) -> Self {
+        let inventory = load_inventory(&client).await;
+
+        Self {
+            client,
+            inventory,
+            is_entering_key: false,
+            key_textarea: TextArea::new_masked(),
+            notice: None,
+            scroll_position: 0,
+        }
+    }
+
+    pub fn is_entering_key(&self) -> bool {
+        self.is_entering_key
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::ScrollUp => self.scroll_position = self.scroll_position.saturating_sub(1),
+            Message::ScrollDown => self.scroll_position = self.scroll_position.saturating_add(1),
+            Message::EnterKey => self.is_entering_key = true,
+            Message::UpdateKey(key_event) => {
+                self.key_textarea.handle_input(key_event);
+            }
+            Message::SubmitKey => {
+                self.is_entering_key = false;
+
+                let key = self.key_textarea.input();
+                self.key_textarea = TextArea::new_masked();
+
+                self.notice = self.try_decrypt(key.trim()).await.err();
+            }
+        }
+
+        None
+    }
+
+    /// Decrypt each secret with the recovery key, or the passphrase, of the
+    /// default secret storage key.
+    async fn try_decrypt(&mut self, key: &str) -> Result<(), String> {
+        let inventory = self.inventory.as_mut().map_err(|error| error.clone())?;
+        let default_key_id = inventory.default_key_id.clone().ok_or("no default key")?;
+        let secret_store =
+            self.client
+                .encryption()
+                .secret_storage()
+                .open_secret_store(key)
+                .await
+                .map_err(|error| format!("failed to open the secret storage: {error}"))?;
+
+        for secret in &mut inventory.secrets {
+            secret.decryption = Some(if !secret.blobs.contains_key(&default_key_id) {
+                Err("not encrypted with the default key".to_owned())
+            } else {
+                match secret_store.get_secret(secret.name.clone()).await {
+                    Ok(Some(_)) => Ok(()),
+                    Ok(None) => Err("not found".to_owned()),
+                    Err(error) => Err(error.to_string()),
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("secret storage", area);
+
+        let block = block_with_title("Secret storage");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [inventory_area, prompt_area, notice_area, help_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(if self.is_entering_key { 4 } else { 0 }),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        let inventory = match &self.inventory {
+            Ok(inventory) => inventory,
+            Err(error) => {
+                Paragraph::new(format!("Failed to load the secret storage: {error}"))
+                    .red()
+                    .render(inventory_area, buffer);
+
+                return;
+            }
+        };
+
+        let mut text = Text::default();
+
+        text.push_line(Line::from("Keys").bold());
+
+        if inventory.keys.is_empty() {
+            text.push_line(Line::from("  no key encrypts a secret").dark_gray());
+        }
+
+        for (key_id, description) in &inventory.keys {
+            let is_default = inventory.default_key_id.as_ref() == Some(key_id);
+            let description = match description {
+                Some(description) => Span::raw(format!(
+                    "{}{}",
+                    description.algorithm,
+                    if description.passphrase.is_some() { ", with a passphrase" } else { "" }
+                )),
+                None => Span::raw("missing from the account data").red(),
+            };
+
+            text.push_line(Line::from(vec![
+                Span::raw(format!("  {key_id} ")),
+                if is_default { Span::raw("(default) ").green() } else { Span::raw("") },
+                description,
+            ]));
+        }
+
+        match &inventory.default_key_id {
+            Some(key_id) if !inventory.keys.contains_key(key_id) => text
+                .push_line(Line::from(format!("  {key_id} (default) encrypts no secret")).yellow()),
+            None => text.push_line(Line::from("  no default key").yellow()),
+            _ => {}
+        }
+
+        text.push_line("");
+        text.push_line(Line::from("Secrets").bold());
+
+        for secret in &inventory.secrets {
+            text.push_line(Line::from(vec![
+                Span::raw(format!("  {} ", secret.name)),
+                if secret.is_known_locally {
+                    Span::raw("known locally").green()
+                } else {
+                    Span::raw("unknown locally").yellow()
+                },
+            ]));
+
+            if secret.blobs.is_empty() {
+                text.push_line(Line::from("    not stored").dark_gray());
+            }
+
+            for (key_id, size) in &secret.blobs {
+                text.push_line(format!("    encrypted with {key_id}, {size} bytes"));
+            }
+
+            match &secret.decryption {
+                Some(Ok(())) => text.push_line(Line::from("    decrypted").green()),
+                Some(Err(error)) => {
+                    text.push_line(Line::from(format!("    not decrypted: {error}")).red())
+                }
+                None => {}
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll_position, 0))
+            .render(inventory_area, buffer);
+
+        if self.is_entering_key {
+            let [label_area, key_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(3)]).areas(prompt_area);
+
+            Line::from("Type the recovery key, or the passphrase, and press <Enter>:")
+                .render(label_area, buffer);
+            self.key_textarea.render(key_area, buffer);
+        }
+
+        if let Some(notice) = &self.notice {
+            Line::styled(notice.as_str(), Style::default().yellow()).render(notice_area, buffer);
+        }
+
+        Line::from("k: try to decrypt with a key").dark_gray().render(help_area, buffer);
+    }
+}
+
+async fn load_inventory(client: &Client) -> Result<Inventory, String> {
+    let account = client.account();
+
+    let default_key_id = account
+        .fetch_account_data(GlobalAccountDataEventType::SecretStorageDefaultKey)
+        .await
+        .map_err(|error| error.to_string())?
+        .and_then(|raw| raw.get_field::<String>("key").ok().flatten());
+
+    let crypto_store = crypto_store::get().ok_or_else(|| "no crypto store".to_owned())?;
+    let private_keys = client.encryption().cross_signing_status().await;
+    let private_keys = private_keys.as_ref();
+    let has_backup_key = crypto_store
+        .load_backup_keys()
+        .await
+        .map_err(|error| error.to_string())?
+        .decryption_key
+        .is_some();
+
+    let mut keys = BTreeMap::new();
+    let mut secrets = Vec::new();
+
+    for name in SECRET_NAMES {
+        let blobs = match account
+            .fetch_account_data(GlobalAccountDataEventType::from(name.as_str()))
+            .await
+            .map_err(|error| error.to_string())?
+        {
+            Some(raw) => raw
+                .deserialize_as::<EncryptedSecret>()
+                .map_err(|error| format!("invalid {name}: {error}"))?
+                .encrypted
+                .into_iter()
+                .map(|(key_id, data)| {
+                    // The ciphertext is encoded in unpadded base64.
+                    let size = STANDARD_NO_PAD
+                        .decode(data.ciphertext.trim_end_matches('='))
+                        .map_or(data.ciphertext.len(), |ciphertext| ciphertext.len());
+
+                    (key_id, size)
+                })
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        keys.extend(blobs.keys().map(|key_id| (key_id.clone(), None)));
+
+        let is_known_locally = match name {
+            SecretName::CrossSigningMasterKey => private_keys.is_some_and(|keys| keys.has_master),
+            SecretName::CrossSigningSelfSigningKey => {
+                private_keys.is_some_and(|keys| keys.has_self_signing)
+            }
+            SecretName::CrossSigningUserSigningKey => {
+                private_keys.is_some_and(|keys| keys.has_user_signing)
+            }
+            SecretName::RecoveryKey => has_backup_key,
+            _ => false,
+        };
+
+        secrets.push(Secret { name, blobs, is_known_locally, decryption: None });
+    }
+
+    for (key_id, description) in &mut keys {
+        *description = account
+            .fetch_account_data(GlobalAccountDataEventType::SecretStorageKey(key_id.clone()))
+            .await
+            .map_err(|error| error.to_string())?
+            .and_then(|raw| raw.deserialize_as::<KeyDescription>().ok());
+    }
+
+    Ok(Inventory { default_key_id, keys, secrets })
+}
@@ -7,40 +7,71 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     widgets::{Cell, Clear, Row, Table, Widget},
 };
-use tokio::sync::mpsc::Sender;
 
-use crate::{app, block::block_with_title, input::Input, mode};
+use crate::{
+    app, bandwidth,
+    block::block_with_title,
+    bus::{self, Destination},
+    debug_panel, layout_debug, mode, performance,
+};
 
 #[derive(Debug)]
 pub enum Message {
     OpenRoomList,
+    OpenDirectory,
+    OpenCrossSigning,
+    OpenSecretStorage,
+    OpenInbox,
+    OpenThirdPartyIds,
+    OpenMemory,
+    OpenTasks,
+    OpenNetwork,
     StartSyncService,
     StopSyncService,
+    ToggleOffline,
+    ToggleLowBandwidth,
     EmptyEventCache,
     OpenLogger,
+    ToggleLayoutDebug,
+    TogglePerformanceHud,
+    OpenDebugPanels,
+    ToggleSplitLogger,
+    Screenshot,
 }
 
 pub struct Model {
     client: Client,
     sync_service: Arc<SyncService>,
-    input_sender: Sender<Input>,
 }
 
 impl Model {
-    pub fn new(
-        client: Client,
-        sync_service: Arc<SyncService>,
-        input_sender: Sender<Input>,
-    ) -> Self {
-        Self { client, sync_service, input_sender }
+    pub fn new(client: Client, sync_service: Arc<SyncService>) -> Self {
+        Self { client, sync_service }
     }
 
     pub async fn update(&mut self, message: Message) -> Option<app::Message> {
         Some(match message {
-            Message::OpenRoomList => app::Message::Mode(app::Mode::RoomList(
-                mode::room_list::Model::new(self.sync_service.clone(), self.input_sender.clone())
-                    .await,
+            Message::OpenRoomList => navigate(Destination::RoomList),
+            Message::OpenDirectory => app::Message::Mode(app::Mode::Directory(
+                mode::directory::Model::new(self.client.clone()).await,
+            )),
+            Message::OpenCrossSigning => app::Message::Mode(app::Mode::CrossSigning(
+                mode::cross_signing::Model::new(self.client.clone()).await,
+            )),
+            Message::OpenSecretStorage => app::Message::Mode(app::Mode::SecretStorage(
+                mode::secret_storage::Model::new(self.client.clone()).await,
             )),
+            Message::OpenInbox => {
+                app::Message::Mode(app::Mode::Inbox(mode::inbox::Model::new(self.client.clone())))
+            }
+            Message::OpenThirdPartyIds => app::Message::Mode(app::Mode::ThirdPartyIds(
+                mode::third_party_ids::Model::new(self.client.clone()).await,
+            )),
+            Message::OpenMemory => navigate(Destination::Memory),
+            Message::OpenTasks => navigate(Destination::Tasks),
+            Message::OpenNetwork => {
+                app::Message::Mode(app::Mode::Network(mode::network::Model::new()))
+            }
             Message::StartSyncService => {
                 self.sync_service.start().await;
                 app::Message::Mode(app::Mode::None)
@@ -49,6 +80,11 @@ impl Model {
                 self.sync_service.stop().await;
                 app::Message::Mode(app::Mode::None)
             }
+            Message::ToggleOffline => app::Message::ToggleOffline,
+            Message::ToggleLowBandwidth => {
+                bandwidth::set_low(!bandwidth::is_low());
+                app::Message::Mode(app::Mode::None)
+            }
             Message::EmptyEventCache => {
                 self.client
                     .event_cache_store()
@@ -62,19 +98,39 @@ impl Model {
                     .unwrap();
                 app::Message::Mode(app::Mode::None)
             }
-            Message::OpenLogger => app::Message::Mode(app::Mode::Logger(mode::logger::Model::new(
-                self.input_sender.clone(),
-            ))),
+            Message::OpenLogger => navigate(Destination::Logger),
+            Message::ToggleLayoutDebug => app::Message::ToggleLayoutDebug,
+            Message::TogglePerformanceHud => debug_panel::open(performance::DEBUG_PANEL_NAME)?,
+            Message::OpenDebugPanels => {
+                app::Message::Mode(app::Mode::DebugPanels(mode::debug_panels::Model::new()))
+            }
+            Message::ToggleSplitLogger => app::Message::ToggleSplitLogger,
+            Message::Screenshot => app::Message::Screenshot,
         })
     }
 
     pub fn render(&self, area: Rect, buffer: &mut Buffer) {
         let rows = [
             Row::new([Cell::new("f"), Cell::new("Open room list")]),
+            Row::new([Cell::new("D"), Cell::new("Browse the room directory")]),
+            Row::new([Cell::new("X"), Cell::new("View the cross-signing status")]),
+            Row::new([Cell::new("Z"), Cell::new("Browse the secret storage")]),
+            Row::new([Cell::new("R"), Cell::new("View the verification and key requests")]),
+            Row::new([Cell::new("I"), Cell::new("Manage the emails and phone numbers")]),
+            Row::new([Cell::new("N"), Cell::new("Simulate network conditions")]),
             Row::new([Cell::new("S"), Cell::new("Start the sync service")]),
             Row::new([Cell::new("s"), Cell::new("Stop the sync service")]),
+            Row::new([Cell::new("o"), Cell::new("Toggle the forced offline mode")]),
+            Row::new([Cell::new("b"), Cell::new("Toggle the low-bandwidth mode")]),
             Row::new([Cell::new("c"), Cell::new("Empty all room event caches")]),
             Row::new([Cell::new("l"), Cell::new("Open logger")]),
+            Row::new([Cell::new("v"), Cell::new("Toggle room and logger split")]),
+            Row::new([Cell::new("d"), Cell::new("Toggle layout debug")]),
+            Row::new([Cell::new("H"), Cell::new("Toggle the performance HUD")]),
+            Row::new([Cell::new("P"), Cell::new("Switch between the debug panels")]),
+            Row::new([Cell::new("M"), Cell::new("View the memory usage")]),
+            Row::new([Cell::new("T"), Cell::new("View the background tasks")]),
+            Row::new([Cell::new("p"), Cell::new("Save a screenshot")]),
         ];
 
         let [_, area] =
@@ -84,6 +140,7 @@ impl Model {
             Layout::horizontal([Constraint::Percentage(100), Constraint::Min(35)]).areas(area);
 
         Clear.render(area, buffer);
+        layout_debug::register("space panel", area);
 
         Table::default()
             .rows(rows)
@@ -92,3 +149,7 @@ impl Model {
             .render(area, buffer);
     }
 }
+
+fn navigate(destination: Destination) -> app::Message {
+    app::Message::Bus(bus::Command::Navigate(destination))
+}
@@ -0,0 +1,169 @@
+use crossterm::event::KeyEvent;
+use matrix_sdk::{Client, Room};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug, room::display_name};
+
+pub enum Message {
+    UpdateSearch(KeyEvent),
+    MoveCursorUp,
+    MoveCursorDown,
+    Select,
+}
+
+/// A popup to jump to a joined room by typing a part of its name.
+pub struct Model {
+    /// All the joined rooms, with their name, sorted by name.
+    rooms: Vec<(Room, String)>,
+    /// The indices in `rooms` of the rooms matching the search, best match
+    /// first.
+    matches: Vec<usize>,
+    list_state: ListState,
+    search_textarea: TextArea,
+}
+
+impl Model {
+    pub fn new(client: &Client) -> Self {
+        let mut rooms = client
+            .joined_rooms()
+            .into_iter()
+            .map(|room| {
+                let name = display_name(&room);
+
+                (room, name)
+            })
+            .collect::<Vec<_>>();
+        rooms.sort_by_cached_key(|(_, name)| name.to_lowercase());
+
+        let mut new = Self {
+            rooms,
+            matches: Vec::new(),
+            list_state: ListState::default(),
+            search_textarea: TextArea::new(),
+        };
+        new.update_matches();
+
+        new
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::UpdateSearch(key_event) => {
+                if self.search_textarea.handle_input(key_event) {
+                    self.update_matches();
+                }
+            }
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::Select => {
+                let index = self.matches.get(self.list_state.selected().unwrap_or(0))?;
+                let (room, _) = &self.rooms[*index];
+
+                return Some(app::Message::OpenRoom(room.clone(), None));
+            }
+        }
+
+        None
+    }
+
+    fn update_matches(&mut self) {
+        let search = self.search_textarea.input().to_lowercase();
+        let mut matches = self
+            .rooms
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, name))| Some((fuzzy_score(&search, name)?, index)))
+            .collect::<Vec<_>>();
+
+        // The sort is stable: rooms with the same score stay sorted by name.
+        matches.sort_by_key(|(score, _)| *score);
+
+        self.matches = matches.into_iter().map(|(_, index)| index).collect();
+        self.list_state.select_first();
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("switcher", area);
+
+        let block = block_with_title("Jump to a room");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [search_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Percentage(100)])
+                .areas(inner_area);
+
+        self.search_textarea.render(search_area.inner(Margin::new(1, 0)), buffer);
+
+        if self.matches.is_empty() {
+            Line::styled("No room matches", Style::default().dark_gray())
+                .render(list_area.inner(Margin::new(1, 1)), buffer);
+
+            return;
+        }
+
+        StatefulWidget::render(
+            List::new(self.matches.iter().map(|index| {
+                let (room, name) = &self.rooms[*index];
+
+                ListItem::new(Line::from(vec![
+                    Span::raw(name.as_str()).bold(),
+                    Span::raw(" "),
+                    Span::raw(room.room_id().as_str()).dark_gray(),
+                ]))
+            }))
+            .highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol(" > "),
+            list_area.inner(Margin::new(0, 1)),
+            buffer,
+            &mut self.list_state,
+        );
+    }
+}
+
+/// Whether all the characters of `search` (lowercase) appear in order in
+/// `name`. Return a score, lower is better: the number of skipped characters
+/// between the first and the last matched ones, then the position of the first
+/// one, so that `gen` ranks `General` before `Design engineering`.
+fn fuzzy_score(search: &str, name: &str) -> Option<(usize, usize)> {
+    if search.is_empty() {
+        return Some((0, 0));
+    }
+
+    let name = name.to_lowercase();
+    let mut search_chars = search.chars().peekable();
+    let mut first = None;
+    let mut last = 0;
+
+    for (position, character) in name.chars().enumerate() {
+        if search_chars.peek() == Some(&character) {
+            search_chars.next();
+            first.get_or_insert(position);
+            last = position;
+
+            if search_chars.peek().is_none() {
+                break;
+            }
+        }
+    }
+
+    if search_chars.peek().is_some() {
+        return None;
+    }
+
+    let first = first?;
+    let skipped = last - first + 1 - search.chars().count();
+
+    Some((skipped, first))
+}
@@ -0,0 +1,71 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Cell, Clear, Row, Table, Widget},
+};
+
+use crate::{block::block_with_title, layout_debug, task_ext};
+
+/// List the supervised background tasks, and why they have terminated.
+pub struct Model;
+
+impl Model {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let tasks = task_ext::tasks();
+
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(tasks.len() as u16 * 2 + 3)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("tasks", area);
+
+        let block = block_with_title("Background tasks");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let header = Row::new(["Task", "State", "Since", "Restarts"]).bold();
+        let rows = tasks.iter().map(|task| {
+            let state = if task.is_running {
+                Span::raw("running").green()
+            } else {
+                Span::raw("terminated").red().bold()
+            };
+            let last_failure = match &task.last_failure {
+                Some((time, failure)) => {
+                    Line::from(format!("  {}: {failure}", time.format("%H:%M:%S"))).red()
+                }
+                None => Line::default(),
+            };
+
+            Row::new([
+                Cell::new(vec![Line::from(task.name), last_failure]),
+                Cell::new(state),
+                Cell::new(task.started_at.format("%H:%M:%S").to_string()),
+                Cell::new(task.number_of_restarts.to_string()),
+            ])
+            .height(2)
+        });
+
+        Table::new(
+            rows,
+            [
+                Constraint::Percentage(100),
+                Constraint::Length(11),
+                Constraint::Length(9),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .render(inner_area, buffer);
+    }
+}
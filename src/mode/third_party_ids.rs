@@ -0,0 +1,412 @@
+use chrono::{DateTime, Local};
+use crossterm::event::KeyEvent;
+use matrix_sdk::{
+    Client,
+    reqwest::header::CONTENT_TYPE,
+    ruma::{
+        ClientSecret, OwnedClientSecret, OwnedSessionId,
+        api::client::uiaa::{AuthData, Password, UserIdentifier},
+        thirdparty::{Medium, ThirdPartyIdentifier},
+        uint,
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{TextArea, app, block::block_with_title, layout_debug, secret_prompt::SecretPrompt};
+
+pub enum Message {
+    MoveCursorUp,
+    MoveCursorDown,
+    AddEmail,
+    AddPhoneNumber,
+    Remove,
+    ConfirmRemove,
+    CancelRemove,
+    UpdateInput(KeyEvent),
+    Submit,
+}
+
+/// A 3PID whose ownership is being validated, before being added to the
+/// account.
+struct Validation {
+    client_secret: OwnedClientSecret,
+    sid: OwnedSessionId,
+}
+
+/// What is waiting for the input.
+enum Prompt {
+    /// The email address, or the phone number, to add.
+    Address { medium: Medium },
+    /// The token received by the user, to submit to `submit_url`.
+    Token { validation: Validation, submit_url: String },
+    /// The homeserver, or the identity server, validates the token itself, e.g.
+    /// with a link in an email: the user tells when it's done.
+    Validated { validation: Validation },
+    /// The password of the user-interactive authentication.
+    Password { validation: Validation, session: Option<String> },
+}
+
+/// List the email addresses and the phone numbers of the account, i.e. the
+/// third-party identifiers, and add or remove them.
+pub struct Model {
+    client: Client,
+    third_party_ids: Result<Vec<ThirdPartyIdentifier>, String>,
+    list_state: ListState,
+    prompt: Option<Prompt>,
+    input_textarea: TextArea,
+    password_prompt: SecretPrompt,
+    is_confirming_removal: bool,
+    notice: Option<String>,
+}
+
+impl Model {
+    pub async fn new(client: Client) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+
+        let mut model = Self {
+            client,
+            third_party_ids: Ok(Vec::new()),
+            list_state,
+            prompt: None,
+            input_textarea: TextArea::new(),
+            password_prompt: SecretPrompt::new(),
+            is_confirming_removal: false,
+            notice: None,
+        };
+        model.load().await;
+
+        model
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.is_confirming_removal
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    async fn load(&mut self) {
+        self.third_party_ids = match self.client.account().get_3pids().await {
+            Ok(response) => Ok(response.threepids),
+            Err(error) => Err(error.to_string()),
+        };
+    }
+
+    fn selected(&self) -> Option<&ThirdPartyIdentifier> {
+        self.third_party_ids.as_ref().ok()?.get(self.list_state.selected()?)
+    }
+
+    pub async fn update(&mut self, message: Message) -> Option<app::Message> {
+        match message {
+            Message::MoveCursorUp => self.list_state.select_previous(),
+            Message::MoveCursorDown => self.list_state.select_next(),
+            Message::AddEmail => self.prompt_for(Prompt::Address { medium: Medium::Email }),
+            Message::AddPhoneNumber => self.prompt_for(Prompt::Address { medium: Medium::Msisdn }),
+            Message::Remove => self.is_confirming_removal = self.selected().is_some(),
+            Message::CancelRemove => self.is_confirming_removal = false,
+            Message::ConfirmRemove => {
+                self.is_confirming_removal = false;
+
+                let ThirdPartyIdentifier { address, medium, .. } = self.selected()?.clone();
+
+                self.notice = match self.client.account().delete_3pid(&address, medium, None).await
+                {
+                    Ok(_) => Some(format!("{address} removed")),
+                    Err(error) => Some(format!("failed to remove {address}: {error}")),
+                };
+                self.load().await;
+            }
+            Message::UpdateInput(key_event) => {
+                if self.password_prompt.is_open() {
+                    self.password_prompt.handle_input(key_event);
+                } else {
+                    self.input_textarea.handle_input(key_event);
+                }
+            }
+            Message::Submit if self.password_prompt.is_open() => {
+                let Some(Prompt::Password { validation, session }) = self.prompt.take() else {
+                    return None;
+                };
+
+                match self.password_prompt.submit() {
+                    Ok(Some(password)) => {
+                        self.notice = None;
+
+                        let user_id = self.client.user_id()?.to_string();
+                        let mut password =
+                            Password::new(UserIdentifier::UserIdOrLocalpart(user_id), password);
+                        password.session = session;
+
+                        self.add(validation, Some(AuthData::Password(password))).await;
+                    }
+                    Ok(None) => self.prompt = Some(Prompt::Password { validation, session }),
+                    Err(notice) => {
+                        self.notice = Some(notice);
+                        self.prompt = Some(Prompt::Password { validation, session });
+                    }
+                }
+            }
+            Message::Submit => {
+                let prompt = self.prompt.take()?;
+                let input = self.input_textarea.input().trim().to_owned();
+                self.input_textarea = TextArea::new();
+                self.notice = None;
+
+                match prompt {
+                    Prompt::Address { medium } => self.request_token(medium, &input).await,
+                    Prompt::Token { validation, submit_url } => {
+                        match submit_token(&self.client, &validation, &submit_url, &input).await {
+                            Ok(()) => self.add(validation, None).await,
+                            Err(error) => {
+                                self.notice = Some(format!("failed to submit the token: {error}"));
+                                // Let the user type the token again.
+                                self.prompt_for(Prompt::Token { validation, submit_url });
+                            }
+                        }
+                    }
+                    Prompt::Validated { validation } => self.add(validation, None).await,
+                    // Submitted through the password prompt.
+                    Prompt::Password { .. } => {}
+                }
+            }
+        }
+
+        None
+    }
+
+    fn prompt_for(&mut self, prompt: Prompt) {
+        if let Prompt::Password { .. } = prompt {
+            self.password_prompt.ask("your password");
+        } else {
+            self.input_textarea = TextArea::new();
+        }
+        self.prompt = Some(prompt);
+    }
+
+    /// Ask the homeserver to send a validation token to `address`, i.e. an
+    /// email address, or a country code and a phone number.
+    async fn request_token(&mut self, medium: Medium, address: &str) {
+        let account = self.client.account();
+        let client_secret = ClientSecret::new();
+
+        let response = match medium {
+            Medium::Email => account
+                .request_3pid_email_token(&client_secret, address, uint!(1))
+                .await
+                .map(|response| (response.sid, response.submit_url)),
+            _ => {
+                let Some((country, phone_number)) = address.split_once(char::is_whitespace) else {
+                    self.notice = Some(
+                        "type the country code, then the number, e.g. `FR 612345678`".to_owned(),
+                    );
+                    self.prompt_for(Prompt::Address { medium });
+
+                    return;
+                };
+
+                account
+                    .request_3pid_msisdn_token(
+                        &client_secret,
+                        &country.to_uppercase(),
+                        phone_number.trim(),
+                        uint!(1),
+                    )
+                    .await
+                    .map(|response| (response.sid, response.submit_url))
+            }
+        };
+
+        match response {
+            Ok((sid, submit_url)) => {
+                let validation = Validation { client_secret, sid };
+
+                self.prompt_for(match submit_url {
+                    Some(submit_url) => Prompt::Token { validation, submit_url },
+                    None => Prompt::Validated { validation },
+                });
+            }
+            // E.g. the address is already used, or denied by the homeserver.
+            Err(error) => {
+                self.notice = Some(format!("failed to request a validation token: {error}"));
+                self.prompt_for(Prompt::Address { medium });
+            }
+        }
+    }
+
+    /// Add the validated 3PID to the account, and ask for the password if the
+    /// homeserver wants the user-interactive authentication, again if it was
+    /// wrong.
+    async fn add(&mut self, validation: Validation, auth_data: Option<AuthData>) {
+        let result = self
+            .client
+            .account()
+            .add_3pid(&validation.client_secret, &validation.sid, auth_data)
+            .await;
+
+        match result {
+            Ok(_) => {
+                self.notice = Some("added".to_owned());
+                self.load().await;
+            }
+            Err(error) => match error.as_uiaa_response() {
+                Some(uiaa_info) => {
+                    self.notice = uiaa_info.auth_error.as_ref().map(|auth_error| {
+                        format!("failed to authenticate: {}", auth_error.message)
+                    });
+                    let session = uiaa_info.session.clone();
+
+                    self.prompt_for(Prompt::Password { validation, session });
+                }
+                // E.g. the validation link hasn't been opened yet.
+                None => {
+                    self.notice = Some(format!("failed to add: {error}"));
+                    self.prompt_for(Prompt::Validated { validation });
+                }
+            },
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buffer: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(70)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(16)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register("3pids", area);
+
+        let block = block_with_title("Email addresses and phone numbers");
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [list_area, help_area, prompt_area, notice_area] = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(1),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        match &self.third_party_ids {
+            Ok(third_party_ids) if third_party_ids.is_empty() => {
+                Line::styled("No email address nor phone number", Style::default().dark_gray())
+                    .render(list_area.inner(Margin::new(1, 0)), buffer);
+            }
+            Ok(third_party_ids) => {
+                StatefulWidget::render(
+                    List::new(third_party_ids.iter().map(|third_party_id| {
+                        let medium = match third_party_id.medium {
+                            Medium::Email => "email",
+                            Medium::Msisdn => "phone",
+                            _ => third_party_id.medium.as_str(),
+                        };
+                        let added_at = third_party_id.added_at.to_system_time().map_or_else(
+                            || "???".to_owned(),
+                            |time| DateTime::<Local>::from(time).format("%Y-%m-%d").to_string(),
+                        );
+
+                        ListItem::new(Line::from(vec![
+                            Span::raw(format!("{medium:<6}")).dark_gray(),
+                            Span::raw(third_party_id.address.clone()).bold(),
+                            Span::raw(format!(" added on {added_at}")).dark_gray(),
+                        ]))
+                    }))
+                    .highlight_style(Style::new().bg(Color::DarkGray))
+                    .highlight_symbol(" > "),
+                    list_area,
+                    buffer,
+                    &mut self.list_state,
+                );
+            }
+            Err(error) => {
+                Line::from(format!(
+                    "failed to load the email addresses and phone numbers: {error}"
+                ))
+                .red()
+                .render(list_area, buffer);
+            }
+        }
+
+        Line::from("e: add an email address · p: add a phone number · d: remove")
+            .dark_gray()
+            .render(help_area, buffer);
+
+        if self.is_confirming_removal {
+            if let Some(third_party_id) = self.selected() {
+                Paragraph::new(format!(
+                    "Remove {} from the account? It's unbound from the identity server too. y/n",
+                    third_party_id.address
+                ))
+                .wrap(Wrap { trim: true })
+                .style(Style::default().yellow())
+                .render(prompt_area, buffer);
+            }
+        } else if let Some(prompt) = &self.prompt {
+            let [label_area, input_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(3)]).areas(prompt_area);
+
+            let label = match prompt {
+                Prompt::Address { medium: Medium::Email } => {
+                    Some("Type the email address, and press <Enter>:")
+                }
+                Prompt::Address { .. } => {
+                    Some("Type the country code, then the phone number, and press <Enter>:")
+                }
+                Prompt::Token { .. } => Some("Type the validation token you received:"),
+                Prompt::Validated { .. } => {
+                    Some("Follow the instructions you received, then press <Enter>")
+                }
+                // The password prompt has its own label.
+                Prompt::Password { .. } => None,
+            };
+
+            match label {
+                Some(label) => {
+                    Line::from(label).render(label_area, buffer);
+
+                    if !matches!(prompt, Prompt::Validated { .. }) {
+                        self.input_textarea.render(input_area, buffer);
+                    }
+                }
+                None => self.password_prompt.render(prompt_area, buffer),
+            }
+        }
+
+        if let Some(notice) = &self.notice {
+            Line::styled(notice.as_str(), Style::default().yellow()).render(notice_area, buffer);
+        }
+    }
+}
+
+/// Submit the validation token to the URL given by the homeserver, with the
+/// deprecated `submitToken` API.
+async fn submit_token(
+    client: &Client,
+    validation: &Validation,
+    submit_url: &str,
+    token: &str,
+) -> Result<(), matrix_sdk::reqwest::Error> {
+    let body = serde_json::json!({
+        "sid": validation.sid,
+        "client_secret": validation.client_secret,
+        "token": token,
+    });
+
+    client
+        .http_client()
+        .post(submit_url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    io,
+    net::SocketAddr,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        TcpListener, TcpStream,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+    spawn,
+    time::sleep,
+};
+use tracing::{debug, error};
+
+/// The latencies to cycle through, in milliseconds.
+pub const LATENCIES: [u64; 5] = [0, 200, 1_000, 5_000, 30_000];
+/// The failure rates to cycle through, in percent.
+pub const FAILURE_RATES: [u8; 5] = [0, 10, 25, 50, 100];
+
+/// The maximum size of the head of a request to the proxy.
+const MAXIMUM_HEAD_SIZE: usize = 8 * 1024;
+const CHUNK_SIZE: usize = 16 * 1024;
+
+static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+static LATENCY: AtomicU64 = AtomicU64::new(0);
+static FAILURE_RATE: AtomicU8 = AtomicU8::new(0);
+
+/// Whether the proxy is running, i.e. if the conditions can be simulated.
+pub fn is_running() -> bool {
+    IS_RUNNING.load(Ordering::Relaxed)
+}
+
+/// The latency added to the requests, in milliseconds.
+pub fn latency() -> u64 {
+    LATENCY.load(Ordering::Relaxed)
+}
+
+pub fn set_latency(latency: u64) {
+    LATENCY.store(latency, Ordering::Relaxed);
+}
+
+/// The percentage of the requests failing.
+pub fn failure_rate() -> u8 {
+    FAILURE_RATE.load(Ordering::Relaxed)
+}
+
+pub fn set_failure_rate(failure_rate: u8) {
+    FAILURE_RATE.store(failure_rate.min(100), Ordering::Relaxed);
+}
+
+/// Start the proxy simulating bad network conditions on a local port. Return
+/// its URL, to be given to the client.
+///
+/// The HTTPS requests are tunnelled with `CONNECT`, so the proxy only sees
+/// bytes: the latency is added to each chunk sent by the client, and a failure
+/// closes the connection, which fails the request in flight.
+pub async fn start_proxy() -> io::Result<String> {
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    spawn(async move {
+                        if let Err(error) = handle_connection(stream).await {
+                            debug!("Simulated network connection closed: {error}");
+                        }
+                    });
+                }
+                Err(error) => error!("Failed to accept a connection to the proxy: {error}"),
+            }
+        }
+    });
+
+    IS_RUNNING.store(true, Ordering::Relaxed);
+
+    Ok(url)
+}
+
+async fn handle_connection(mut client: TcpStream) -> io::Result<()> {
+    let head = read_head(&mut client).await?;
+    let request_line = head.lines().next().unwrap_or_default().to_owned();
+    let mut words = request_line.split_whitespace();
+
+    let (method, target) = (words.next().unwrap_or_default(), words.next().unwrap_or_default());
+
+    let mut server = if method == "CONNECT" {
+        let server = TcpStream::connect(target).await?;
+        client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n").await?;
+
+        server
+    } else {
+        // A plain HTTP request, with an absolute URI: forward it as is.
+        let authority = target
+            .strip_prefix("http://")
+            .and_then(|rest| rest.split('/').next())
+            .ok_or_else(|| io::Error::other(format!("unsupported request `{request_line}`")))?;
+        let authority =
+            if authority.contains(':') { authority.to_owned() } else { format!("{authority}:80") };
+
+        let mut server = TcpStream::connect(authority).await?;
+        server.write_all(head.as_bytes()).await?;
+
+        server
+    };
+
+    server.set_nodelay(true)?;
+
+    let (client_reader, client_writer) = client.into_split();
+    let (server_reader, server_writer) = server.into_split();
+
+    // The requests are delayed, and may fail; the responses are passed as is.
+    tokio::try_join!(
+        forward(client_reader, server_writer, true),
+        forward(server_reader, client_writer, false),
+    )?;
+
+    Ok(())
+}
+
+/// Read the head of the request to the proxy, until the empty line.
+async fn read_head(client: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut byte = [0; 1];
+
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > MAXIMUM_HEAD_SIZE || client.read(&mut byte).await? == 0 {
+            return Err(io::Error::other("invalid request head"));
+        }
+
+        head.push(byte[0]);
+    }
+
+    String::from_utf8(head).map_err(io::Error::other)
+}
+
+async fn forward(
+    mut reader: OwnedReadHalf,
+    mut writer: OwnedWriteHalf,
+    is_degraded: bool,
+) -> io::Result<()> {
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    loop {
+        let length = reader.read(&mut chunk).await?;
+
+        if length == 0 {
+            return writer.shutdown().await;
+        }
+
+        if is_degraded {
+            let latency = latency();
+
+            if latency > 0 {
+                sleep(Duration::from_millis(latency)).await;
+            }
+
+            if fails(failure_rate()) {
+                return Err(io::Error::other("simulated failure"));
+            }
+        }
+
+        writer.write_all(&chunk[..length]).await?;
+    }
+}
+
+/// Whether a request fails, `failure_rate` percent of the time.
+fn fails(failure_rate: u8) -> bool {
+    // Each `RandomState` is seeded differently, no need for a random crate.
+    failure_rate > 0 && RandomState::new().hash_one(()) % 100 < u64::from(failure_rate)
+}
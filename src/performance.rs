@@ -0,0 +1,123 @@
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Clear, Paragraph, Widget},
+};
+
+use crate::{
+    block::block_with_title,
+    debug_panel::{self, Hook, Panel},
+};
+
+/// The name of the debug panel of the HUD.
+pub const DEBUG_PANEL_NAME: &str = "performance HUD";
+
+/// The number of frames the statistics are computed over.
+const NUMBER_OF_SAMPLES: usize = 120;
+
+static STATS: Mutex<Stats> = Mutex::new(Stats::new());
+
+const STYLE: Style = Style::new().fg(Color::Cyan);
+
+struct Samples(VecDeque<Duration>);
+
+impl Samples {
+    const fn new() -> Self {
+        Self(VecDeque::new())
+    }
+
+    fn push(&mut self, duration: Duration) {
+        if self.0.len() == NUMBER_OF_SAMPLES {
+            self.0.pop_front();
+        }
+
+        self.0.push_back(duration);
+    }
+
+    /// The last, the average, and the maximum durations, in milliseconds.
+    fn format(&self) -> String {
+        let Some(last) = self.0.back() else { return "-".to_owned() };
+        let average = self.0.iter().sum::<Duration>() / self.0.len() as u32;
+        let maximum = self.0.iter().max().unwrap_or(last);
+
+        format!(
+            "{:>6.1} {:>6.1} {:>6.1}",
+            last.as_secs_f64() * 1000.0,
+            average.as_secs_f64() * 1000.0,
+            maximum.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+struct Stats {
+    render_times: Samples,
+    input_latencies: Samples,
+    /// The number of inputs waiting in the input channel, the last time one
+    /// has been received.
+    input_backlog: usize,
+    maximum_input_backlog: usize,
+}
+
+impl Stats {
+    const fn new() -> Self {
+        Self {
+            render_times: Samples::new(),
+            input_latencies: Samples::new(),
+            input_backlog: 0,
+            maximum_input_backlog: 0,
+        }
+    }
+}
+
+/// Record the time to render, and to draw, a frame.
+pub fn record_render_time(duration: Duration) {
+    STATS.lock().unwrap().render_times.push(duration);
+}
+
+/// Record the time between the reception of an input and the end of the
+/// redraw it has caused.
+pub fn record_input_latency(duration: Duration) {
+    STATS.lock().unwrap().input_latencies.push(duration);
+}
+
+/// Record the number of inputs waiting in the input channel.
+pub fn record_input_backlog(input_backlog: usize) {
+    let mut stats = STATS.lock().unwrap();
+    stats.input_backlog = input_backlog;
+    stats.maximum_input_backlog = stats.maximum_input_backlog.max(input_backlog);
+}
+
+/// Register the HUD as an overlay in the debug panels.
+pub fn register_debug_panel() {
+    debug_panel::register(Panel { name: DEBUG_PANEL_NAME, key: 'H', hook: Hook::Render(render) });
+}
+
+/// Draw the statistics in the top right corner of `area`. They are the ones of
+/// the previous frames.
+pub fn render(area: Rect, buffer: &mut Buffer) {
+    let stats = STATS.lock().unwrap();
+
+    let [area] = Layout::horizontal([Constraint::Length(44)]).flex(Flex::End).areas(area);
+    let [area] = Layout::vertical([Constraint::Length(6)]).flex(Flex::Start).areas(area);
+
+    Clear.render(area, buffer);
+
+    Paragraph::new(vec![
+        Line::styled(format!("{:<17}{:>7}{:>7}{:>7}", "ms", "last", "avg", "max"), STYLE),
+        Line::styled(format!("{:<17}{}", "render", stats.render_times.format()), STYLE),
+        Line::styled(format!("{:<17}{}", "input → redraw", stats.input_latencies.format()), STYLE),
+        Line::styled(
+            format!(
+                "{:<17}{:>6} inputs, {} at most",
+                "input backlog", stats.input_backlog, stats.maximum_input_backlog
+            ),
+            STYLE,
+        ),
+    ])
+    .block(block_with_title("Performance"))
+    .render(area, buffer);
+}
@@ -0,0 +1,101 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The profile picked at startup, so that it isn't asked again when the
+/// configuration is reloaded.
+static PICKED_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// `$XDG_DATA_HOME/multiverse`, or `~/.local/share/multiverse`: the directory
+/// of the profiles, each with its own session.
+fn profiles_directory() -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+    })?;
+
+    Some(data_home.join("multiverse"))
+}
+
+/// The names of the existing profiles, sorted.
+fn list(profiles_directory: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(profiles_directory) else {
+        return Vec::new();
+    };
+    let mut profiles = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    profiles.sort();
+
+    profiles
+}
+
+/// The name of the profile of `server_name`: its host, e.g. `matrix.org` for
+/// `https://matrix.org/`, as a server name can be a homeserver URL.
+fn default_profile(server_name: &str) -> String {
+    let host = server_name.split_once("://").map_or(server_name, |(_, rest)| rest);
+
+    host.split(['/', '?', '#']).next().unwrap_or_default().to_owned()
+}
+
+/// The session path of `profile`. Without a profile, the only existing one is
+/// used, or one is picked interactively if there are several, or else the
+/// profile is named after the homeserver, e.g. `matrix.org`.
+pub fn session_path(profile: Option<String>, server_name: &str) -> Result<PathBuf, String> {
+    let profiles_directory = profiles_directory().ok_or_else(|| "no home directory".to_owned())?;
+    let profile = match profile.or_else(|| PICKED_PROFILE.get().cloned()) {
+        Some(profile) => profile,
+        None => match list(&profiles_directory).as_slice() {
+            // Not kept as picked: the homeserver may be changed on the login
+            // screen, and the profile with it.
+            [] => default_profile(server_name),
+            [profile] => profile.clone(),
+            profiles => {
+                let profile = pick(profiles).map_err(|error| error.to_string())?;
+
+                PICKED_PROFILE.get_or_init(|| profile).clone()
+            }
+        },
+    };
+
+    if profile.is_empty() || profile.starts_with('.') || profile.contains(['/', '\\']) {
+        return Err(format!("invalid profile name `{profile}`"));
+    }
+
+    Ok(profiles_directory.join(profile))
+}
+
+/// Ask which profile to use, by number or by name; a new name creates a new
+/// profile.
+fn pick(profiles: &[String]) -> io::Result<String> {
+    println!("Profiles:");
+
+    for (index, profile) in profiles.iter().enumerate() {
+        println!("  {}. {profile}", index + 1);
+    }
+
+    loop {
+        print!("\nProfile, by number or by name (1 by default): ");
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        let answer = answer.trim();
+
+        if answer.is_empty() {
+            return Ok(profiles[0].clone());
+        }
+
+        match answer.parse::<usize>() {
+            Ok(number) => match number.checked_sub(1).and_then(|index| profiles.get(index)) {
+                Some(profile) => return Ok(profile.clone()),
+                None => println!("There is no profile {number}"),
+            },
+            Err(_) => return Ok(answer.to_owned()),
+        }
+    }
+}
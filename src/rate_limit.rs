@@ -0,0 +1,129 @@
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use matrix_sdk::{
+    Client,
+    ruma::api::client::error::{ErrorKind, RetryAfter},
+};
+use tokio::{
+    spawn,
+    sync::{broadcast::error::RecvError, mpsc::Sender},
+    time::{Instant, interval, timeout_at},
+};
+use tracing::{info, warn};
+
+use crate::input::Input;
+
+/// The delay before retrying, when the homeserver doesn't give one.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// The rate-limited actions waiting to be retried, and when.
+static WAITING_ACTIONS: Mutex<Vec<(&'static str, Instant)>> = Mutex::new(Vec::new());
+
+/// The delay asked by the homeserver if `error` is `M_LIMIT_EXCEEDED`.
+pub fn retry_after(error: &matrix_sdk::Error) -> Option<Duration> {
+    match error.client_api_error_kind()? {
+        ErrorKind::LimitExceeded { retry_after } => Some(match retry_after {
+            Some(RetryAfter::Delay(delay)) => *delay,
+            Some(RetryAfter::DateTime(time)) => {
+                time.duration_since(SystemTime::now()).unwrap_or_default()
+            }
+            None => DEFAULT_RETRY_AFTER,
+        }),
+        _ => None,
+    }
+}
+
+/// The status of the next rate-limited action to be retried, e.g. `rate
+/// limited, retrying the read receipt in 3s`.
+pub fn status() -> Option<String> {
+    let waiting_actions = WAITING_ACTIONS.lock().unwrap();
+    let (action, at) = waiting_actions.iter().min_by_key(|(_, at)| *at)?;
+    let seconds = at.saturating_duration_since(Instant::now()).as_secs_f64().ceil();
+
+    Some(format!("rate limited, retrying the {action} in {seconds}s"))
+}
+
+/// Wait `delay` before retrying `action`, redrawing every second for the
+/// countdown of the status.
+async fn wait(action: &'static str, delay: Duration, input_sender: &Sender<Input>) {
+    let at = Instant::now() + delay;
+    let mut countdown = interval(Duration::from_secs(1));
+
+    info!("Rate limited, retrying the {action} in {delay:?}");
+    WAITING_ACTIONS.lock().unwrap().push((action, at));
+
+    while Instant::now() < at {
+        let _ = timeout_at(at, countdown.tick()).await;
+        let _ = input_sender.send(Input::Redraw).await;
+    }
+
+    let mut waiting_actions = WAITING_ACTIONS.lock().unwrap();
+
+    if let Some(index) = waiting_actions.iter().position(|waiting| *waiting == (action, at)) {
+        waiting_actions.remove(index);
+    }
+}
+
+/// Run `request`, and run it again after the delay asked by the homeserver as
+/// long as it's rate-limited. The other errors are returned.
+pub async fn retry<T, F, Fut>(
+    action: &'static str,
+    input_sender: &Sender<Input>,
+    mut request: F,
+) -> Result<T, matrix_sdk::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, matrix_sdk::Error>>,
+{
+    loop {
+        match request().await {
+            Err(error) => match retry_after(&error) {
+                Some(delay) => wait(action, delay, input_sender).await,
+                None => return Err(error),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// The send queue of a room is disabled when it's rate-limited, with the
+/// messages and the reactions kept in the queue: enable it again after the
+/// delay asked by the homeserver, to send them. If the client has been forced
+/// offline meanwhile, the queue is left disabled: going back online enables it.
+pub async fn send_queue_errors_task(client: Client, input_sender: Sender<Input>) {
+    let mut errors = client.send_queue().subscribe_errors();
+
+    loop {
+        let error = match errors.recv().await {
+            Ok(error) => error,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+
+        let Some(delay) = retry_after(&error.error).filter(|_| error.is_recoverable) else {
+            continue;
+        };
+
+        let Some(room) = client.get_room(&error.room_id) else {
+            warn!("Rate limited in the unknown room {}", error.room_id);
+            continue;
+        };
+
+        let client = client.clone();
+        let input_sender = input_sender.clone();
+
+        spawn(async move {
+            wait("message", delay, &input_sender).await;
+
+            if client.send_queue().is_enabled() {
+                room.send_queue().set_enabled(true);
+            } else {
+                info!("Forced offline, the send queue of {} stays disabled", room.room_id());
+            }
+        });
+    }
+}
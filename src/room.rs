@@ -1,65 +1,410 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
 use crossterm::event::{KeyCode, KeyEvent};
+use futures::{StreamExt, pin_mut, stream};
 use matrix_sdk::{
-    Room,
+    ComposerDraft, ComposerDraftType, EncryptionState, Room, RoomMemberships,
+    encryption::VerificationState,
     ruma::{
-        api::client::receipt::create_receipt::v3::ReceiptType,
-        events::room::message::RoomMessageEventContent,
+        OwnedEventId, OwnedRoomId, OwnedUserId, RoomVersionId,
+        api::client::{receipt::create_receipt::v3::ReceiptType, room::upgrade_room},
+        events::{Mentions, room::message::RoomMessageEventContent},
     },
 };
+use matrix_sdk_ui::timeline::{Error as TimelineError, Profile, Timeline, TimelineDetails};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Margin, Rect},
-    style::{Modifier, Style, Styled},
-    text::Line,
-    widgets::Widget,
+    style::{Color, Modifier, Style, Styled, Stylize},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use tokio::{
+    spawn,
+    sync::mpsc::Sender,
+    time::{Instant, interval, timeout_at},
+};
+use tracing::{error, info};
+
+use crate::{
+    TextArea, app, avatar, bandwidth,
+    completion::{Candidate, Completion},
+    input::Input,
+    layout_debug, mode, rate_limit,
+    slash_command::SlashCommand,
+    spell_check,
+    task_ext::{self, AbortOnDrop, JoinHandleExt},
+    timeline::{self, summarize_timeline_item_content},
+    toast,
 };
-use tokio::sync::mpsc::Sender;
 
-use crate::{TextArea, app, input::Input, timeline};
+static DRAFTS_ARE_PERSISTENT: AtomicBool = AtomicBool::new(false);
+
+/// The delay before sending a message, in seconds, to undo it.
+static SEND_DELAY: AtomicU64 = AtomicU64::new(0);
+
+/// Save the drafts in the state store too, so that they survive a restart.
+pub fn persist_drafts(is_enabled: bool) {
+    DRAFTS_ARE_PERSISTENT.store(is_enabled, Ordering::Relaxed);
+}
+
+/// Wait `seconds` before sending each message, during which it can be undone.
+/// `0` sends them immediately.
+pub fn set_send_delay(seconds: u64) {
+    SEND_DELAY.store(seconds, Ordering::Relaxed);
+}
 
 pub enum Message {
     UpdateMessage(KeyEvent),
+    /// Insert a pasted text in the composer, without sending it on a newline.
+    PasteMessage(String),
+    /// Suggest the corrections of the word before the cursor.
+    SuggestSpellings,
     SendMessage,
+    SendDelayedMessage,
+    /// Cancel the message waiting for the send delay, and give it back.
+    UndoSend,
     Timeline(timeline::Message),
+    /// Compare the events of the event cache with the ones of the timeline.
+    CheckEventCache,
     MarkAsRead,
     EmptyEventCache,
+    OpenPowerLevels,
+    OpenModeration,
+    OpenCrypto,
+    OpenDevices,
+    OpenInboundSessions,
+    OpenAuditLog,
+    OpenFederation,
+    OpenJumpToDate,
+    /// Reply to the selected event with the next message, or cancel the reply.
+    ToggleReply,
+    JoinSuccessorRoom,
+    Upgrade,
+    UpdateInfo,
+    OpenExport,
+}
+
+/// What a completion in the composer completes.
+enum Completed {
+    Member(OwnedUserId),
+    Emoji,
+    Spelling,
+}
+
+/// The event the composed message replies to.
+struct ReplyingTo {
+    event_id: OwnedEventId,
+    sender: String,
+    summary: Span<'static>,
+}
+
+/// A message waiting for the send delay to elapse.
+struct DelayedMessage {
+    message: String,
+    mentions: Vec<(OwnedUserId, String)>,
+    replying_to: Option<ReplyingTo>,
+    send_at: Instant,
+    _handle: AbortOnDrop<()>,
 }
 
 pub struct Model {
     room: Room,
     timeline: timeline::Model,
     message_textarea: TextArea,
+    members: Vec<(OwnedUserId, Option<String>)>,
+    completion: Option<Completion<Completed>>,
+    mentions: Vec<(OwnedUserId, String)>,
+    replying_to: Option<ReplyingTo>,
+    delayed_message: Option<DelayedMessage>,
+    input_sender: Sender<Input>,
+    _info_updates_handle: AbortOnDrop<()>,
 }
 
 impl Model {
-    pub async fn new(room: Room, input_sender: Sender<Input>) -> Self {
-        let timeline = timeline::Model::new(&room, Some(input_sender)).await;
+    pub async fn new(
+        room: Room,
+        focused_event_id: Option<OwnedEventId>,
+        draft: Option<String>,
+        input_sender: Sender<Input>,
+    ) -> Result<Self, TimelineError> {
+        let timeline =
+            timeline::Model::new(&room, focused_event_id, Some(input_sender.clone())).await?;
+        let members = room
+            .members_no_sync(RoomMemberships::JOIN)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|member| {
+                (member.user_id().to_owned(), member.display_name().map(ToOwned::to_owned))
+            })
+            .collect();
+
+        let draft = match draft {
+            Some(draft) => Some(draft),
+            None if DRAFTS_ARE_PERSISTENT.load(Ordering::Relaxed) => {
+                match room.load_composer_draft(None).await {
+                    Ok(draft) => draft.map(|draft| draft.plain_text),
+                    Err(error) => {
+                        error!("Failed to load the draft: {error}");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        let mut message_textarea = TextArea::new_with_border();
+
+        if let Some(draft) = draft {
+            message_textarea.set_input(&draft);
+        }
+
+        let _info_updates_handle =
+            task_ext::supervise_with_restart("room info updates", input_sender.clone(), {
+                let room = room.clone();
+                let input_sender = input_sender.clone();
+
+                move || info_updates_task(room.clone(), input_sender.clone())
+            });
 
-        Self { room, timeline, message_textarea: TextArea::new_with_border() }
+        Ok(Self {
+            room,
+            timeline,
+            message_textarea,
+            members,
+            completion: None,
+            mentions: Vec::new(),
+            replying_to: None,
+            delayed_message: None,
+            input_sender,
+            _info_updates_handle,
+        })
+    }
+
+    pub fn timeline(&self) -> &timeline::Model {
+        &self.timeline
+    }
+
+    pub fn room(&self) -> &Room {
+        &self.room
+    }
+
+    async fn run_slash_command(&mut self, message: &str) -> Result<(), String> {
+        match message.parse::<SlashCommand>()? {
+            SlashCommand::Send(content) => {
+                spawn(send_task(
+                    self.timeline.timeline.clone(),
+                    content,
+                    None,
+                    self.input_sender.clone(),
+                ));
+                self.timeline.update(timeline::Message::Scroll(timeline::Scroll::End)).await;
+            }
+            SlashCommand::React(key) => {
+                self.timeline.update(timeline::Message::ToggleReactionOnLastMessage(key)).await;
+            }
+            SlashCommand::Invite(user_id) => {
+                self.room.invite_user_by_id(&user_id).await.map_err(|error| error.to_string())?;
+                info!("{user_id} has been invited");
+            }
+            SlashCommand::Topic(topic) => {
+                self.room.set_room_topic(&topic).await.map_err(|error| error.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The message being typed, not sent yet.
+    pub fn draft(&self) -> String {
+        self.message_textarea.input()
+    }
+
+    /// Save the draft in the state store, if the drafts are persistent.
+    pub async fn save_draft(&self) {
+        if !DRAFTS_ARE_PERSISTENT.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let draft = self.draft();
+        let result = if draft.is_empty() {
+            self.room.clear_composer_draft(None).await
+        } else {
+            self.room
+                .save_composer_draft(
+                    ComposerDraft {
+                        plain_text: draft,
+                        html_text: None,
+                        draft_type: ComposerDraftType::NewMessage,
+                        attachments: Vec::new(),
+                    },
+                    None,
+                )
+                .await
+        };
+
+        if let Err(error) = result {
+            error!("Failed to save the draft: {error}");
+        }
     }
 
     pub async fn update(&mut self, message: Message) -> Option<app::Message> {
         match message {
             Message::UpdateMessage(key_event) => {
+                if let Some(completion) = &mut self.completion {
+                    match key_event.code {
+                        KeyCode::Up => {
+                            completion.select_previous();
+                            return None;
+                        }
+                        KeyCode::Down => {
+                            completion.select_next();
+                            return None;
+                        }
+                        _ => {}
+                    }
+                }
+
+                if key_event.code == KeyCode::Tab {
+                    if let Some(completion) = self.completion.take() {
+                        let candidate = completion.accept(&mut self.message_textarea);
+
+                        if let Completed::Member(user_id) = candidate.data {
+                            self.mentions
+                                .push((user_id, candidate.replacement.trim_end().to_owned()));
+                        }
+
+                        return None;
+                    }
+                }
+
                 if key_event.code == KeyCode::Enter {
                     return Some(app::Message::Room(Message::SendMessage));
                 }
 
                 self.message_textarea.handle_input(key_event);
+                self.message_textarea.highlight_misspelled_words();
+                self.update_completion();
+
+                return None;
+            }
+            Message::PasteMessage(text) => {
+                self.message_textarea.paste(&text);
+                self.message_textarea.highlight_misspelled_words();
+                self.update_completion();
+
+                return None;
+            }
+            Message::SuggestSpellings => {
+                self.completion = Completion::new(
+                    spell_check::suggest(self.message_textarea.word_before_cursor())
+                        .into_iter()
+                        .map(|suggestion| Candidate {
+                            label: suggestion.clone(),
+                            replacement: suggestion,
+                            data: Completed::Spelling,
+                        }),
+                );
+
+                return None;
+            }
+            Message::SendDelayedMessage => {
+                // A newer message may have replaced the one whose delay elapsed.
+                let Some(delayed_message) = self
+                    .delayed_message
+                    .take_if(|delayed_message| delayed_message.send_at <= Instant::now())
+                else {
+                    return None;
+                };
+
+                self.send_message(
+                    delayed_message.message,
+                    delayed_message.mentions,
+                    delayed_message.replying_to,
+                );
 
+                // Keep the current mode.
                 return None;
             }
+            Message::UndoSend => {
+                let Some(delayed_message) = self.delayed_message.take() else {
+                    return None;
+                };
+
+                // Give the message back, before what has been typed since.
+                let typed = self.message_textarea.input();
+                self.message_textarea.set_input(&if typed.is_empty() {
+                    delayed_message.message
+                } else {
+                    format!("{}\n{typed}", delayed_message.message)
+                });
+                self.mentions.extend(delayed_message.mentions);
+                self.replying_to = delayed_message.replying_to;
+
+                return Some(app::Message::Mode(app::Mode::Insert));
+            }
             Message::SendMessage => {
                 let message = self.message_textarea.input();
 
                 self.message_textarea.clear();
+                self.completion = None;
+
+                if message.starts_with('/') {
+                    self.mentions.clear();
+
+                    if let Err(error) = self.run_slash_command(&message).await {
+                        error!("Failed to run `{message}`: {error}");
+
+                        // Give the message back, to fix it.
+                        self.message_textarea.set_input(&message);
+                    }
+
+                    return None;
+                }
+
+                // Keep the mentions whose text hasn't been removed.
+                let mentions = self
+                    .mentions
+                    .drain(..)
+                    .filter(|(_, text)| message.contains(text.as_str()))
+                    .collect::<Vec<_>>();
 
                 if message.len() > 0 {
-                    self.timeline
-                        .timeline
-                        .send(RoomMessageEventContent::text_plain(message).into())
-                        .await
-                        .unwrap();
+                    let replying_to = self.replying_to.take();
+                    let delay = Duration::from_secs(SEND_DELAY.load(Ordering::Relaxed));
+
+                    if delay.is_zero() {
+                        self.send_message(message, mentions, replying_to);
+                    } else {
+                        // Only one message waits at a time, the previous one is
+                        // sent now.
+                        if let Some(delayed_message) = self.delayed_message.take() {
+                            self.send_message(
+                                delayed_message.message,
+                                delayed_message.mentions,
+                                delayed_message.replying_to,
+                            );
+                        }
+
+                        self.delayed_message = Some(DelayedMessage {
+                            message,
+                            mentions,
+                            replying_to,
+                            send_at: Instant::now() + delay,
+                            _handle: spawn(delayed_send_task(
+                                self.room.room_id().to_owned(),
+                                delay,
+                                self.input_sender.clone(),
+                            ))
+                            .abort_on_drop(),
+                        });
+                    }
                 }
 
                 self.timeline.update(timeline::Message::Scroll(timeline::Scroll::End)).await;
@@ -67,8 +412,20 @@ impl Model {
             Message::Timeline(timeline_message) => {
                 self.timeline.update(timeline_message).await;
             }
+            Message::CheckEventCache => {
+                self.timeline.update(timeline::Message::CheckEventCache).await;
+
+                return Some(app::Message::Mode(app::Mode::None));
+            }
             Message::MarkAsRead => {
-                self.timeline.timeline.mark_as_read(ReceiptType::Read).await.unwrap();
+                if bandwidth::is_low() {
+                    info!("Low-bandwidth mode, the read receipt is not sent");
+                } else {
+                    spawn(mark_as_read_task(
+                        self.timeline.timeline.clone(),
+                        self.input_sender.clone(),
+                    ));
+                }
             }
             Message::EmptyEventCache => {
                 if let Ok((room_event_cache, _event_cache_drop_handle)) =
@@ -77,30 +434,466 @@ impl Model {
                     room_event_cache.clear().await.unwrap();
                 }
             }
+            Message::ToggleReply => {
+                if self.replying_to.take().is_some() {
+                    return Some(app::Message::Mode(app::Mode::None));
+                }
+
+                let event_id = self.timeline.selected_event_id()?;
+                let event_item = self.timeline.timeline.item_by_event_id(&event_id).await?;
+
+                if !event_item.can_be_replied_to() {
+                    info!("The event {event_id} can't be replied to");
+
+                    return Some(app::Message::Mode(app::Mode::None));
+                }
+
+                let sender = match event_item.sender_profile() {
+                    TimelineDetails::Ready(Profile {
+                        display_name: Some(display_name), ..
+                    }) => display_name.clone(),
+                    _ => event_item.sender().to_string(),
+                };
+
+                self.replying_to = Some(ReplyingTo {
+                    event_id,
+                    sender,
+                    summary: summarize_timeline_item_content(event_item.content()),
+                });
+
+                return Some(app::Message::Mode(app::Mode::Insert));
+            }
+            Message::JoinSuccessorRoom => {
+                if let Some(successor_room) = self.room.successor_room() {
+                    match self.room.client().join_room_by_id(&successor_room.room_id).await {
+                        Ok(room) => return Some(app::Message::OpenRoom(room, None)),
+                        Err(error) => error!("Failed to join the replacement room: {error}"),
+                    }
+                }
+            }
+            Message::Upgrade => {
+                // Upgrade to the same version, it's enough to test the tombstone
+                // handling.
+                let new_version = self.room.version().unwrap_or(RoomVersionId::V11);
+                let request =
+                    upgrade_room::v3::Request::new(self.room.room_id().to_owned(), new_version);
+
+                match self.room.client().send(request).await {
+                    Ok(response) => {
+                        info!("Room upgraded, replaced by `{}`", response.replacement_room)
+                    }
+                    Err(error) => error!("Failed to upgrade the room: {error}"),
+                }
+            }
+            Message::UpdateInfo => {
+                return Some(app::Message::UpdateTitle(display_name(&self.room)));
+            }
+            Message::OpenExport => {
+                return Some(app::Message::Mode(app::Mode::Export(mode::export::Model::new(
+                    self.timeline.timeline.clone(),
+                ))));
+            }
+            Message::OpenPowerLevels => {
+                return Some(app::Message::Mode(app::Mode::PowerLevels(
+                    mode::power_levels::Model::new(self.room.clone()).await,
+                )));
+            }
+            Message::OpenModeration => {
+                return Some(app::Message::Mode(app::Mode::Moderation(
+                    mode::moderation::Model::new(
+                        self.room.clone(),
+                        self.members.clone(),
+                        self.input_sender.clone(),
+                    )
+                    .await,
+                )));
+            }
+            Message::OpenJumpToDate => {
+                return Some(app::Message::Mode(app::Mode::JumpToDate(
+                    mode::jump_to_date::Model::new(self.room.clone()),
+                )));
+            }
+            Message::OpenAuditLog => {
+                return Some(app::Message::Mode(app::Mode::AuditLog(
+                    mode::audit_log::Model::new(&self.timeline.timeline).await,
+                )));
+            }
+            Message::OpenFederation => {
+                return Some(app::Message::Mode(app::Mode::Federation(
+                    mode::federation::Model::new(self.room.clone()).await,
+                )));
+            }
+            Message::OpenCrypto => {
+                return Some(app::Message::Mode(app::Mode::Crypto(
+                    mode::crypto::Model::new(self.room.clone(), self.timeline.utd_session_ids())
+                        .await,
+                )));
+            }
+            Message::OpenDevices => {
+                return Some(app::Message::Mode(app::Mode::Devices(mode::devices::Model::new(
+                    self.room.client(),
+                    self.members.clone(),
+                ))));
+            }
+            Message::OpenInboundSessions => {
+                return Some(app::Message::Mode(app::Mode::InboundSessions(
+                    mode::inbound_sessions::Model::new(self.room.clone()).await,
+                )));
+            }
         }
 
         Some(app::Message::Mode(app::Mode::None))
     }
 
+    /// Send `message`, as HTML if it mentions some members, and as a reply if
+    /// it replies to an event. It's sent in the background: the local echo
+    /// shows up as soon as it's queued, and the failures are toasted.
+    fn send_message(
+        &self,
+        message: String,
+        mentions: Vec<(OwnedUserId, String)>,
+        replying_to: Option<ReplyingTo>,
+    ) {
+        spawn(send_task(
+            self.timeline.timeline.clone(),
+            message_content(message, mentions),
+            replying_to.map(|replying_to| replying_to.event_id),
+            self.input_sender.clone(),
+        ));
+    }
+
+    /// Send the message waiting for the send delay now, before the room is
+    /// closed or replaced, which would abort the delay and lose the message.
+    /// Return once the message is in the send queue.
+    pub async fn flush_delayed_message(&mut self) {
+        let Some(delayed_message) = self.delayed_message.take() else {
+            return;
+        };
+
+        info!("Sending the delayed message of {} now", self.room.room_id());
+
+        send_task(
+            self.timeline.timeline.clone(),
+            message_content(delayed_message.message, delayed_message.mentions),
+            delayed_message.replying_to.map(|replying_to| replying_to.event_id),
+            self.input_sender.clone(),
+        )
+        .await;
+    }
+
+    /// Open the member completion if the word before the cursor starts with
+    /// `@`, the emoji completion if it starts with `:`, close it otherwise.
+    fn update_completion(&mut self) {
+        let word = self.message_textarea.word_before_cursor();
+
+        self.completion = if let Some(query) = word.strip_prefix('@') {
+            let query = query.to_lowercase();
+
+            Completion::new(
+                self.members
+                    .iter()
+                    .filter(|(user_id, display_name)| {
+                        user_id.as_str().to_lowercase().contains(&query)
+                            || display_name.as_ref().is_some_and(|display_name| {
+                                display_name.to_lowercase().contains(&query)
+                            })
+                    })
+                    .map(|(user_id, display_name)| Candidate {
+                        label: match display_name {
+                            Some(display_name) => format!("{display_name} ({user_id})"),
+                            None => user_id.to_string(),
+                        },
+                        replacement: format!(
+                            "{} ",
+                            display_name.clone().unwrap_or_else(|| user_id.to_string())
+                        ),
+                        data: Completed::Member(user_id.clone()),
+                    }),
+            )
+        } else if let Some(query) = word.strip_prefix(':') {
+            let query = query.strip_suffix(':').unwrap_or(query).to_lowercase();
+
+            // Wait for a couple of characters, a single one matches everything.
+            if query.chars().count() < 2 {
+                None
+            } else {
+                Completion::new(emojis::iter().filter_map(|emoji| {
+                    let shortcode =
+                        emoji.shortcodes().find(|shortcode| shortcode.starts_with(&query))?;
+
+                    Some(Candidate {
+                        label: format!("{} :{shortcode}:", emoji.as_str()),
+                        replacement: emoji.as_str().to_owned(),
+                        data: Completed::Emoji,
+                    })
+                }))
+            }
+        } else {
+            None
+        };
+    }
+
     pub fn render(&self, area: Rect, buffer: &mut Buffer) {
         let [title_area, timeline_area, input_area] = Layout::vertical([
-            Constraint::Length(2),
+            Constraint::Length(3),
             Constraint::Percentage(100),
             Constraint::Min(3),
         ])
         .areas(area);
         let timeline_area = timeline_area.inner(Margin::new(1, 0));
 
-        Line::from(
-            self.room
-                .cached_display_name()
-                .map(|display_name| display_name.to_string())
-                .unwrap_or_else(|| self.room.room_id().as_str().to_owned())
-                .set_style(Style::new().add_modifier(Modifier::BOLD)),
-        )
-        .centered()
-        .render(title_area, buffer);
+        // A tombstoned room shows its replacement at the end of the timeline.
+        let successor_room = self.room.successor_room();
+        let (timeline_area, tombstone_area) = if successor_room.is_some() {
+            let [timeline_area, tombstone_area] =
+                Layout::vertical([Constraint::Percentage(100), Constraint::Length(2)])
+                    .areas(timeline_area);
+
+            (timeline_area, Some(tombstone_area))
+        } else {
+            (timeline_area, None)
+        };
+
+        // The replied-to event, or the message waiting for the send delay, is
+        // shown above the composer.
+        let (timeline_area, reply_area) =
+            if self.replying_to.is_some() || self.delayed_message.is_some() {
+                let [timeline_area, reply_area] =
+                    Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)])
+                        .areas(timeline_area);
+
+                (timeline_area, Some(reply_area))
+            } else {
+                (timeline_area, None)
+            };
+
+        layout_debug::register("room title", title_area);
+        layout_debug::register("timeline", timeline_area);
+        layout_debug::register("composer", input_area);
+
+        render_header(&self.room, title_area, buffer);
         self.timeline.render(timeline_area, buffer);
+
+        if let (Some(successor_room), Some(tombstone_area)) = (successor_room, tombstone_area) {
+            layout_debug::register("tombstone", tombstone_area);
+
+            let style = Style::new().fg(Color::Black).bg(Color::Yellow);
+            let mut banner = vec![Line::from(format!(
+                "This room has been replaced by {}",
+                successor_room.room_id
+            ))];
+
+            if let Some(reason) = successor_room.reason {
+                banner[0].push_span(format!(": {reason}"));
+            }
+
+            banner.push(Line::from("Press <r> then <J> to join it"));
+
+            Paragraph::new(banner).style(style).centered().render(tombstone_area, buffer);
+        }
+
+        if let (Some(delayed_message), Some(reply_area)) = (&self.delayed_message, reply_area) {
+            layout_debug::register("delayed message", reply_area);
+
+            let remaining = delayed_message.send_at.saturating_duration_since(Instant::now());
+
+            Line::from(vec![
+                Span::raw(format!("sending in {}s: ", remaining.as_millis().div_ceil(1000)))
+                    .yellow(),
+                Span::raw(delayed_message.message.lines().next().unwrap_or_default().to_owned()),
+                Span::raw(" · <Ctrl-z> to undo").dark_gray(),
+            ])
+            .render(reply_area, buffer);
+        } else if let (Some(replying_to), Some(reply_area)) = (&self.replying_to, reply_area) {
+            layout_debug::register("reply", reply_area);
+
+            Line::from(vec![
+                Span::raw("↳ replying to "),
+                Span::raw(format!("{}: ", replying_to.sender)).bold(),
+                replying_to.summary.clone(),
+                Span::raw(" · <r> then <a> to cancel").dark_gray(),
+            ])
+            .render(reply_area, buffer);
+        }
+
         self.message_textarea.render(input_area, buffer);
+
+        if let Some(completion) = &self.completion {
+            completion.render(input_area.inner(Margin::new(1, 0)), buffer);
+        }
     }
 }
+
+/// The display name of `room`, or its ID if it has never been computed.
+pub fn display_name(room: &Room) -> String {
+    room.cached_display_name()
+        .map(|display_name| display_name.to_string())
+        .unwrap_or_else(|| room.room_id().as_str().to_owned())
+}
+
+/// Render the name of `room`, its topic, and below, its encryption, its
+/// number of members, its join rule and its canonical alias.
+pub fn render_header(room: &Room, area: Rect, buffer: &mut Buffer) {
+    let [avatar_area, area] =
+        Layout::horizontal([Constraint::Length(avatar::WIDTH), Constraint::Fill(1)])
+            .spacing(1)
+            .areas(area);
+
+    avatar::render(room, avatar_area, buffer);
+
+    let [name_area, topic_area, details_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+            .areas(area);
+
+    Line::from(display_name(room).set_style(Style::new().add_modifier(Modifier::BOLD)))
+        .centered()
+        .render(name_area, buffer);
+
+    if let Some(topic) = room.topic() {
+        Line::styled(topic.lines().next().unwrap_or_default().to_owned(), Style::new().italic())
+            .centered()
+            .render(topic_area, buffer);
+    }
+
+    let encryption = match room.encryption_state() {
+        EncryptionState::Encrypted => match room.client().encryption().verification_state().get() {
+            VerificationState::Verified => {
+                Span::styled("🛡 encrypted", Style::new().fg(Color::Green))
+            }
+            _ => Span::styled(
+                "⚠ encrypted, this session is not verified",
+                Style::new().fg(Color::Yellow),
+            ),
+        },
+        EncryptionState::NotEncrypted => {
+            Span::styled("unencrypted", Style::new().fg(Color::DarkGray))
+        }
+        EncryptionState::Unknown => {
+            Span::styled("encryption unknown", Style::new().fg(Color::DarkGray))
+        }
+    };
+    let separator = Span::styled(" · ", Style::new().fg(Color::DarkGray));
+    let mut details = Line::from(vec![
+        encryption,
+        separator.clone(),
+        Span::raw(match room.joined_members_count() {
+            1 => "1 member".to_owned(),
+            count => format!("{count} members"),
+        }),
+    ]);
+
+    if let Some(join_rule) = room.join_rule() {
+        details.push_span(separator.clone());
+        details.push_span(Span::raw(join_rule.as_str().to_owned()));
+    }
+
+    if let Some(alias) = room.canonical_alias() {
+        details.push_span(separator);
+        details.push_span(Span::raw(alias.to_string()));
+    }
+
+    details.centered().render(details_area, buffer);
+}
+
+/// The content of `message`, in HTML too if it mentions someone.
+fn message_content(
+    message: String,
+    mentions: Vec<(OwnedUserId, String)>,
+) -> RoomMessageEventContent {
+    if mentions.is_empty() {
+        return RoomMessageEventContent::text_plain(message);
+    }
+
+    let mut html_message = escape_html(&message);
+
+    for (user_id, text) in &mentions {
+        let text = escape_html(text);
+
+        html_message = html_message
+            .replace(&text, &format!("<a href=\"https://matrix.to/#/{user_id}\">{text}</a>"));
+    }
+
+    RoomMessageEventContent::text_html(message, html_message.replace('\n', "<br>"))
+        .add_mentions(Mentions::with_user_ids(mentions.into_iter().map(|(user_id, _)| user_id)))
+}
+
+/// Queue `content` in the send queue of the room, as a reply to
+/// `in_reply_to` if any. The send queue sends it, and retries it if needed.
+async fn send_task(
+    timeline: Arc<Timeline>,
+    content: RoomMessageEventContent,
+    in_reply_to: Option<OwnedEventId>,
+    input_sender: Sender<Input>,
+) {
+    let result = match in_reply_to {
+        // The timeline adds the reply relation, and the thread of the
+        // replied-to event if any.
+        Some(in_reply_to) => timeline.send_reply(content.into(), in_reply_to).await,
+        None => timeline.send(content.into()).await.map(|_| ()),
+    };
+
+    if let Err(error) = result {
+        error!("Failed to send the message: {error}");
+        toast::error(format!("Failed to send the message: {error}"), &input_sender);
+    }
+}
+
+/// Send the read receipt of the latest event, retried while rate-limited.
+async fn mark_as_read_task(timeline: Arc<Timeline>, input_sender: Sender<Input>) {
+    let result = rate_limit::retry("read receipt", &input_sender, || {
+        timeline.mark_as_read(ReceiptType::Read)
+    })
+    .await;
+
+    if let Err(error) = result {
+        error!("Failed to send the read receipt: {error}");
+        toast::error(format!("Failed to send the read receipt: {error}"), &input_sender);
+    }
+}
+
+/// Redraw every second for the countdown, until the send delay elapses.
+async fn delayed_send_task(room_id: OwnedRoomId, delay: Duration, input_sender: Sender<Input>) {
+    let at = Instant::now() + delay;
+    let mut countdown = interval(Duration::from_secs(1));
+
+    while Instant::now() < at {
+        let _ = timeout_at(at, countdown.tick()).await;
+        let _ = input_sender.send(Input::Redraw).await;
+    }
+
+    let _ = input_sender.send(Input::DelayedSendElapsed(room_id)).await;
+}
+
+/// Redraw when the room info, or the verification state of the session, change,
+/// so that the header stays up to date. Notify when the name, the topic or the
+/// join rule change, so that the title is updated too.
+async fn info_updates_task(room: Room, input_sender: Sender<Input>) {
+    let summary = |room: &Room| {
+        (display_name(room), room.topic(), room.join_rule().map(|rule| rule.as_str().to_owned()))
+    };
+    let mut previous_summary = summary(&room);
+    let updates = stream::select(
+        room.subscribe_info().map(|_| ()),
+        room.client().encryption().verification_state().map(|_| ()),
+    );
+
+    pin_mut!(updates);
+
+    while updates.next().await.is_some() {
+        let current_summary = summary(&room);
+
+        let input = if current_summary != previous_summary {
+            previous_summary = current_summary;
+
+            Input::RoomInfoUpdate(room.room_id().to_owned())
+        } else {
+            Input::Redraw
+        };
+
+        let _ = input_sender.send(input).await;
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,109 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use chrono::Local;
+use crossterm::{
+    queue,
+    style::{Attribute, Color as CColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::Modifier,
+};
+
+/// Save `buffer` into a plain text file and into an ANSI-coloured file. Return
+/// the path of the plain text file.
+pub fn save(buffer: &Buffer) -> io::Result<PathBuf> {
+    let name = format!("multiverse-screenshot-{}", Local::now().format("%Y%m%d-%H%M%S"));
+    let text_path = PathBuf::from(format!("{name}.txt"));
+    let ansi_path = PathBuf::from(format!("{name}.ansi"));
+
+    fs::write(&text_path, to_text(buffer))?;
+    fs::write(&ansi_path, to_ansi(buffer)?)?;
+
+    Ok(text_path.canonicalize().unwrap_or(text_path))
+}
+
+/// Iterate over the rows of `buffer`, skipping the cells hidden by a wide
+/// symbol on their left.
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let area = buffer.area;
+
+    (area.top()..area.bottom()).map(move |y| {
+        let mut cells = Vec::with_capacity(area.width as usize);
+        let mut to_skip = 0;
+
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+
+            if to_skip > 0 {
+                to_skip -= 1;
+
+                continue;
+            }
+
+            to_skip = textwrap::core::display_width(cell.symbol()).saturating_sub(1);
+            cells.push(cell);
+        }
+
+        cells
+    })
+}
+
+fn to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+
+    for row in rows(buffer) {
+        text.extend(row.iter().map(|cell| cell.symbol()));
+        text.truncate(text.trim_end().len());
+        text.push('\n');
+    }
+
+    text
+}
+
+fn to_ansi(buffer: &Buffer) -> io::Result<Vec<u8>> {
+    let mut ansi = Vec::new();
+
+    for row in rows(buffer) {
+        let mut previous_style = None;
+
+        for cell in row {
+            let style = (cell.fg, cell.bg, cell.modifier);
+
+            if previous_style != Some(style) {
+                queue!(
+                    ansi,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(CColor::from(cell.fg)),
+                    SetBackgroundColor(CColor::from(cell.bg)),
+                )?;
+
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                    (Modifier::REVERSED, Attribute::Reverse),
+                    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        queue!(ansi, SetAttribute(attribute))?;
+                    }
+                }
+
+                previous_style = Some(style);
+            }
+
+            ansi.write_all(cell.symbol().as_bytes())?;
+        }
+
+        queue!(ansi, SetAttribute(Attribute::Reset))?;
+        ansi.write_all(b"\n")?;
+    }
+
+    Ok(ansi)
+}
@@ -0,0 +1,138 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{TextArea, block::block_with_title, layout_debug};
+
+/// What is waiting for the input.
+enum Step {
+    Closed,
+    Secret {
+        what: &'static str,
+        is_new: bool,
+    },
+    /// A new secret, typed again to catch the typos.
+    Confirmation {
+        what: &'static str,
+        secret: String,
+    },
+}
+
+/// A masked input for a secret, e.g. a password or a passphrase, typed twice
+/// when it's a new one.
+pub struct SecretPrompt {
+    step: Step,
+    textarea: TextArea,
+}
+
+impl SecretPrompt {
+    pub fn new() -> Self {
+        Self { step: Step::Closed, textarea: TextArea::new_masked() }
+    }
+
+    /// Ask for `what`, e.g. `your password`.
+    pub fn ask(&mut self, what: &'static str) {
+        self.step = Step::Secret { what, is_new: false };
+        self.textarea = TextArea::new_masked();
+    }
+
+    /// Ask for the new `what`, e.g. `the new password`, twice.
+    pub fn ask_new(&mut self, what: &'static str) {
+        self.step = Step::Secret { what, is_new: true };
+        self.textarea = TextArea::new_masked();
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(self.step, Step::Closed)
+    }
+
+    pub fn handle_input(&mut self, key_event: KeyEvent) {
+        self.textarea.handle_input(key_event);
+    }
+
+    /// Submit the input. Return the secret once typed, and confirmed if it's a
+    /// new one, which closes the prompt; `None` if it must be typed again; or
+    /// the notice explaining why it's rejected, which asks for it again.
+    pub fn submit(&mut self) -> Result<Option<String>, String> {
+        let input = self.textarea.input();
+        self.textarea = TextArea::new_masked();
+
+        match std::mem::replace(&mut self.step, Step::Closed) {
+            Step::Closed => Ok(None),
+            Step::Secret { what, is_new } if input.is_empty() => {
+                self.step = Step::Secret { what, is_new };
+
+                Err(format!("{what} can't be empty"))
+            }
+            Step::Secret { what, is_new: true } => {
+                self.step = Step::Confirmation { what, secret: input };
+
+                Ok(None)
+            }
+            Step::Secret { is_new: false, .. } => Ok(Some(input)),
+            Step::Confirmation { what, secret } if secret != input => {
+                self.step = Step::Secret { what, is_new: true };
+
+                Err(format!("{what} doesn't match, type it again"))
+            }
+            Step::Confirmation { secret, .. } => Ok(Some(secret)),
+        }
+    }
+
+    /// Render the label and the input, on 4 lines, if the prompt is open.
+    pub fn render(&self, area: Rect, buffer: &mut Buffer) {
+        let label = match self.step {
+            Step::Closed => return,
+            Step::Secret { what, .. } => format!("Type {what}, and press <Enter>:"),
+            Step::Confirmation { what, .. } => format!("Type {what} again:"),
+        };
+
+        let [label_area, input_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(3)]).areas(area);
+
+        Line::from(label).render(label_area, buffer);
+        self.textarea.render(input_area, buffer);
+    }
+
+    /// Render the prompt in a popup, below `explanation`, and above the notice.
+    pub fn render_popup(
+        &self,
+        name: &'static str,
+        title: &str,
+        explanation: &str,
+        notice: Option<&str>,
+        area: Rect,
+        buffer: &mut Buffer,
+    ) {
+        let [area] = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Length(11)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        layout_debug::register(name, area);
+
+        let block = block_with_title(title);
+        let inner_area = block.inner(area);
+
+        block.render(area, buffer);
+
+        let [explanation_area, prompt_area, notice_area] = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(inner_area);
+
+        Paragraph::new(explanation).wrap(Wrap { trim: true }).render(explanation_area, buffer);
+
+        self.render(prompt_area, buffer);
+
+        if let Some(notice) = notice {
+            Line::styled(notice, Style::default().yellow()).render(notice_area, buffer);
+        }
+    }
+}
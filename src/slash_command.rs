@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use matrix_sdk::ruma::{OwnedUserId, events::room::message::RoomMessageEventContent};
+
+const SHRUG: &str = r"¯\_(ツ)_/¯";
+
+/// The colours of `/rainbow`, one per character, looping.
+const RAINBOW: [&str; 6] = ["#ff0000", "#ff8000", "#ffff00", "#00ff00", "#0080ff", "#8000ff"];
+
+/// A command typed in the composer, starting with `/`.
+pub enum SlashCommand {
+    /// Send this message instead of the typed text.
+    Send(RoomMessageEventContent),
+    /// Toggle this reaction on the last message.
+    React(String),
+    Invite(OwnedUserId),
+    Topic(String),
+}
+
+impl FromStr for SlashCommand {
+    type Err = String;
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = message.split_once(char::is_whitespace).unwrap_or((message, ""));
+        let argument = argument.trim();
+
+        Ok(match name {
+            "/me" => Self::Send(RoomMessageEventContent::emote_plain(required(name, argument)?)),
+            "/react" => Self::React(required(name, argument)?.to_owned()),
+            "/invite" => Self::Invite(
+                OwnedUserId::try_from(required(name, argument)?)
+                    .map_err(|error| format!("`{argument}` is not a user ID: {error}"))?,
+            ),
+            "/topic" => Self::Topic(argument.to_owned()),
+            "/shrug" => Self::Send(RoomMessageEventContent::text_plain(if argument.is_empty() {
+                SHRUG.to_owned()
+            } else {
+                format!("{argument} {SHRUG}")
+            })),
+            "/rainbow" => {
+                let argument = required(name, argument)?;
+
+                Self::Send(RoomMessageEventContent::text_html(argument, rainbow(argument)))
+            }
+            "/html" => {
+                let argument = required(name, argument)?;
+
+                Self::Send(RoomMessageEventContent::text_html(argument, argument))
+            }
+            // Send the text as is, e.g. to start a message with `/`.
+            "/plain" => Self::Send(RoomMessageEventContent::text_plain(required(name, argument)?)),
+            _ => return Err(format!("unknown command `{name}`")),
+        })
+    }
+}
+
+fn required<'a>(name: &str, argument: &'a str) -> Result<&'a str, String> {
+    if argument.is_empty() { Err(format!("`{name}` expects an argument")) } else { Ok(argument) }
+}
+
+fn rainbow(text: &str) -> String {
+    let mut colours = RAINBOW.iter().cycle();
+
+    text.chars()
+        .map(|character| match character {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            character if character.is_whitespace() => character.to_string(),
+            character => {
+                format!("<font data-mx-color=\"{}\">{character}</font>", colours.next().unwrap())
+            }
+        })
+        .collect()
+}
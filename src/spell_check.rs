@@ -0,0 +1,97 @@
+use std::{fs, path::Path, sync::Mutex};
+
+use itertools::Itertools as _;
+use spellbook::Dictionary;
+
+/// The directory of the Hunspell dictionaries of most distributions.
+pub const DEFAULT_DICTIONARIES_DIRECTORY: &str = "/usr/share/hunspell";
+
+static DICTIONARIES: Mutex<Vec<Dictionary>> = Mutex::new(Vec::new());
+
+/// Load the Hunspell dictionaries of `languages`, e.g. `en_US`, from their
+/// `.aff` and `.dic` files in `directory`. No language disables the spell
+/// checking.
+pub fn load(languages: &[String], directory: &Path) -> Result<(), String> {
+    let dictionaries = languages
+        .iter()
+        .map(|language| {
+            let read = |extension: &str| {
+                let path = directory.join(format!("{language}.{extension}"));
+
+                fs::read_to_string(&path)
+                    .map_err(|error| format!("`{}` can't be read: {error}", path.display()))
+            };
+
+            Dictionary::new(&read("aff")?, &read("dic")?)
+                .map_err(|error| format!("the `{language}` dictionary is invalid: {error}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    *DICTIONARIES.lock().unwrap() = dictionaries;
+
+    Ok(())
+}
+
+/// The distinct misspelled words of `text`, i.e. the words unknown to all the
+/// dictionaries.
+pub fn misspelled_words(text: &str) -> Vec<String> {
+    let dictionaries = DICTIONARIES.lock().unwrap();
+
+    if dictionaries.is_empty() {
+        return Vec::new();
+    }
+
+    text.split_whitespace()
+        .filter_map(|token| {
+            let (_, word, _) = split_token(token)?;
+
+            dictionaries.iter().all(|dictionary| !dictionary.check(word)).then(|| word.to_owned())
+        })
+        .unique()
+        .collect()
+}
+
+/// The corrections of `token`, e.g. the word before the cursor, with its
+/// surrounding punctuation. Empty if the word is correct.
+pub fn suggest(token: &str) -> Vec<String> {
+    let dictionaries = DICTIONARIES.lock().unwrap();
+    let Some((prefix, word, suffix)) = split_token(token) else {
+        return Vec::new();
+    };
+
+    if dictionaries.iter().any(|dictionary| dictionary.check(word)) {
+        return Vec::new();
+    }
+
+    let mut suggestions = Vec::new();
+
+    for dictionary in dictionaries.iter() {
+        let mut words = Vec::new();
+        dictionary.suggest(word, &mut words);
+
+        suggestions.extend(words.into_iter().map(|word| format!("{prefix}{word}{suffix}")));
+    }
+
+    suggestions.into_iter().unique().collect()
+}
+
+/// Split `token` into its leading punctuation, its word, and its trailing
+/// punctuation. `None` if it isn't a word, e.g. a mention, a link, or a
+/// number.
+fn split_token(token: &str) -> Option<(&str, &str, &str)> {
+    if token.contains(['@', ':', '/', '#']) || token.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let is_punctuation = |c: char| !c.is_alphabetic();
+    let word = token.trim_matches(is_punctuation);
+
+    if word.is_empty() {
+        return None;
+    }
+
+    let start = token.len() - token.trim_start_matches(is_punctuation).len();
+    let end = start + word.len();
+
+    Some((&token[..start], word, &token[end..]))
+}
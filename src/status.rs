@@ -0,0 +1,65 @@
+use std::{fmt, str::FromStr, sync::Mutex};
+
+/// The fields shown by default.
+pub const DEFAULT_FIELDS: [Field; 4] = [Field::Room, Field::Unread, Field::UserId, Field::Clock];
+
+static FIELDS: Mutex<Vec<Field>> = Mutex::new(Vec::new());
+
+/// A field of the status line, after the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// The name of the active room.
+    Room,
+
+    /// The number of unread rooms, and of unread mentions, of the account.
+    Unread,
+
+    /// The ID of the logged-in user.
+    UserId,
+
+    /// The current time.
+    Clock,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "room" => Self::Room,
+            "unread" => Self::Unread,
+            "user-id" => Self::UserId,
+            "clock" => Self::Clock,
+            _ => {
+                return Err(format!(
+                    "unknown status field `{value}`, expected `room`, `unread`, `user-id` or \
+                     `clock`"
+                ));
+            }
+        })
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Room => "room",
+            Self::Unread => "unread",
+            Self::UserId => "user-id",
+            Self::Clock => "clock",
+        })
+    }
+}
+
+/// The fields of the status line, in order.
+pub fn fields() -> Vec<Field> {
+    FIELDS.lock().unwrap().clone()
+}
+
+pub fn set_fields(fields: Vec<Field>) {
+    *FIELDS.lock().unwrap() = fields;
+}
+
+pub fn shows(field: Field) -> bool {
+    FIELDS.lock().unwrap().contains(&field)
+}
@@ -0,0 +1,99 @@
+use matrix_sdk::ruma::events::{FullStateEventContent, room::message::MessageType};
+use matrix_sdk_ui::timeline::{AnyOtherFullStateEventContent, MsgLikeKind, TimelineItemContent};
+
+use crate::timeline::{SPOILER, describe_membership_change, describe_profile_change, has_spoiler};
+
+/// Summarize an event for a human, in a single line, outside of the timeline,
+/// e.g. `Alice: 📷 Image` or `Alice joined`. Unlike in the timeline, there
+/// isn't any raw placeholder.
+///
+/// `sender` is the name of the sender, to start the summary with.
+pub fn summarize(sender: &str, content: &TimelineItemContent) -> String {
+    match content {
+        TimelineItemContent::MsgLike(message_like) => {
+            let summary = match &message_like.kind {
+                MsgLikeKind::Message(message) => {
+                    let first_line =
+                        |body: &str| body.lines().next().unwrap_or_default().to_owned();
+
+                    match message.msgtype() {
+                        _ if has_spoiler(message) => SPOILER.to_owned(),
+                        MessageType::Emote(_) => {
+                            return format!("* {sender} {}", first_line(message.body()));
+                        }
+                        MessageType::Image(image) => {
+                            format!("📷 {}", image.caption().map_or("Image".to_owned(), first_line))
+                        }
+                        MessageType::Video(video) => {
+                            format!("🎥 {}", video.caption().map_or("Video".to_owned(), first_line))
+                        }
+                        MessageType::Audio(audio) => {
+                            format!("🎤 {}", audio.caption().map_or("Audio".to_owned(), first_line))
+                        }
+                        MessageType::File(file) => format!("📎 {}", file.filename()),
+                        MessageType::Location(_) => "📍 Location".to_owned(),
+                        MessageType::VerificationRequest(_) => {
+                            "🛡 requested a verification".to_owned()
+                        }
+                        _ => first_line(message.body()),
+                    }
+                }
+                MsgLikeKind::Sticker(sticker) => format!("🏷 {}", sticker.content().body),
+                MsgLikeKind::Poll(poll) => format!("📊 {}", poll.results().question),
+                MsgLikeKind::Redacted => return format!("{sender} deleted a message"),
+                MsgLikeKind::UnableToDecrypt(_) => "🔒 Encrypted message".to_owned(),
+                MsgLikeKind::Other(_) => "sent an unsupported event".to_owned(),
+            };
+
+            format!("{sender}: {summary}")
+        }
+        TimelineItemContent::MembershipChange(membership_change) => {
+            // The member isn't the sender for a kick, a ban or an invitation.
+            let member = membership_change
+                .display_name()
+                .unwrap_or_else(|| membership_change.user_id().localpart().to_owned());
+
+            format!("{member} {}", describe_membership_change(membership_change.change()))
+        }
+        TimelineItemContent::ProfileChange(profile_change) => {
+            format!("{sender} {}", describe_profile_change(profile_change))
+        }
+        TimelineItemContent::OtherState(other_state) => {
+            format!("{sender} {}", describe_state_change(other_state.content()))
+        }
+        TimelineItemContent::CallInvite | TimelineItemContent::RtcNotification => {
+            format!("📞 {sender} started a call")
+        }
+        TimelineItemContent::FailedToParseMessageLike { .. }
+        | TimelineItemContent::FailedToParseState { .. } => {
+            format!("{sender} sent an invalid event")
+        }
+    }
+}
+
+/// Describe a state change, after the name of the sender.
+fn describe_state_change(content: &AnyOtherFullStateEventContent) -> String {
+    match content {
+        AnyOtherFullStateEventContent::RoomName(FullStateEventContent::Original {
+            content,
+            ..
+        }) => format!("renamed the room to {}", content.name),
+        AnyOtherFullStateEventContent::RoomName(_) => "removed the room name".to_owned(),
+        AnyOtherFullStateEventContent::RoomTopic(_) => "changed the topic".to_owned(),
+        AnyOtherFullStateEventContent::RoomAvatar(_) => "changed the room avatar".to_owned(),
+        AnyOtherFullStateEventContent::RoomCreate(_) => "created the room".to_owned(),
+        AnyOtherFullStateEventContent::RoomEncryption(_) => "enabled the encryption".to_owned(),
+        AnyOtherFullStateEventContent::RoomTombstone(_) => "upgraded the room".to_owned(),
+        AnyOtherFullStateEventContent::RoomJoinRules(_) => "changed who can join".to_owned(),
+        AnyOtherFullStateEventContent::RoomHistoryVisibility(_) => {
+            "changed who can read the history".to_owned()
+        }
+        AnyOtherFullStateEventContent::RoomPowerLevels(_) => "changed the power levels".to_owned(),
+        AnyOtherFullStateEventContent::RoomPinnedEvents(_) => {
+            "changed the pinned messages".to_owned()
+        }
+        AnyOtherFullStateEventContent::RoomCanonicalAlias(_)
+        | AnyOtherFullStateEventContent::RoomAliases(_) => "changed the room address".to_owned(),
+        content => format!("changed the room state ({})", content.event_type()),
+    }
+}
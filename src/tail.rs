@@ -0,0 +1,108 @@
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+use futures::{StreamExt, pin_mut};
+use matrix_sdk::{Client, ruma::OwnedRoomId};
+use matrix_sdk_ui::{
+    eyeball_im::VectorDiff,
+    sync_service::SyncService,
+    timeline::{RoomExt, TimelineItem, TimelineItemKind, VirtualTimelineItem},
+};
+use serde_json::{Value, json};
+
+use crate::{Error, timeline::summarize_timeline_item_content};
+
+/// Sync, and print every diff of the timeline of `room_id` as a JSON line on
+/// stdout, without any user interface.
+pub async fn run(client: Client, room_id: OwnedRoomId) -> Result<(), Error> {
+    let sync_service = SyncService::builder(client.clone()).build().await?;
+    sync_service.start().await;
+    sync_service.room_list_service().subscribe_to_rooms(&[&room_id]).await;
+
+    // The room may not be known yet, e.g. on the first sync.
+    let room = client.await_room_remote_echo(&room_id).await;
+    let timeline = room.timeline().await?;
+    let (initial_items, items_stream) = timeline.subscribe().await;
+
+    print_diffs([VectorDiff::Reset { values: initial_items }])?;
+
+    pin_mut!(items_stream);
+
+    while let Some(diffs) = items_stream.next().await {
+        print_diffs(diffs)?;
+    }
+
+    sync_service.stop().await;
+
+    Ok(())
+}
+
+fn print_diffs(diffs: impl IntoIterator<Item = VectorDiff<Arc<TimelineItem>>>) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    for diff in diffs {
+        writeln!(stdout, "{}", diff_to_json(&diff))?;
+    }
+
+    stdout.flush()
+}
+
+/// The JSON of `diff`, as printed by `--tail-room`, and exported from the
+/// diff log.
+pub fn diff_to_json(diff: &VectorDiff<Arc<TimelineItem>>) -> Value {
+    match diff {
+        VectorDiff::Append { values } => json!({
+            "diff": "append",
+            "items": values.iter().map(item_to_json).collect::<Vec<_>>(),
+        }),
+        VectorDiff::Clear => json!({ "diff": "clear" }),
+        VectorDiff::PushFront { value } => {
+            json!({ "diff": "push_front", "item": item_to_json(value) })
+        }
+        VectorDiff::PushBack { value } => {
+            json!({ "diff": "push_back", "item": item_to_json(value) })
+        }
+        VectorDiff::PopFront => json!({ "diff": "pop_front" }),
+        VectorDiff::PopBack => json!({ "diff": "pop_back" }),
+        VectorDiff::Insert { index, value } => {
+            json!({ "diff": "insert", "index": index, "item": item_to_json(value) })
+        }
+        VectorDiff::Set { index, value } => {
+            json!({ "diff": "set", "index": index, "item": item_to_json(value) })
+        }
+        VectorDiff::Remove { index } => json!({ "diff": "remove", "index": index }),
+        VectorDiff::Truncate { length } => json!({ "diff": "truncate", "length": length }),
+        VectorDiff::Reset { values } => json!({
+            "diff": "reset",
+            "items": values.iter().map(item_to_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn item_to_json(item: &Arc<TimelineItem>) -> Value {
+    let unique_id = &item.unique_id().0;
+
+    match item.kind() {
+        TimelineItemKind::Event(event_item) => json!({
+            "unique_id": unique_id,
+            "kind": "event",
+            "event_id": event_item.event_id(),
+            "transaction_id": event_item.transaction_id(),
+            "sender": event_item.sender(),
+            "timestamp": event_item.timestamp(),
+            "origin": event_item.origin().map(|origin| format!("{origin:?}")),
+            "is_local_echo": event_item.is_local_echo(),
+            "content": summarize_timeline_item_content(event_item.content()).content,
+        }),
+        TimelineItemKind::Virtual(virtual_item) => json!({
+            "unique_id": unique_id,
+            "kind": match virtual_item {
+                VirtualTimelineItem::DateDivider(_) => "date_divider",
+                VirtualTimelineItem::ReadMarker => "read_marker",
+                VirtualTimelineItem::TimelineStart => "timeline_start",
+            },
+        }),
+    }
+}
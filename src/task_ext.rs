@@ -1,9 +1,24 @@
 use std::{
+    future::pending,
     pin::Pin,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
     task::{Context, Poll},
+    time::Duration,
 };
 
-use tokio::task::{JoinError, JoinHandle};
+use chrono::{DateTime, Local};
+use tokio::{
+    spawn,
+    sync::mpsc::Sender,
+    task::{JoinError, JoinHandle},
+    time::sleep,
+};
+use tracing::{error, info};
+
+use crate::input::Input;
 
 pub trait JoinHandleExt<T> {
     fn abort_on_drop(self) -> AbortOnDrop<T>;
@@ -21,6 +36,10 @@ impl<T> AbortOnDrop<T> {
     fn new(join_handle: JoinHandle<T>) -> Self {
         Self(join_handle)
     }
+
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
 }
 
 impl<T> Drop for AbortOnDrop<T> {
@@ -36,3 +55,149 @@ impl<T: 'static> Future for AbortOnDrop<T> {
         Pin::new(&mut self.0).poll(cx)
     }
 }
+
+/// The delay before restarting a supervised task which has terminated.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+static TASKS: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A long-running task, registered by [`supervise`] or
+/// [`supervise_with_restart`] until its handle is dropped.
+#[derive(Clone)]
+pub struct Task {
+    id: u64,
+    pub name: &'static str,
+    pub started_at: DateTime<Local>,
+    pub is_running: bool,
+    pub number_of_restarts: u32,
+    /// Why the task has terminated the last time, e.g. its panic message.
+    pub last_failure: Option<(DateTime<Local>, String)>,
+}
+
+/// Spawn `future` as the task `name`, and report when it terminates, i.e. when
+/// it panics or returns, as it's expected to run as long as its handle lives.
+pub fn supervise<Fut>(
+    name: &'static str,
+    input_sender: Sender<Input>,
+    future: Fut,
+) -> AbortOnDrop<()>
+where
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut future = Some(future);
+
+    supervisor(name, input_sender, move || future.take())
+}
+
+/// Like [`supervise`], but the task is restarted with a new future from
+/// `new_future` when it terminates.
+pub fn supervise_with_restart<F, Fut>(
+    name: &'static str,
+    input_sender: Sender<Input>,
+    mut new_future: F,
+) -> AbortOnDrop<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    supervisor(name, input_sender, move || Some(new_future()))
+}
+
+fn supervisor<F, Fut>(
+    name: &'static str,
+    input_sender: Sender<Input>,
+    mut next_future: F,
+) -> AbortOnDrop<()>
+where
+    F: FnMut() -> Option<Fut> + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+
+    TASKS.lock().unwrap().push(Task {
+        id,
+        name,
+        started_at: Local::now(),
+        is_running: true,
+        number_of_restarts: 0,
+        last_failure: None,
+    });
+
+    // Moved in the future, to be unregistered even if it's aborted before
+    // being polled.
+    let registration = Registration(id);
+
+    spawn(async move {
+        let _registration = registration;
+        let mut number_of_restarts = 0;
+
+        while let Some(future) = next_future() {
+            if number_of_restarts > 0 {
+                info!("Restarting the task `{name}`");
+
+                update_task(id, |task| {
+                    task.is_running = true;
+                    task.number_of_restarts = number_of_restarts;
+                    task.started_at = Local::now();
+                });
+            }
+
+            // Aborted with the supervisor.
+            let failure = match spawn(future).abort_on_drop().await {
+                Ok(()) => "terminated".to_owned(),
+                Err(error) if error.is_panic() => {
+                    let panic = error.into_panic();
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "???".to_owned());
+
+                    format!("panicked: {message}")
+                }
+                Err(error) => error.to_string(),
+            };
+
+            error!("The task `{name}` has {failure}");
+
+            update_task(id, |task| {
+                task.is_running = false;
+                task.last_failure = Some((Local::now(), failure));
+            });
+            let _ = input_sender.send(Input::Redraw).await;
+
+            number_of_restarts += 1;
+            sleep(RESTART_DELAY).await;
+        }
+
+        // Still listed, as terminated, until the handle is dropped.
+        pending::<()>().await
+    })
+    .abort_on_drop()
+}
+
+fn update_task(id: u64, update: impl FnOnce(&mut Task)) {
+    if let Some(task) = TASKS.lock().unwrap().iter_mut().find(|task| task.id == id) {
+        update(task);
+    }
+}
+
+/// Unregister the task when dropped.
+struct Registration(u64);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        TASKS.lock().unwrap().retain(|task| task.id != self.0);
+    }
+}
+
+/// The supervised tasks, the oldest first.
+pub fn tasks() -> Vec<Task> {
+    TASKS.lock().unwrap().clone()
+}
+
+/// The number of supervised tasks which aren't running anymore.
+pub fn number_of_terminated_tasks() -> usize {
+    TASKS.lock().unwrap().iter().filter(|task| !task.is_running).count()
+}
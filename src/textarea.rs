@@ -1,7 +1,58 @@
-use crossterm::event::KeyEvent;
-use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use std::{fmt, str::FromStr, sync::Mutex};
 
-use crate::block::block;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    widgets::Widget,
+};
+use tui_textarea::CursorMove;
+
+use crate::{block::block, spell_check};
+
+static KEY_BINDINGS: Mutex<KeyBindings> = Mutex::new(KeyBindings::Emacs);
+
+/// The editing bindings of the text areas, in addition to the characters, the
+/// arrows, and the deletion keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyBindings {
+    /// Like readline: `Ctrl-w` and `Ctrl-u` delete the word and the line
+    /// before the cursor, `Alt-b` and `Alt-f` move by word, `Ctrl-a` and
+    /// `Ctrl-e` move to the start and the end of the line, and the other
+    /// shortcuts of `tui-textarea` are kept.
+    Emacs,
+
+    /// Like the insert mode of Vim: only `Ctrl-w`, `Ctrl-u`, and `Ctrl-h` to
+    /// delete the character before the cursor.
+    Vim,
+}
+
+impl FromStr for KeyBindings {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "emacs" => Self::Emacs,
+            "vim" => Self::Vim,
+            _ => return Err(format!("unknown key bindings `{value}`, expected `emacs` or `vim`")),
+        })
+    }
+}
+
+impl fmt::Display for KeyBindings {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Emacs => "emacs",
+            Self::Vim => "vim",
+        })
+    }
+}
+
+/// Set the editing bindings of all the text areas.
+pub fn set_key_bindings(key_bindings: KeyBindings) {
+    *KEY_BINDINGS.lock().unwrap() = key_bindings;
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct TextArea {
@@ -22,14 +73,112 @@ impl TextArea {
         new
     }
 
+    /// A text area with a border, hiding what is typed, e.g. for a password.
+    pub fn new_masked() -> Self {
+        let mut new = Self::new_with_border();
+        new.inner.set_mask_char('•');
+
+        new
+    }
+
+    /// Edit with `key_event`, and return whether the input has changed.
     pub fn handle_input(&mut self, key_event: KeyEvent) -> bool {
-        self.inner.input(key_event)
+        let key_bindings = *KEY_BINDINGS.lock().unwrap();
+        let is_ctrl = key_event.modifiers == KeyModifiers::CONTROL;
+        let is_alt = key_event.modifiers == KeyModifiers::ALT;
+
+        match (key_bindings, key_event.code) {
+            (_, KeyCode::Char('w')) if is_ctrl => self.inner.delete_word(),
+            (_, KeyCode::Char('u')) if is_ctrl => self.inner.delete_line_by_head(),
+            (KeyBindings::Vim, KeyCode::Char('h')) if is_ctrl => self.inner.delete_char(),
+            (KeyBindings::Emacs, KeyCode::Char('b')) if is_alt => {
+                self.move_cursor(CursorMove::WordBack)
+            }
+            (KeyBindings::Emacs, KeyCode::Char('f')) if is_alt => {
+                self.move_cursor(CursorMove::WordForward)
+            }
+            (KeyBindings::Emacs, KeyCode::Char('a')) if is_ctrl => {
+                self.move_cursor(CursorMove::Head)
+            }
+            (KeyBindings::Emacs, KeyCode::Char('e')) if is_ctrl => {
+                self.move_cursor(CursorMove::End)
+            }
+            (KeyBindings::Emacs, _) => self.inner.input(key_event),
+            (KeyBindings::Vim, _) => self.inner.input_without_shortcuts(key_event),
+        }
+    }
+
+    /// Move the cursor, which doesn't change the input.
+    fn move_cursor(&mut self, cursor_move: CursorMove) -> bool {
+        self.inner.move_cursor(cursor_move);
+
+        false
+    }
+
+    /// Show the cursor only in the focused text area, when there are several.
+    pub fn set_focus(&mut self, is_focused: bool) {
+        self.inner.set_cursor_style(if is_focused {
+            Style::default().reversed()
+        } else {
+            Style::default()
+        });
     }
 
     pub fn input(&self) -> String {
         self.inner.lines().join("\n")
     }
 
+    /// The word before the cursor, i.e. everything since the last whitespace
+    /// on the cursor line.
+    pub fn word_before_cursor(&self) -> &str {
+        let (row, column) = self.inner.cursor();
+        let line = &self.inner.lines()[row];
+        let end = line.char_indices().nth(column).map_or(line.len(), |(index, _)| index);
+        let line = &line[..end];
+        let start = line
+            .char_indices()
+            .rev()
+            .find(|(_, character)| character.is_whitespace())
+            .map_or(0, |(index, character)| index + character.len_utf8());
+
+        &line[start..]
+    }
+
+    /// Replace the word before the cursor by `replacement`.
+    pub fn replace_word_before_cursor(&mut self, replacement: &str) {
+        for _ in 0..self.word_before_cursor().chars().count() {
+            self.inner.delete_char();
+        }
+
+        self.inner.insert_str(replacement);
+    }
+
+    /// Insert `text` at the cursor, at once, with its newlines.
+    pub fn paste(&mut self, text: &str) {
+        self.inner.insert_str(text);
+    }
+
+    /// Replace the input by `text`, with the cursor at its end.
+    pub fn set_input(&mut self, text: &str) {
+        self.clear();
+        self.inner.insert_str(text);
+    }
+
+    /// Underline the misspelled words of the input.
+    pub fn highlight_misspelled_words(&mut self) {
+        let pattern = spell_check::misspelled_words(&self.input())
+            .iter()
+            .map(|word| regex::escape(word))
+            .collect::<Vec<_>>()
+            .join("|");
+
+        // An empty pattern removes the highlighting.
+        let pattern = if pattern.is_empty() { pattern } else { format!(r"\b(?:{pattern})\b") };
+
+        self.inner.set_search_style(Style::default().red().underlined());
+        let _ = self.inner.set_search_pattern(pattern);
+    }
+
     pub fn clear(&mut self) {
         self.inner = tui_textarea::TextArea::new(vec![]);
 
@@ -0,0 +1,112 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local};
+use tokio::{sync::mpsc::Sender, time::interval};
+
+use crate::{input::Input, status};
+
+/// How often the relative timestamps are refreshed.
+const RELATIVE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+static FORMAT: Mutex<Format> = Mutex::new(Format::Hour24);
+
+/// How the timestamps are rendered, in the timeline and in the room list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `14:05`.
+    Hour24,
+
+    /// `2:05 PM`.
+    Hour12,
+
+    /// `14:05:37`.
+    Seconds,
+
+    /// `5m ago`.
+    Relative,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "24h" => Self::Hour24,
+            "12h" => Self::Hour12,
+            "seconds" => Self::Seconds,
+            "relative" => Self::Relative,
+            _ => {
+                return Err(format!(
+                    "unknown time format `{value}`, expected `24h`, `12h`, `seconds` or `relative`"
+                ));
+            }
+        })
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Hour24 => "24h",
+            Self::Hour12 => "12h",
+            Self::Seconds => "seconds",
+            Self::Relative => "relative",
+        })
+    }
+}
+
+/// Set the format used by [`format`].
+pub fn set_format(format: Format) {
+    *FORMAT.lock().unwrap() = format;
+}
+
+/// Format `time` according to the current format.
+pub fn format(time: SystemTime) -> String {
+    let format = *FORMAT.lock().unwrap();
+
+    match format {
+        Format::Hour24 => DateTime::<Local>::from(time).format("%H:%M").to_string(),
+        Format::Hour12 => DateTime::<Local>::from(time).format("%-I:%M %p").to_string(),
+        Format::Seconds => DateTime::<Local>::from(time).format("%H:%M:%S").to_string(),
+        Format::Relative => {
+            let elapsed = SystemTime::now().duration_since(time).unwrap_or_default().as_secs();
+
+            match elapsed {
+                0..60 => "now".to_owned(),
+                60..3_600 => format!("{}m ago", elapsed / 60),
+                3_600..86_400 => format!("{}h ago", elapsed / 3_600),
+                _ => format!("{}d ago", elapsed / 86_400),
+            }
+        }
+    }
+}
+
+/// Format `time` as a clock, in 12 or 24 hours according to the current format.
+pub fn format_clock(time: SystemTime) -> String {
+    let format = *FORMAT.lock().unwrap();
+    let time = DateTime::<Local>::from(time);
+
+    match format {
+        Format::Hour12 => time.format("%-I:%M %p").to_string(),
+        Format::Hour24 | Format::Seconds | Format::Relative => time.format("%H:%M").to_string(),
+    }
+}
+
+/// Redraw regularly while the timestamps are relative, or while the status line
+/// shows the clock, so that they stay accurate.
+pub async fn refresh_relative_timestamps_task(input_sender: Sender<Input>) {
+    let mut interval = interval(RELATIVE_REFRESH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if *FORMAT.lock().unwrap() == Format::Relative || status::shows(status::Field::Clock) {
+            let _ = input_sender.send(Input::Redraw).await;
+        }
+    }
+}
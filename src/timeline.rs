@@ -1,45 +1,76 @@
-use std::{borrow::Cow, cmp::min, iter, ops::Not, sync::Arc};
+use std::{
+    borrow::Cow,
+    cmp::{Ordering, min},
+    collections::{HashMap, HashSet, VecDeque},
+    iter, mem,
+    ops::Not,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
 use futures::{StreamExt, pin_mut};
 use itertools::Itertools as _;
 use matrix_sdk::{
-    Client, Room,
-    deserialized_responses::TimelineEvent,
+    Client, Room, RoomMemberships,
+    deserialized_responses::{
+        AlgorithmInfo, ShieldState, TimelineEvent, TimelineEventKind, VerificationState,
+    },
+    event_cache::RoomPaginationStatus,
     linked_chunk::{ChunkContent, ChunkIdentifier, LinkedChunkId},
     locks::Mutex,
-    ruma::{EventId, OwnedEventId, OwnedRoomId},
+    ruma::{
+        EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedTransactionId,
+        OwnedUserId, UserId,
+        events::{
+            fully_read::FullyReadEventContent,
+            receipt::{ReceiptThread, ReceiptType},
+            room::message::{MessageFormat, MessageType},
+        },
+        exports::serde_json,
+    },
 };
 use matrix_sdk_ui::{
     Timeline,
     eyeball_im::{Vector, VectorDiff},
     timeline::{
-        MsgLikeKind, Profile, RoomExt, TimelineDetails, TimelineItem, TimelineItemContent,
-        TimelineItemKind, VirtualTimelineItem,
+        EncryptedMessage, Error as TimelineError, EventSendState, MemberProfileChange,
+        MembershipChange, Message as MessageContent, MsgLikeContent, MsgLikeKind, Profile, RoomExt,
+        TimelineDetails, TimelineFocus, TimelineItem, TimelineItemContent, TimelineItemKind,
+        TimelineUniqueId, VirtualTimelineItem,
     },
 };
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Margin, Rect},
-    style::{Color, Style, Styled, Stylize},
+    layout::{Alignment, Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Modifier, Style, Styled, Stylize},
     text::{Line, Span, Text},
     widgets::{
-        List, ListDirection, ListItem, Paragraph, ScrollbarOrientation, ScrollbarState,
-        StatefulWidget, Widget,
+        Cell, Clear, Paragraph, Row, ScrollbarOrientation, ScrollbarState, StatefulWidget, Table,
+        Widget, Wrap,
     },
 };
-use tokio::{spawn, sync::mpsc::Sender};
+use tokio::{select, spawn, sync::mpsc::Sender, time::interval};
+use tracing::error;
 
 use crate::{
-    app, block,
+    app, block, bot, bus, clipboard,
+    debug_panel::{self, Hook, Panel},
+    diff_log, divider,
     input::Input,
-    scrollbar,
-    task_ext::{AbortOnDrop, JoinHandleExt},
+    mode, room, scrollbar, tail,
+    task_ext::{self, AbortOnDrop, JoinHandleExt},
+    time,
+    url_preview::{self, UrlPreview},
 };
 
 pub enum Scroll {
     Up,
     Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
     Start,
     End,
 }
@@ -51,6 +82,16 @@ pub enum Details {
     EventId,
     Origin,
     LinkedChunk,
+    BackupDecryption,
+    ReadReceipts,
+    UnreadBy,
+    ReadMarkers,
+    Relations,
+    LocalEchoes,
+    Shield,
+    DiffLog,
+    Ordering,
+    EventCacheConsistency,
 }
 
 pub enum Message {
@@ -58,10 +99,126 @@ pub enum Message {
     Scroll(Scroll),
     PaginateBackwards,
     ShowDetails(Details),
-    ToggleReactionOnLastMessage,
+    ToggleReactionOnLastMessage(String),
+    DecryptWithBackup,
+    ShowReadReceipts,
+    ShowUnreadBy,
+    ShowReadMarkers,
+    ShowRelations,
+    ShowShield,
+    UpdateOwnReadReceipt(Option<OwnedEventId>),
+    UpdateUrlPreview(String, Option<UrlPreview>),
+    ToggleSpoiler,
+    ExportDiffLog,
+    ToggleOrderingValidation,
+    CheckEventCache,
+    UpdatePaginationStatus(RoomPaginationStatus),
+    TogglePaginationBar,
+}
+
+/// Register the details of the timeline, with the keys of the room panel, as
+/// debug panels. They are rendered by the timeline of the opened room.
+pub fn register_debug_panels() {
+    fn timeline(message: Message) -> app::Message {
+        app::Message::Room(room::Message::Timeline(message))
+    }
+
+    let panels: [(&str, char, fn() -> app::Message); 14] = [
+        ("event IDs", 'i', || timeline(Message::ShowDetails(Details::EventId))),
+        ("event origins", 'o', || timeline(Message::ShowDetails(Details::Origin))),
+        ("linked chunk", 'l', || timeline(Message::ShowDetails(Details::LinkedChunk))),
+        ("local echoes lifecycle", 'E', || timeline(Message::ShowDetails(Details::LocalEchoes))),
+        ("diffs of the timeline", 'L', || timeline(Message::ShowDetails(Details::DiffLog))),
+        ("ordering validation", 'O', || timeline(Message::ToggleOrderingValidation)),
+        ("event cache consistency", 'C', || app::Message::Room(room::Message::CheckEventCache)),
+        ("backup decryption", 'u', || timeline(Message::DecryptWithBackup)),
+        ("read receipts", 'R', || timeline(Message::ShowReadReceipts)),
+        ("unread by", 'N', || timeline(Message::ShowUnreadBy)),
+        ("read markers", 'F', || timeline(Message::ShowReadMarkers)),
+        ("relations", 'G', || timeline(Message::ShowRelations)),
+        ("shield", 'V', || timeline(Message::ShowShield)),
+        ("pagination bar", 'B', || timeline(Message::TogglePaginationBar)),
+    ];
+
+    for (name, key, update) in panels {
+        debug_panel::register(Panel { name, key, hook: Hook::Update(update) });
+    }
+}
+
+/// The send states a local echo went through, until its remote echo.
+struct LocalEchoTrace {
+    unique_id: TimelineUniqueId,
+    transaction_id: OwnedTransactionId,
+    transitions: Vec<(DateTime<Local>, String)>,
+}
+
+/// A broken ordering invariant, found by the ordering validation.
+struct OrderingViolation {
+    /// Where the invariant is broken, e.g. `timeline` or `chunk #3`.
+    location: String,
+    explanation: String,
+    /// The offending events, in the order they are found.
+    event_ids: Vec<OwnedEventId>,
+}
+
+/// The differences between the events of the linked chunk, in the event cache,
+/// and the ones of the timeline.
+struct EventCacheConsistency {
+    time: DateTime<Local>,
+    /// The events of the event cache missing from the timeline, with their
+    /// type, and whether they are aggregated by the timeline, e.g. the
+    /// reactions or the edits.
+    only_in_event_cache: Vec<(OwnedEventId, String, bool)>,
+    /// The remote events of the timeline missing from the event cache.
+    only_in_timeline: Vec<OwnedEventId>,
 }
 
-const MINIMUM_NUMBER_OF_VISIBLE_ITEMS: usize = 3;
+/// The outcome of decrypting a UTD with a room key from the backup only.
+enum BackupDecryption {
+    Decrypted,
+    StillUnableToDecrypt,
+    NotInBackup(String),
+    BackupUnavailable,
+    NotMegolm,
+}
+
+/// A read receipt on the selected event.
+struct ReadReceipt {
+    user_id: OwnedUserId,
+    display_name: Option<String>,
+    timestamp: Option<DateTime<Local>>,
+    receipt_type: ReceiptType,
+}
+
+const ESTIMATED_ITEM_HEIGHT: usize = 2;
+const NUMBER_OF_EVENTS_TO_PAGINATE: u16 = 20;
+const PAGINATION_DEBOUNCE: Duration = Duration::from_millis(500);
+pub(crate) const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+pub(crate) const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+const SENDER_COLORS: [Color; 8] = [
+    Color::Indexed(215),
+    Color::Indexed(114),
+    Color::Indexed(75),
+    Color::Indexed(176),
+    Color::Indexed(221),
+    Color::Indexed(80),
+    Color::Indexed(210),
+    Color::Indexed(147),
+];
+/// What is shown instead of a message hidden behind a spoiler.
+pub(crate) const SPOILER: &str = "▓▓▓ spoiler ▓▓▓";
+/// The style of the messages mentioning the current user.
+const MENTION_STYLE: Style = Style::new().bg(Color::Indexed(52));
+/// The style of the notices, i.e. the messages sent by bots.
+const NOTICE_STYLE: Style = Style::new().fg(Color::Gray).add_modifier(Modifier::DIM);
+const NUMBER_OF_CONTEXT_EVENTS: u16 = 20;
+const NUMBER_OF_UTDS_TO_DECRYPT_WITH_BACKUP: usize = 10;
+/// How many in-reply-to ancestors are resolved, in case of a cycle.
+const MAXIMUM_NUMBER_OF_ANCESTORS: usize = 10;
+const MAXIMUM_NUMBER_OF_LOCAL_ECHO_TRACES: usize = 20;
+/// The lines of the read items shown above the read position, when the room
+/// is opened.
+const READ_POSITION_CONTEXT_HEIGHT: usize = 3;
 
 pub struct Model {
     pub(crate) timeline: Arc<Timeline>,
@@ -70,50 +227,278 @@ pub struct Model {
     items: Vector<Arc<TimelineItem>>,
     linked_chunks: Vec<(ChunkIdentifier, ChunkContent<TimelineEvent, String>)>,
     _items_updates_handle: Option<AbortOnDrop<()>>,
+    _own_read_receipt_updates_handle: Option<AbortOnDrop<()>>,
+    _pagination_status_updates_handle: Option<AbortOnDrop<()>>,
+    fetch_members_handle: Option<AbortOnDrop<()>>,
+    own_read_receipt: Option<OwnedEventId>,
+    /// The event of the fully-read marker, loaded with
+    /// [`Message::ShowReadMarkers`].
+    fully_read: Option<OwnedEventId>,
     scroll_position: Mutex<usize>,
+    /// Whether the timeline must be scrolled to the read marker, or to the own
+    /// read receipt, once the items are loaded, rather than staying at the
+    /// bottom.
+    scrolls_to_read_position: Mutex<bool>,
+    /// The items in the viewport as of the last render, from the bottom.
+    scroll_anchors: Mutex<Vec<ScrollAnchor>>,
+    /// The messages received below the viewport while scrolled up.
+    number_of_new_messages: Mutex<usize>,
+    viewport_height: Mutex<usize>,
+    heights: Mutex<Heights>,
+    input_sender: Option<Sender<Input>>,
+    pagination: Mutex<Pagination>,
+    /// Whether the top of the timeline was visible at the last render, to
+    /// paginate backwards automatically after the render.
+    top_is_visible: Mutex<bool>,
+    /// The back-pagination status, `None` if the timeline is focused on an
+    /// event.
+    pagination_status: Option<RoomPaginationStatus>,
+    /// The token of the gap before the oldest loaded chunk, when the last
+    /// back-pagination started.
+    last_pagination_token: Option<String>,
+    /// The number of events when the last back-pagination started.
+    number_of_events_before_pagination: usize,
+    /// The number of events gained by the last back-pagination.
+    last_pagination_gain: Option<usize>,
+    pagination_bar_is_visible: bool,
     details: Details,
+    backup_decryptions: Vec<(OwnedEventId, BackupDecryption)>,
+    selected_event_id: Mutex<Option<OwnedEventId>>,
+    read_receipts: Option<(OwnedEventId, Vec<ReadReceipt>)>,
+    /// The own events read by all the joined members, updated with the items
+    /// as the read receipts are received.
+    seen_by_everyone: HashSet<OwnedEventId>,
+    /// The joined members, for [`Self::seen_by_everyone`], loaded again after
+    /// a membership change.
+    joined_member_ids: Option<HashSet<OwnedUserId>>,
+    /// The joined members who haven't read the selected event, with their
+    /// display name, loaded with [`Message::ShowUnreadBy`].
+    unread_by: Option<(OwnedEventId, Vec<(OwnedUserId, Option<String>)>)>,
+    /// The tree of the relations of the selected event, as lines with their
+    /// depth.
+    relations: Option<(OwnedEventId, Vec<(usize, String)>)>,
+    /// The event whose shield is explained.
+    shield_event_id: Option<OwnedEventId>,
+    /// The last local echoes, the most recent last.
+    local_echo_traces: VecDeque<LocalEchoTrace>,
+    diff_log: diff_log::Log,
+    /// The outcome of the last export of the diff log.
+    diff_log_notice: Option<String>,
+    /// Whether the ordering invariants are checked after every update.
+    validates_ordering: bool,
+    ordering_violations: Vec<OrderingViolation>,
+    event_cache_consistency: Option<EventCacheConsistency>,
+    url_previews: url_preview::Cache,
+    url_preview_handles: Vec<(String, AbortOnDrop<()>)>,
+    /// The day of the focused event, whose date divider is highlighted as the
+    /// anchor of a jump to a date.
+    anchor_date: Option<NaiveDate>,
+    revealed_spoilers: HashSet<OwnedEventId>,
+}
+
+/// The heights of the rendered timeline items, indexed like `Model::items`.
+///
+/// Only the items inside the viewport are rendered. The heights of the other
+/// items are remembered from a previous frame, or estimated, to compute the
+/// scrollbar.
+#[derive(Default)]
+struct Heights {
+    /// The width the heights have been computed for.
+    width: u16,
+
+    /// The height of each item. `None` if unknown, `Some(0)` if the item is not
+    /// rendered.
+    heights: Vector<Option<usize>>,
+}
+
+/// An item in the viewport, to keep the viewport stable when the items below it
+/// change, e.g. when new events arrive while scrolled up.
+///
+/// The scroll position is the number of lines below the viewport, so that
+/// prepending older items doesn't move the viewport. The viewport is anchored
+/// to the lowest item of the last render still there: the date dividers and
+/// the read marker are often removed and inserted again, with another unique
+/// ID, while paginating.
+struct ScrollAnchor {
+    unique_id: TimelineUniqueId,
+    /// The height of the items below the anchor, as of the last render.
+    height_below: usize,
+}
+
+/// The automatic backwards pagination, run when the top of the timeline is
+/// visible.
+#[derive(Default)]
+struct Pagination {
+    handle: Option<AbortOnDrop<()>>,
+    started_at: Option<Instant>,
+}
+
+impl Pagination {
+    fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|handle| handle.is_finished().not())
+    }
+}
+
+impl Heights {
+    fn invalidate(&mut self) {
+        self.heights = Vector::from(vec![None; self.heights.len()]);
+    }
+
+    fn invalidate_event(&mut self, items: &Vector<Arc<TimelineItem>>, event_id: &EventId) {
+        if let Some(index) = items
+            .iter()
+            .position(|item| item.as_event().and_then(|event| event.event_id()) == Some(event_id))
+        {
+            self.heights.set(index, None);
+        }
+    }
 }
 
 impl Model {
-    pub async fn new(room: &Room, input_sender: Option<Sender<Input>>) -> Self {
-        let timeline = Arc::new(room.timeline_builder().build().await.unwrap());
+    /// Build the timeline of `room`, focused on `focused_event_id` if any. It
+    /// fails if the focused event can't be fetched, e.g. if it's unknown.
+    pub async fn new(
+        room: &Room,
+        focused_event_id: Option<OwnedEventId>,
+        input_sender: Option<Sender<Input>>,
+    ) -> Result<Self, TimelineError> {
+        let mut timeline_builder = room.timeline_builder();
+
+        if let Some(focused_event_id) = focused_event_id.clone() {
+            timeline_builder = timeline_builder.with_focus(TimelineFocus::Event {
+                target: focused_event_id,
+                num_context_events: NUMBER_OF_CONTEXT_EVENTS,
+                hide_threaded_events: false,
+            });
+        }
+
+        let timeline = Arc::new(timeline_builder.build().await?);
+
+        let anchor_date = match &focused_event_id {
+            Some(focused_event_id) => timeline
+                .item_by_event_id(focused_event_id)
+                .await
+                .and_then(|event_item| event_item.timestamp().to_system_time())
+                .map(|time| DateTime::<Local>::from(time).date_naive()),
+            None => None,
+        };
         let client = room.client();
         let room_id = timeline.room().room_id().to_owned();
         let mut items = Vector::new();
+        let mut heights = Heights::default();
+
+        let own_read_receipt = match client.user_id() {
+            Some(own_user_id) => {
+                timeline.latest_user_read_receipt_timeline_event_id(own_user_id).await
+            }
+            None => None,
+        };
+
+        let _own_read_receipt_updates_handle = input_sender.clone().map(|input_sender| {
+            let timeline = timeline.clone();
+
+            task_ext::supervise_with_restart(
+                "own read receipt updates",
+                input_sender.clone(),
+                move || own_read_receipt_updates_task(timeline.clone(), input_sender.clone()),
+            )
+        });
+
+        let _pagination_status_updates_handle = input_sender.clone().map(|input_sender| {
+            let timeline = timeline.clone();
 
-        let _items_updates_handle = match input_sender {
+            task_ext::supervise_with_restart(
+                "pagination status updates",
+                input_sender.clone(),
+                move || pagination_status_updates_task(timeline.clone(), input_sender.clone()),
+            )
+        });
+
+        let _items_updates_handle = match input_sender.clone() {
             // Run the task to update the timeline items.
+            // Restarted with a reset of the items.
             Some(input_sender) => {
-                Some(spawn(items_updates_task(timeline.clone(), input_sender)).abort_on_drop())
+                let timeline = timeline.clone();
+
+                Some(task_ext::supervise_with_restart(
+                    "timeline items updates",
+                    input_sender.clone(),
+                    move || items_updates_task(timeline.clone(), input_sender.clone()),
+                ))
             }
             // Initialise the timeline items without listening to the stream of updates.
             None => {
                 let (initial_items, _) = timeline.subscribe().await;
 
+                heights.heights = Vector::from(vec![None; initial_items.len()]);
                 VectorDiff::Reset { values: initial_items }.apply(&mut items);
 
                 None
             }
         };
 
-        Self {
+        Ok(Self {
             timeline,
             client,
             room_id,
             items,
             linked_chunks: Vec::new(),
             _items_updates_handle,
+            _own_read_receipt_updates_handle,
+            _pagination_status_updates_handle,
+            fetch_members_handle: None,
+            own_read_receipt,
+            fully_read: None,
             scroll_position: Mutex::new(0),
+            scrolls_to_read_position: Mutex::new(focused_event_id.is_none()),
+            scroll_anchors: Mutex::new(Vec::new()),
+            number_of_new_messages: Mutex::new(0),
+            viewport_height: Mutex::new(0),
+            heights: Mutex::new(heights),
+            input_sender,
+            pagination: Mutex::new(Pagination::default()),
+            top_is_visible: Mutex::new(false),
+            pagination_status: None,
+            last_pagination_token: None,
+            number_of_events_before_pagination: 0,
+            last_pagination_gain: None,
+            pagination_bar_is_visible: false,
             details: Details::default(),
-        }
+            backup_decryptions: Vec::new(),
+            selected_event_id: Mutex::new(None),
+            read_receipts: None,
+            seen_by_everyone: HashSet::new(),
+            joined_member_ids: None,
+            unread_by: None,
+            relations: None,
+            shield_event_id: None,
+            local_echo_traces: VecDeque::new(),
+            diff_log: diff_log::Log::default(),
+            diff_log_notice: None,
+            validates_ordering: false,
+            ordering_violations: Vec::new(),
+            event_cache_consistency: None,
+            url_previews: url_preview::Cache::default(),
+            url_preview_handles: Vec::new(),
+            anchor_date,
+            revealed_spoilers: HashSet::new(),
+        })
     }
 
     pub async fn update(&mut self, message: Message) -> Option<app::Message> {
         match message {
             Message::Update(diffs) => {
                 let mut recompute_linked_chunks = false;
+                let mut heights = self.heights.lock();
+                let mut new_items = Vec::new();
+                let is_scrolled_up = *self.scroll_position.lock() > 0;
+                let mut number_of_new_messages = self.number_of_new_messages.lock();
 
                 for diff in diffs {
+                    if is_scrolled_up {
+                        *number_of_new_messages += self.count_new_messages_below_anchor(&diff);
+                    }
+
                     // If the diff is not `VectorDiff::Set`, we need to
                     // recompute the linked chunks.
                     if recompute_linked_chunks.not() && matches!(diff, VectorDiff::Set { .. }).not()
@@ -121,49 +506,108 @@ impl Model {
                         recompute_linked_chunks = true;
                     }
 
+                    // Updated items must be measured again.
+                    diff.clone().map(|_| None).apply(&mut heights.heights);
+                    // Collect the new items, to fetch their URL previews.
+                    diff.clone().map(|item| new_items.push(item));
+
+                    let mut entry = describe_diff(&diff, &self.items);
+                    entry.diff = tail::diff_to_json(&diff);
+                    let mut new_event_ids = Vec::new();
+                    diff.clone().map(|item| {
+                        new_event_ids.extend(
+                            item.as_event()
+                                .and_then(|event| event.event_id().map(ToOwned::to_owned)),
+                        )
+                    });
+
                     diff.apply(&mut self.items);
+
+                    entry.duplicated_event_ids = new_event_ids
+                        .into_iter()
+                        .filter(|event_id| {
+                            self.items
+                                .iter()
+                                .filter(|item| {
+                                    item.as_event().and_then(|event| event.event_id())
+                                        == Some(&**event_id)
+                                })
+                                .count()
+                                > 1
+                        })
+                        .unique()
+                        .collect();
+
+                    self.diff_log.push(entry);
                 }
 
-                if recompute_linked_chunks.not() {
-                    return None;
+                // The compact dividers are counted from the most recent message, so
+                // they move when items are added or removed.
+                if recompute_linked_chunks
+                    && matches!(divider::dividers(), divider::Dividers::Every(_))
+                {
+                    heights.invalidate();
                 }
 
-                reload_linked_chunks(
-                    &mut self.linked_chunks,
-                    &self.client,
-                    &self.room_id,
-                    self.items.iter().find_map(|item| item.as_event()?.event_id()),
-                )
-                .await?;
+                drop(heights);
+                drop(number_of_new_messages);
+
+                self.trace_local_echoes(&new_items);
+                self.fetch_members_if_needed();
+                self.fetch_url_previews(&new_items);
+                self.update_seen_by_everyone(&new_items).await;
+
+                if recompute_linked_chunks {
+                    // There is nothing to load without any event yet.
+                    let _ = reload_linked_chunks(
+                        &mut self.linked_chunks,
+                        &self.client,
+                        &self.room_id,
+                        self.items.iter().find_map(|item| item.as_event()?.event_id()),
+                    )
+                    .await;
+                }
+
+                if self.validates_ordering {
+                    self.validate_ordering();
+                }
             }
             Message::Scroll(scroll) => {
+                *self.scrolls_to_read_position.lock() = false;
+
                 let mut scroll_position = self.scroll_position.lock();
 
-                *scroll_position = match &self.details {
-                    Details::None | Details::EventId | Details::Origin => {
-                        update_scroll_position_for_timeline(&scroll, *scroll_position, &self.items)
-                    }
-                    Details::LinkedChunk => {
-                        update_scroll_position_for_linked_chunk(&scroll, *scroll_position)
-                    }
-                };
+                *scroll_position =
+                    update_scroll_position(&scroll, *scroll_position, *self.viewport_height.lock());
             }
             Message::PaginateBackwards => {
                 // TODO: do something with the result.
-                let _ = self.timeline.paginate_backwards(20).await;
+                let _ = self.timeline.paginate_backwards(NUMBER_OF_EVENTS_TO_PAGINATE).await;
             }
             Message::ShowDetails(details) => {
                 if matches!(
                     (&self.details, &details),
-                    (Details::None | Details::EventId, Details::LinkedChunk)
-                        | (Details::LinkedChunk, Details::None | Details::EventId)
+                    (
+                        Details::None | Details::EventId,
+                        Details::LinkedChunk
+                            | Details::DiffLog
+                            | Details::Ordering
+                            | Details::EventCacheConsistency
+                    ) | (
+                        Details::LinkedChunk
+                            | Details::DiffLog
+                            | Details::Ordering
+                            | Details::EventCacheConsistency,
+                        Details::None | Details::EventId
+                    )
                 ) {
                     *self.scroll_position.lock() = 0;
                 }
 
                 self.details = details;
+                self.heights.lock().invalidate();
             }
-            Message::ToggleReactionOnLastMessage => {
+            Message::ToggleReactionOnLastMessage(key) => {
                 if let Some(last_timeline_item_id) =
                     self.items.iter().rev().find_map(|timeline_item| {
                         timeline_item.as_event().and_then(|event_timeline_item| {
@@ -175,20 +619,794 @@ impl Model {
                         })
                     })
                 {
-                    self.timeline.toggle_reaction(&last_timeline_item_id, "👍").await.unwrap();
+                    // The reactions go through the send queue, which is enabled
+                    // again once rate-limited.
+                    let timeline = self.timeline.clone();
+
+                    spawn(async move {
+                        if let Err(error) =
+                            timeline.toggle_reaction(&last_timeline_item_id, &key).await
+                        {
+                            error!("Failed to toggle the reaction: {error}");
+                            bus::emit(bus::Command::Toast(format!(
+                                "Failed to toggle the reaction: {error}"
+                            )));
+                        }
+                    });
+                }
+            }
+            Message::ToggleSpoiler => {
+                let Some(event_id) = self.selected_event_id.lock().clone() else { return None };
+
+                if self.revealed_spoilers.remove(&event_id).not() {
+                    self.revealed_spoilers.insert(event_id.clone());
+                }
+
+                self.heights.lock().invalidate_event(&self.items, &event_id);
+            }
+            Message::ToggleOrderingValidation => {
+                self.validates_ordering = self.validates_ordering.not();
+                *self.scroll_position.lock() = 0;
+
+                if self.validates_ordering {
+                    self.validate_ordering();
+                    self.details = Details::Ordering;
+                } else {
+                    self.ordering_violations.clear();
+                    self.details = Details::None;
+                }
+
+                self.heights.lock().invalidate();
+            }
+            Message::CheckEventCache => {
+                // The linked chunk may be outdated, e.g. after a `VectorDiff::Set`.
+                let _ = reload_linked_chunks(
+                    &mut self.linked_chunks,
+                    &self.client,
+                    &self.room_id,
+                    self.items.iter().find_map(|item| item.as_event()?.event_id()),
+                )
+                .await;
+
+                self.event_cache_consistency =
+                    Some(event_cache_consistency(&self.linked_chunks, &self.items));
+                self.details = Details::EventCacheConsistency;
+                *self.scroll_position.lock() = 0;
+                self.heights.lock().invalidate();
+            }
+            Message::ExportDiffLog => {
+                self.diff_log_notice = Some(match self.diff_log.export() {
+                    Ok(path) => {
+                        let path = path.display().to_string();
+
+                        match clipboard::copy(&path) {
+                            Ok(()) => format!("exported to `{path}`, copied to the clipboard"),
+                            Err(_) => format!("exported to `{path}`"),
+                        }
+                    }
+                    Err(error) => format!("failed to export: {error}"),
+                });
+
+                if matches!(self.details, Details::DiffLog).not() {
+                    self.details = Details::DiffLog;
+                    *self.scroll_position.lock() = 0;
                 }
             }
+            Message::DecryptWithBackup => {
+                self.backup_decryptions = decrypt_with_backup(&self.timeline, &self.items).await;
+                self.details = Details::BackupDecryption;
+            }
+            Message::ShowReadReceipts => {
+                let selected_event_id = self.selected_event_id.lock().clone();
+
+                self.read_receipts = match selected_event_id {
+                    Some(event_id) => {
+                        let read_receipts =
+                            load_read_receipts(&self.timeline, &self.items, &event_id).await;
+
+                        Some((event_id, read_receipts))
+                    }
+                    None => None,
+                };
+                self.details = Details::ReadReceipts;
+            }
+            Message::ShowUnreadBy => {
+                let selected_event_id = self.selected_event_id.lock().clone();
+
+                self.unread_by = match selected_event_id {
+                    Some(event_id) => {
+                        let readers = readers_from(&self.items, &event_id);
+                        let unread_by = load_joined_members(self.timeline.room())
+                            .await
+                            .into_iter()
+                            .filter(|(user_id, _)| readers.contains(user_id).not())
+                            .collect();
+
+                        Some((event_id, unread_by))
+                    }
+                    None => None,
+                };
+                self.details = Details::UnreadBy;
+            }
+            Message::ShowRelations => {
+                let selected_event_id = self.selected_event_id.lock().clone();
+
+                self.relations = match selected_event_id {
+                    Some(event_id) => {
+                        let relations =
+                            load_relations(&self.timeline, &self.items, &event_id).await;
+
+                        Some((event_id, relations))
+                    }
+                    None => None,
+                };
+                self.details = Details::Relations;
+            }
+            Message::ShowShield => {
+                self.shield_event_id = self.selected_event_id.lock().clone();
+                self.details = Details::Shield;
+            }
+            Message::ShowReadMarkers => {
+                self.fully_read =
+                    match self.timeline.room().account_data_static::<FullyReadEventContent>().await
+                    {
+                        Ok(fully_read) => fully_read
+                            .and_then(|fully_read| fully_read.deserialize().ok())
+                            .map(|fully_read| fully_read.content.event_id),
+                        Err(error) => {
+                            error!("Failed to load the fully-read marker: {error}");
+                            None
+                        }
+                    };
+                self.details = Details::ReadMarkers;
+                self.heights.lock().invalidate();
+            }
+            Message::UpdateOwnReadReceipt(event_id) => {
+                // The items holding the previous and the new read receipts
+                // change of height, or all the items if their position
+                // relatively to the read receipt is shown.
+                let mut heights = self.heights.lock();
+
+                if let Details::ReadMarkers = self.details {
+                    heights.invalidate();
+                }
+
+                for event_id in self.own_read_receipt.iter().chain(event_id.iter()) {
+                    heights.invalidate_event(&self.items, event_id);
+                }
+
+                drop(heights);
+
+                self.own_read_receipt = event_id;
+            }
+            Message::UpdatePaginationStatus(status) => {
+                let number_of_events =
+                    self.items.iter().filter(|item| item.as_event().is_some()).count();
+
+                match status {
+                    RoomPaginationStatus::Paginating => {
+                        self.last_pagination_token =
+                            load_pagination_token(&self.linked_chunks, &self.client, &self.room_id)
+                                .await;
+                        self.number_of_events_before_pagination = number_of_events;
+                    }
+                    RoomPaginationStatus::Idle { .. } => {
+                        if let Some(RoomPaginationStatus::Paginating) = self.pagination_status {
+                            self.last_pagination_gain = Some(
+                                number_of_events
+                                    .saturating_sub(self.number_of_events_before_pagination),
+                            );
+                        }
+                    }
+                }
+
+                self.pagination_status = Some(status);
+            }
+            Message::TogglePaginationBar => {
+                self.pagination_bar_is_visible = self.pagination_bar_is_visible.not();
+            }
+            Message::UpdateUrlPreview(url, preview) => {
+                self.url_preview_handles.retain(|(pending_url, _)| *pending_url != url);
+                self.url_previews.insert(url, preview);
+                self.heights.lock().invalidate();
+            }
         }
 
         None
     }
 
     pub fn render(&self, area: Rect, buffer: &mut Buffer) {
-        if let Details::LinkedChunk = &self.details {
-            self.render_linked_chunk(area, buffer);
+        let area = if self.pagination_bar_is_visible {
+            let [bar_area, area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Percentage(100)]).areas(area);
+
+            self.render_pagination_bar(bar_area, buffer);
+
+            area
         } else {
-            self.render_timeline(area, buffer);
+            area
+        };
+
+        match &self.details {
+            Details::LinkedChunk => self.render_linked_chunk(area, buffer),
+            Details::LocalEchoes => self.render_local_echoes(area, buffer),
+            Details::DiffLog => self.render_diff_log(area, buffer),
+            Details::Ordering => self.render_ordering_violations(area, buffer),
+            Details::EventCacheConsistency => self.render_event_cache_consistency(area, buffer),
+            Details::BackupDecryption => self.render_backup_decryption(area, buffer),
+            Details::None | Details::EventId | Details::Origin | Details::ReadMarkers => {
+                self.render_timeline(area, buffer)
+            }
+            Details::ReadReceipts => {
+                self.render_timeline(area, buffer);
+                self.render_read_receipts(area, buffer);
+            }
+            Details::UnreadBy => {
+                self.render_timeline(area, buffer);
+                self.render_unread_by(area, buffer);
+            }
+            Details::Relations => {
+                self.render_timeline(area, buffer);
+                self.render_relations(area, buffer);
+            }
+            Details::Shield => {
+                self.render_timeline(area, buffer);
+                self.render_shield(area, buffer);
+            }
+        }
+    }
+
+    /// The back-pagination status, the last token used, and the number of
+    /// events gained by the last back-pagination.
+    fn render_pagination_bar(&self, area: Rect, buffer: &mut Buffer) {
+        let status = match self.pagination_status {
+            Some(RoomPaginationStatus::Idle { hit_timeline_start: false }) => {
+                Span::raw("idle").green()
+            }
+            Some(RoomPaginationStatus::Idle { hit_timeline_start: true }) => {
+                Span::raw("hit start").yellow()
+            }
+            Some(RoomPaginationStatus::Paginating) => Span::raw("paginating").cyan(),
+            None => Span::raw("unavailable, the timeline is focused").dark_gray(),
+        };
+        let token = match &self.last_pagination_token {
+            Some(token) => format!("`{token}`"),
+            None => "none".to_owned(),
+        };
+        let gain = match self.last_pagination_gain {
+            Some(gain) => format!("+{gain} events"),
+            None => "n/a".to_owned(),
+        };
+
+        Line::from(vec![
+            Span::raw(" back-pagination: "),
+            status,
+            Span::raw(format!(" · last prev_batch: {token} · last gain: {gain}")),
+        ])
+        .style(Style::default().bg(Color::Indexed(236)))
+        .render(area, buffer);
+    }
+
+    /// Where `item` is, relatively to the fully-read marker and to the own read
+    /// receipt.
+    fn render_read_markers_position(&self, item: &TimelineItem) -> Line<'static> {
+        let position_of = |event_id: &Option<OwnedEventId>| {
+            let event_id = event_id.as_deref()?;
+
+            self.items.iter().position(|item| {
+                item.as_event().and_then(|event| event.event_id()) == Some(event_id)
+            })
+        };
+        let item_position =
+            self.items.iter().position(|other_item| other_item.unique_id() == item.unique_id());
+
+        let describe = |marker_position: Option<usize>| match (item_position, marker_position) {
+            (Some(item_position), Some(marker_position)) => {
+                match item_position.cmp(&marker_position) {
+                    Ordering::Less => Span::styled("before", Style::default().green()),
+                    Ordering::Equal => Span::styled("at", Style::default().yellow().bold()),
+                    Ordering::Greater => Span::styled("after", Style::default().red()),
+                }
+            }
+            _ => Span::styled("not loaded", Style::default().dark_gray()),
+        };
+
+        Line::from(vec![
+            Span::raw("fully read: ").dark_gray(),
+            describe(position_of(&self.fully_read)),
+            Span::raw(" · read receipt: ").dark_gray(),
+            describe(position_of(&self.own_read_receipt)),
+        ])
+    }
+
+    pub fn render_read_receipts(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+
+        let Some((event_id, read_receipts)) = &self.read_receipts else {
+            Paragraph::new("No event selected")
+                .centered()
+                .block(block::block_with_title("Read receipts"))
+                .render(area, buffer);
+
+            return;
+        };
+
+        let title = format!("Read receipts of {}", format_event_id(event_id.clone()));
+
+        if read_receipts.is_empty() {
+            Paragraph::new("No read receipt")
+                .centered()
+                .block(block::block_with_title(&title))
+                .render(area, buffer);
+
+            return;
+        }
+
+        let rows = read_receipts.iter().map(|read_receipt| {
+            Row::new([
+                Cell::new(read_receipt.user_id.as_str()),
+                Cell::new(read_receipt.display_name.as_deref().unwrap_or("")),
+                Cell::new(read_receipt.receipt_type.as_str()),
+                Cell::new(
+                    read_receipt
+                        .timestamp
+                        .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                        .unwrap_or_else(|| "???".to_owned()),
+                ),
+            ])
+        });
+
+        Table::new(
+            rows,
+            [
+                Constraint::Percentage(35),
+                Constraint::Percentage(25),
+                Constraint::Length(15),
+                Constraint::Length(23),
+            ],
+        )
+        .header(
+            Row::new(["User", "Display name", "Type", "Timestamp"]).style(Style::default().bold()),
+        )
+        .block(block::block_with_title(&title))
+        .render(area, buffer);
+    }
+
+    pub fn render_unread_by(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+
+        let Some((event_id, unread_by)) = &self.unread_by else {
+            Paragraph::new("No event selected")
+                .centered()
+                .block(block::block_with_title("Not read by"))
+                .render(area, buffer);
+
+            return;
+        };
+
+        let title = format!("Members who haven't read {}", format_event_id(event_id.clone()));
+
+        if unread_by.is_empty() {
+            Paragraph::new("Read by all the joined members")
+                .centered()
+                .green()
+                .block(block::block_with_title(&title))
+                .render(area, buffer);
+
+            return;
+        }
+
+        let rows = unread_by.iter().map(|(user_id, display_name)| {
+            Row::new([
+                Cell::new(user_id.as_str()),
+                Cell::new(display_name.as_deref().unwrap_or("")),
+            ])
+        });
+
+        Table::new(rows, [Constraint::Percentage(60), Constraint::Percentage(40)])
+            .header(Row::new(["User", "Display name"]).style(Style::default().bold()))
+            .block(block::block_with_title(&title))
+            .render(area, buffer);
+    }
+
+    pub fn render_relations(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+
+        let Some((event_id, relations)) = &self.relations else {
+            Paragraph::new("No event selected")
+                .centered()
+                .block(block::block_with_title("Relations"))
+                .render(area, buffer);
+
+            return;
+        };
+
+        let title = format!("Relations of {}", format_event_id(event_id.clone()));
+        let text = relations
+            .iter()
+            .map(|(depth, label)| {
+                let style = if *depth == 0 {
+                    Style::default().bold()
+                } else if depth % 2 == 1 {
+                    Style::default().yellow()
+                } else {
+                    Style::default()
+                };
+
+                Line::from(vec![
+                    Span::raw(str::repeat("  ", *depth)),
+                    Span::raw(if *depth == 0 { "" } else { "└ " }).dark_gray(),
+                    Span::styled(label.as_str(), style),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Paragraph::new(text).block(block::block_with_title(&title)).render(area, buffer);
+    }
+
+    pub fn render_shield(&self, area: Rect, buffer: &mut Buffer) {
+        let [area] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+
+        let Some((event_id, event_item)) = self.shield_event_id.as_ref().and_then(|event_id| {
+            let event_item = self.items.iter().find_map(|item| {
+                item.as_event().filter(|event_item| event_item.event_id() == Some(&**event_id))
+            })?;
+
+            Some((event_id, event_item))
+        }) else {
+            Paragraph::new("No event selected")
+                .centered()
+                .block(block::block_with_title("Shield"))
+                .render(area, buffer);
+
+            return;
+        };
+
+        let title = format!("Shield of {}", format_event_id(event_id.clone()));
+        let mut text = Text::default();
+
+        text.push_line(match event_item.get_shield(true) {
+            Some(ShieldState::Red { code, message }) => {
+                Line::from(format!("red shield, {code:?}: {message}")).red().bold()
+            }
+            Some(ShieldState::Grey { code, message }) => {
+                Line::from(format!("grey shield, {code:?}: {message}")).gray().bold()
+            }
+            Some(ShieldState::None) => {
+                Line::from("no shield: sent by a verified device").green().bold()
+            }
+            None if event_item.is_local_echo() => {
+                Line::from("no shield: this is a local echo").bold()
+            }
+            None if event_item.content().is_unable_to_decrypt() => {
+                Line::from("no shield: unable to decrypt, the authenticity is unknown").bold()
+            }
+            None => Line::from("no shield: the room is not encrypted").bold(),
+        });
+
+        if let Some(encryption_info) = event_item.encryption_info() {
+            text.push_line("");
+            text.push_line(format!("sender: {}", encryption_info.sender));
+            text.push_line(format!(
+                "sender device: {}",
+                encryption_info
+                    .sender_device
+                    .as_ref()
+                    .map_or_else(|| "unknown".to_owned(), ToString::to_string)
+            ));
+            text.push_line(match &encryption_info.verification_state {
+                VerificationState::Verified => "verification state: verified".to_owned(),
+                VerificationState::Unverified(level) => {
+                    format!("verification state: unverified, {level}")
+                }
+            });
+
+            match &encryption_info.algorithm_info {
+                AlgorithmInfo::MegolmV1AesSha2 { curve25519_key, session_id, .. } => {
+                    text.push_line("algorithm: m.megolm.v1.aes-sha2");
+                    text.push_line(format!(
+                        "session: {}",
+                        session_id.as_deref().unwrap_or("unknown")
+                    ));
+                    text.push_line(format!("sender key: {curve25519_key}"));
+                }
+                AlgorithmInfo::OlmV1Curve25519AesSha2 { curve25519_public_key_base64 } => {
+                    text.push_line("algorithm: m.olm.v1.curve25519-aes-sha2");
+                    text.push_line(format!("sender key: {curve25519_public_key_base64}"));
+                }
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(block::block_with_title(&title))
+            .render(area, buffer);
+    }
+
+    pub fn render_local_echoes(&self, area: Rect, buffer: &mut Buffer) {
+        let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+
+        if self.local_echo_traces.is_empty() {
+            Paragraph::new("No local echo since the room has been opened")
+                .centered()
+                .render(area, buffer);
+
+            return;
+        }
+
+        let mut text = Text::default();
+
+        // The most recent first.
+        for trace in self.local_echo_traces.iter().rev() {
+            text.push_line(Line::styled(
+                trace.transaction_id.to_string(),
+                Style::default().green().bold(),
+            ));
+
+            let mut previous_time = None;
+
+            for (time, state) in &trace.transitions {
+                let elapsed = match previous_time {
+                    Some(previous_time) => {
+                        format!("+{}ms", (*time - previous_time).num_milliseconds())
+                    }
+                    None => String::new(),
+                };
+
+                text.push_line(Line::from(vec![
+                    Span::raw(format!("  {} ", time.format("%H:%M:%S%.3f"))).dark_gray(),
+                    Span::raw(format!("{elapsed:>8} ")).yellow(),
+                    Span::raw(state.as_str()),
+                ]));
+
+                previous_time = Some(*time);
+            }
+
+            text.push_line("");
+        }
+
+        Paragraph::new(text).render(area, buffer);
+    }
+
+    pub fn render_diff_log(&self, area: Rect, buffer: &mut Buffer) {
+        let scrollbar_area = area;
+        let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+
+        if self.diff_log.is_empty() {
+            Paragraph::new("No diff since the room has been opened")
+                .centered()
+                .render(area, buffer);
+
+            return;
+        }
+
+        let mut text = Text::default();
+
+        if let Some(notice) = &self.diff_log_notice {
+            text.push_line(Line::from(notice.as_str()).yellow());
+        }
+
+        // The most recent last, like in the timeline.
+        for entry in self.diff_log.entries() {
+            let mut line = Line::from(vec![
+                Span::raw(format!("{} ", entry.time.format("%H:%M:%S%.3f"))).dark_gray(),
+                Span::raw(format!("{:<14}", entry.kind)).yellow(),
+                Span::raw(entry.items.join(", ")),
+            ]);
+
+            if entry.duplicated_event_ids.is_empty().not() {
+                line.push_span(
+                    Span::raw(format!(
+                        "  duplicated: {}",
+                        entry.duplicated_event_ids.iter().cloned().map(format_event_id).join(", ")
+                    ))
+                    .red()
+                    .bold(),
+                );
+            }
+
+            text.push_line(line);
+        }
+
+        self.render_scrollable_text(text, area, scrollbar_area, buffer);
+    }
+
+    pub fn render_ordering_violations(&self, area: Rect, buffer: &mut Buffer) {
+        let scrollbar_area = area;
+        let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+
+        if self.ordering_violations.is_empty() {
+            Paragraph::new(
+                "The ordering of the timeline and of the linked chunk is validated: no violation",
+            )
+            .centered()
+            .render(area, buffer);
+
+            return;
+        }
+
+        let mut text = Text::default();
+
+        text.push_line(
+            Line::from(format!("{} ordering violations", self.ordering_violations.len()))
+                .red()
+                .bold(),
+        );
+
+        for violation in &self.ordering_violations {
+            text.push_line("");
+            text.push_line(Line::from(vec![
+                Span::raw(format!("{}: ", violation.location)).dark_gray(),
+                Span::raw(violation.explanation.as_str()),
+            ]));
+            text.push_line(
+                Line::from(format!(
+                    "  {}",
+                    violation.event_ids.iter().map(|event_id| event_id.as_str()).join(", ")
+                ))
+                .red(),
+            );
+        }
+
+        self.render_scrollable_text(text, area, scrollbar_area, buffer);
+    }
+
+    pub fn render_event_cache_consistency(&self, area: Rect, buffer: &mut Buffer) {
+        let scrollbar_area = area;
+        let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+
+        let Some(consistency) = &self.event_cache_consistency else {
+            Paragraph::new("Run `:check event-cache` to compare the event cache with the timeline")
+                .centered()
+                .render(area, buffer);
+
+            return;
+        };
+
+        let mut text = Text::default();
+
+        text.push_line(
+            Line::from(format!("Event cache checked at {}", consistency.time.format("%H:%M:%S")))
+                .bold(),
+        );
+
+        if consistency.only_in_event_cache.is_empty() && consistency.only_in_timeline.is_empty() {
+            text.push_line(
+                Line::from("The timeline has the same events as the event cache").green(),
+            );
+        }
+
+        if consistency.only_in_event_cache.is_empty().not() {
+            text.push_line("");
+            text.push_line(Line::from(format!(
+                "{} events in the event cache, not in the timeline:",
+                consistency.only_in_event_cache.len()
+            )));
+
+            for (event_id, event_type, is_aggregated) in &consistency.only_in_event_cache {
+                let line = Line::from(format!("  {event_id} {event_type}"));
+
+                text.push_line(if *is_aggregated { line.dark_gray() } else { line.yellow() });
+            }
+        }
+
+        if consistency.only_in_timeline.is_empty().not() {
+            text.push_line("");
+            text.push_line(Line::from(format!(
+                "{} events in the timeline, not in the event cache:",
+                consistency.only_in_timeline.len()
+            )));
+
+            for event_id in &consistency.only_in_timeline {
+                text.push_line(Line::from(format!("  {event_id}")).red());
+            }
         }
+
+        self.render_scrollable_text(text, area, scrollbar_area, buffer);
+    }
+
+    /// Render `text` in `area`, the most recent lines last, scrolled by the
+    /// scroll position from the bottom, with a scrollbar in `scrollbar_area`
+    /// if it doesn't fit.
+    fn render_scrollable_text(
+        &self,
+        text: Text<'_>,
+        area: Rect,
+        scrollbar_area: Rect,
+        buffer: &mut Buffer,
+    ) {
+        let text_height = text.height();
+        let paragraph = Paragraph::new(text);
+        let area_height = area.height as usize;
+        *self.viewport_height.lock() = area_height;
+
+        if area_height >= text_height {
+            paragraph.render(area, buffer);
+
+            return;
+        }
+
+        let scroll_length = text_height - area_height;
+
+        let mut scroll_position = self.scroll_position.lock();
+        *scroll_position = min(*scroll_position, scroll_length);
+        let scroll_position = *scroll_position;
+
+        let mut state =
+            ScrollbarState::new(scroll_length).position(scroll_length - scroll_position);
+
+        StatefulWidget::render(
+            scrollbar::scrollbar(ScrollbarOrientation::VerticalRight),
+            scrollbar_area,
+            buffer,
+            &mut state,
+        );
+
+        paragraph.scroll(((scroll_length - scroll_position) as u16, 0)).render(area, buffer);
+    }
+
+    pub fn render_backup_decryption(&self, area: Rect, buffer: &mut Buffer) {
+        let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+
+        if self.backup_decryptions.is_empty() {
+            Paragraph::new("No UTD to decrypt").centered().render(area, buffer);
+
+            return;
+        }
+
+        let mut text = Text::default();
+
+        text.push_line(Line::from(format!(
+            "Last {} UTDs, decrypted with the backup only:",
+            self.backup_decryptions.len()
+        )));
+        text.push_line("");
+
+        for (event_id, decryption) in &self.backup_decryptions {
+            let decryption = match decryption {
+                BackupDecryption::Decrypted => {
+                    Span::styled("decrypted by the backup", Style::default().green().bold())
+                }
+                BackupDecryption::StillUnableToDecrypt => Span::styled(
+                    "key found in the backup, but unable to decrypt",
+                    Style::default().red().bold(),
+                ),
+                BackupDecryption::NotInBackup(error) => Span::styled(
+                    format!("key not found in the backup ({error})"),
+                    Style::default().red(),
+                ),
+                BackupDecryption::BackupUnavailable => {
+                    Span::styled("backup is not enabled", Style::default().yellow())
+                }
+                BackupDecryption::NotMegolm => {
+                    Span::styled("not a Megolm event", Style::default().dark_gray())
+                }
+            };
+
+            text.push_line(Line::from(vec![
+                Span::raw(format_event_id(event_id.clone())),
+                Span::raw(" "),
+                decryption,
+            ]));
+        }
+
+        Paragraph::new(text).render(area, buffer);
     }
 
     pub fn render_linked_chunk(&self, area: Rect, buffer: &mut Buffer) {
@@ -276,6 +1494,7 @@ impl Model {
         let text_height = text.height();
         let paragraph = Paragraph::new(text);
         let area_height = area.height as usize;
+        *self.viewport_height.lock() = area_height;
 
         if area_height > text_height {
             paragraph
@@ -302,29 +1521,159 @@ impl Model {
     }
 
     pub fn render_timeline(&self, area: Rect, buffer: &mut Buffer) {
-        let mut list_total_height: usize = 0;
-        let mut list_skipped_height: usize = 0;
-        let scroll_position = *self.scroll_position.lock();
-        let list = List::new(
-            self.items
-                .iter()
-                .rev()
-                .enumerate()
-                .filter_map(|(nth, item)| Some((nth, self.render_timeline_item(item, &area)?)))
-                .inspect(|(_nth, item)| {
-                    list_total_height += item.height();
-                })
-                .skip_while(|(nth, item)| {
-                    list_skipped_height += item.height();
+        let viewport_height = area.height as usize;
+        *self.viewport_height.lock() = viewport_height;
 
-                    *nth < scroll_position
-                })
-                .map(|(_, item)| item),
-        )
-        .direction(ListDirection::BottomToTop);
+        let mut scroll_position = self.scroll_position.lock();
+        let mut heights = self.heights.lock();
+
+        if heights.width != area.width {
+            heights.width = area.width;
+            heights.invalidate();
+        }
+
+        {
+            let mut scrolls_to_read_position = self.scrolls_to_read_position.lock();
 
-        let mut state = ScrollbarState::new(list_total_height)
-            .position(list_total_height - list_skipped_height + 1);
+            if *scrolls_to_read_position && self.items.is_empty().not() {
+                *scrolls_to_read_position = false;
+
+                if let Some(height) = self.height_from_read_position(&mut heights, &area) {
+                    *scroll_position =
+                        (height + READ_POSITION_CONTEXT_HEIGHT).saturating_sub(viewport_height);
+                }
+            }
+        }
+
+        // Keep the viewport stable when the items below it have changed since
+        // the last render, unless it sticks to the bottom.
+        if *scroll_position > 0 {
+            let anchor = self.scroll_anchors.lock().iter().find_map(|anchor| {
+                let index =
+                    self.items.iter().position(|item| item.unique_id() == &anchor.unique_id)?;
+
+                Some((index, anchor.height_below))
+            });
+
+            if let Some((index, previous_height_below)) = anchor {
+                let height_below = self.height_from(index + 1, &mut heights, &area);
+
+                *scroll_position = scroll_position
+                    .saturating_add(height_below)
+                    .saturating_sub(previous_height_below);
+            }
+        } else {
+            *self.number_of_new_messages.lock() = 0;
+        }
+
+        // The scroll position is the number of lines below the viewport. Walk
+        // the items from the bottom: the items entirely below the viewport are
+        // skipped, and rendered only if their height is unknown; the items
+        // inside the viewport are rendered.
+        let mut below_height = 0;
+        let mut texts = Vec::new();
+        let mut texts_height = 0;
+        let mut has_reached_top = true;
+        let mut selected_event_id = self.selected_event_id.lock();
+        *selected_event_id = None;
+        let mut scroll_anchors = self.scroll_anchors.lock();
+        scroll_anchors.clear();
+
+        for (index, item) in self.items.iter().enumerate().rev() {
+            if below_height + texts_height >= scroll_position.saturating_add(viewport_height) {
+                has_reached_top = false;
+
+                break;
+            }
+
+            let is_below_viewport =
+                |height: usize| texts.is_empty() && below_height + height <= *scroll_position;
+
+            if let Some(height) = heights
+                .heights
+                .get(index)
+                .copied()
+                .flatten()
+                .filter(|height| is_below_viewport(*height))
+            {
+                below_height += height;
+
+                continue;
+            }
+
+            let text = self.render_timeline_item(item, &area);
+            let height = text.as_ref().map_or(0, Text::height);
+            heights.heights.set(index, Some(height));
+
+            if is_below_viewport(height) {
+                below_height += height;
+
+                continue;
+            }
+
+            scroll_anchors.push(ScrollAnchor {
+                unique_id: item.unique_id().to_owned(),
+                height_below: below_height + texts_height,
+            });
+
+            // The selected event is the most recent remote event in the
+            // viewport.
+            if let Some(event_id) = item.as_event().and_then(|event| event.event_id()) {
+                selected_event_id.get_or_insert_with(|| event_id.to_owned());
+            }
+
+            texts_height += height;
+            texts.extend(text);
+        }
+
+        // Do not scroll past the top of the timeline.
+        if has_reached_top {
+            let max_scroll_position = (below_height + texts_height).saturating_sub(viewport_height);
+
+            if *scroll_position > max_scroll_position {
+                *scroll_position = max_scroll_position;
+
+                drop(selected_event_id);
+                drop(scroll_anchors);
+                drop(heights);
+                drop(scroll_position);
+
+                return self.render_timeline(area, buffer);
+            }
+        }
+
+        // Older messages are loaded automatically when the top of the timeline
+        // is visible, once rendered.
+        *self.top_is_visible.lock() = has_reached_top;
+
+        if has_reached_top && self.pagination.lock().is_running() {
+            let frame = self
+                .pagination
+                .lock()
+                .started_at
+                .map(|started_at| started_at.elapsed().as_millis() / SPINNER_INTERVAL.as_millis())
+                .unwrap_or_default() as usize;
+
+            texts.push(
+                Text::styled(
+                    format!("{} loading older messages…", SPINNER[frame % SPINNER.len()]),
+                    Style::default().dark_gray().italic(),
+                )
+                .centered(),
+            );
+            texts_height += 1;
+        }
+
+        let height_of = |height: &Option<usize>| height.unwrap_or(ESTIMATED_ITEM_HEIGHT);
+        let total_height: usize = heights.heights.iter().map(height_of).sum();
+        let scroll_position = *scroll_position;
+
+        drop(selected_event_id);
+        drop(scroll_anchors);
+        drop(heights);
+
+        let mut state = ScrollbarState::new(total_height)
+            .position(total_height.saturating_sub(scroll_position));
 
         StatefulWidget::render(
             scrollbar::scrollbar(ScrollbarOrientation::VerticalRight),
@@ -333,21 +1682,450 @@ impl Model {
             &mut state,
         );
 
+        // Flatten the items, from the top, keeping their own alignment.
+        let lines = texts
+            .into_iter()
+            .rev()
+            .flat_map(|text| {
+                let alignment = text.alignment;
+
+                text.lines.into_iter().map(move |mut line| {
+                    line.alignment = line.alignment.or(alignment);
+                    line
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Hide the lines below the viewport, and stick the lines to the bottom
+        // if they don't fill the viewport.
+        let visible_height = texts_height - (scroll_position - below_height);
         let area = area.inner(Margin { horizontal: 2, vertical: 0 });
+        let area = if visible_height < viewport_height {
+            let offset = (viewport_height - visible_height) as u16;
+
+            Rect { y: area.y + offset, height: area.height - offset, ..area }
+        } else {
+            area
+        };
+
+        Paragraph::new(lines)
+            .scroll((visible_height.saturating_sub(viewport_height) as u16, 0))
+            .render(area, buffer);
+
+        let number_of_new_messages = *self.number_of_new_messages.lock();
 
-        Widget::render(list, area, buffer);
+        if number_of_new_messages > 0 {
+            let pill = Line::from(match number_of_new_messages {
+                1 => " 1 new message ↓ ".to_owned(),
+                number => format!(" {number} new messages ↓ "),
+            })
+            .black()
+            .on_yellow();
+            let [pill_area] = Layout::horizontal([Constraint::Length(pill.width() as u16)])
+                .flex(Flex::Center)
+                .areas(Rect { y: area.bottom().saturating_sub(1), height: 1, ..area });
+
+            pill.render(pill_area, buffer);
+        }
+    }
+
+    /// The number of messages added below the scroll anchor by `diff`.
+    fn count_new_messages_below_anchor(&self, diff: &VectorDiff<Arc<TimelineItem>>) -> usize {
+        let is_message = |item: &Arc<TimelineItem>| {
+            item.as_event().is_some_and(|event| event.content().as_msglike().is_some())
+        };
+        let anchor_index = self.scroll_anchors.lock().iter().find_map(|anchor| {
+            self.items.iter().position(|item| item.unique_id() == &anchor.unique_id)
+        });
+
+        match diff {
+            VectorDiff::PushBack { value } => usize::from(is_message(value)),
+            VectorDiff::Append { values } => {
+                values.iter().filter(|value| is_message(*value)).count()
+            }
+            VectorDiff::Insert { index, value } => usize::from(
+                anchor_index.is_some_and(|anchor_index| *index > anchor_index) && is_message(value),
+            ),
+            _ => 0,
+        }
+    }
+
+    /// The most recent remote event in the viewport, as of the last render.
+    pub fn selected_event_id(&self) -> Option<OwnedEventId> {
+        self.selected_event_id.lock().clone()
+    }
+
+    /// The Megolm sessions of the UTDs.
+    pub fn utd_session_ids(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter_map(|item| match item.as_event()?.content().as_unable_to_decrypt()? {
+                EncryptedMessage::MegolmV1AesSha2 { session_id, .. } => Some(session_id.clone()),
+                _ => None,
+            })
+            .unique()
+            .collect()
+    }
+
+    /// Find the own events read by all the other joined members: a member has
+    /// read an event if they have a read receipt on it or on a later event, or
+    /// if they have sent a later event. Only if `new_items` may change them,
+    /// i.e. if they are own events, or have read receipts.
+    async fn update_seen_by_everyone(&mut self, new_items: &[Arc<TimelineItem>]) {
+        let new_events = new_items.iter().filter_map(|item| item.as_event());
+
+        if new_events
+            .clone()
+            .any(|event| matches!(event.content(), TimelineItemContent::MembershipChange(_)))
+        {
+            self.joined_member_ids = None;
+        }
+
+        // The event of another member carries the implicit read receipt of its
+        // sender.
+        if self.joined_member_ids.is_some()
+            && new_events
+                .clone()
+                .any(|event| event.is_own() || event.read_receipts().is_empty().not())
+                .not()
+        {
+            return;
+        }
+
+        if self.joined_member_ids.is_none() {
+            self.joined_member_ids = Some(
+                load_joined_members(self.timeline.room())
+                    .await
+                    .into_iter()
+                    .map(|(user_id, _)| user_id)
+                    .collect(),
+            );
+        }
+
+        let Some(joined_member_ids) = &self.joined_member_ids else {
+            return;
+        };
+
+        self.seen_by_everyone.clear();
+
+        // Alone in the room, nobody else can read the events.
+        if joined_member_ids.len() < 2 {
+            return;
+        }
+
+        let mut readers = HashSet::new();
+
+        for item in self.items.iter().rev() {
+            let Some(event_item) = item.as_event() else {
+                continue;
+            };
+
+            readers.extend(event_item.read_receipts().keys().cloned());
+            readers.insert(event_item.sender().to_owned());
+
+            let Some(event_id) = event_item.event_id().filter(|_| event_item.is_own()) else {
+                continue;
+            };
+
+            if joined_member_ids.iter().all(|user_id| readers.contains(user_id)) {
+                self.seen_by_everyone.insert(event_id.to_owned());
+            }
+        }
+    }
+
+    /// Fetch the room members once, if some sender profiles are unavailable.
+    /// The timeline updates the items with the resolved profiles.
+    fn fetch_members_if_needed(&mut self) {
+        if self.input_sender.is_none() || self.fetch_members_handle.is_some() {
+            return;
+        }
+
+        let has_unavailable_profiles = self.items.iter().any(|item| {
+            item.as_event()
+                .is_some_and(|event| matches!(event.sender_profile(), TimelineDetails::Unavailable))
+        });
+
+        if has_unavailable_profiles {
+            let timeline = self.timeline.clone();
+
+            self.fetch_members_handle =
+                Some(spawn(async move { timeline.fetch_members().await }).abort_on_drop());
+        }
+    }
+
+    /// Check the ordering invariants of the items and of the linked chunk, and
+    /// show the violations instead of the timeline as soon as there are new
+    /// ones.
+    fn validate_ordering(&mut self) {
+        let mut violations = timeline_ordering_violations(&self.items);
+        violations.extend(linked_chunk_ordering_violations(&self.linked_chunks, &self.items));
+
+        if violations.len() > self.ordering_violations.len() {
+            error!("{} ordering violations in the timeline of {}", violations.len(), self.room_id);
+
+            if matches!(self.details, Details::Ordering).not() {
+                self.details = Details::Ordering;
+                *self.scroll_position.lock() = 0;
+            }
+        }
+
+        self.ordering_violations = violations;
+    }
+
+    /// Record the send state of the local echoes in `items`, and the arrival of
+    /// their remote echo, which keeps the same unique ID.
+    fn trace_local_echoes(&mut self, items: &[Arc<TimelineItem>]) {
+        for item in items {
+            let Some(event_item) = item.as_event() else {
+                continue;
+            };
+
+            let state = match event_item.send_state() {
+                Some(EventSendState::NotSentYet { progress: None }) => "not sent yet".to_owned(),
+                Some(EventSendState::NotSentYet { progress: Some(progress) }) => {
+                    format!("uploading, {}/{}", progress.progress.current, progress.progress.total)
+                }
+                Some(EventSendState::SendingFailed { error, is_recoverable }) => format!(
+                    "sending failed, {}: {error}",
+                    if *is_recoverable { "recoverable" } else { "unrecoverable" }
+                ),
+                Some(EventSendState::Sent { event_id }) => format!("sent as {event_id}"),
+                None => "remote echo".to_owned(),
+            };
+
+            let index = self
+                .local_echo_traces
+                .iter()
+                .position(|trace| trace.unique_id == *item.unique_id());
+
+            let trace = match (index, event_item.transaction_id()) {
+                (Some(index), _) => &mut self.local_echo_traces[index],
+                (None, Some(transaction_id)) => {
+                    if self.local_echo_traces.len() == MAXIMUM_NUMBER_OF_LOCAL_ECHO_TRACES {
+                        self.local_echo_traces.pop_front();
+                    }
+
+                    self.local_echo_traces.push_back(LocalEchoTrace {
+                        unique_id: item.unique_id().clone(),
+                        transaction_id: transaction_id.to_owned(),
+                        transitions: Vec::new(),
+                    });
+                    self.local_echo_traces.back_mut().unwrap()
+                }
+                // Not a local echo.
+                (None, None) => continue,
+            };
+
+            if trace.transitions.last().is_none_or(|(_, last_state)| *last_state != state) {
+                trace.transitions.push((Local::now(), state));
+            }
+        }
+    }
+
+    /// The approximate memory used by the items, the linked chunk, and the diff
+    /// log.
+    pub fn memory_usage(&self) -> Vec<mode::memory::Usage> {
+        let linked_chunk_size = self
+            .linked_chunks
+            .iter()
+            .map(|(_, chunk_content)| match chunk_content {
+                ChunkContent::Items(events) => events
+                    .iter()
+                    .map(|event| mem::size_of_val(event) + event.raw().json().get().len())
+                    .sum(),
+                ChunkContent::Gap(prev_token) => prev_token.len(),
+            })
+            .sum();
+
+        vec![
+            mode::memory::Usage {
+                name: "timeline items",
+                count: self.items.len(),
+                size: self
+                    .items
+                    .iter()
+                    .map(|item| {
+                        mem::size_of::<TimelineItem>()
+                            + item
+                                .as_event()
+                                .and_then(|event_item| event_item.original_json())
+                                .map_or(0, |raw| raw.json().get().len())
+                    })
+                    .sum(),
+            },
+            mode::memory::Usage {
+                name: "linked chunks",
+                count: self.linked_chunks.len(),
+                size: linked_chunk_size,
+            },
+            mode::memory::Usage {
+                name: "diff log",
+                count: self.diff_log.len(),
+                size: self.diff_log.estimated_size(),
+            },
+        ]
+    }
+
+    /// Fetch the previews of the URLs in the messages of `items`, if they are
+    /// not already known.
+    fn fetch_url_previews(&mut self, items: &[Arc<TimelineItem>]) {
+        let Some(input_sender) = &self.input_sender else {
+            return;
+        };
+
+        if url_preview::is_enabled_for(self.timeline.room()).not() {
+            return;
+        }
+
+        for item in items {
+            let Some(message) = item.as_event().and_then(|event| event.content().as_message())
+            else {
+                continue;
+            };
+
+            for url in url_preview::find_urls(message.body()) {
+                if self.url_previews.contains(url) {
+                    self.url_previews.touch(url);
+
+                    continue;
+                }
+
+                if self.url_preview_handles.iter().any(|(pending_url, _)| pending_url == url) {
+                    continue;
+                }
+
+                let client = self.client.clone();
+                let input_sender = input_sender.clone();
+                let room_id = self.timeline.room().room_id().to_owned();
+                let url = url.to_owned();
+
+                self.url_preview_handles.push((
+                    url.clone(),
+                    spawn(async move {
+                        let preview = url_preview::fetch(&client, url.clone()).await;
+                        let _ = input_sender.send(Input::UrlPreview(room_id, url, preview)).await;
+                    })
+                    .abort_on_drop(),
+                ));
+            }
+        }
+    }
+
+    /// Start a backwards pagination if the top of the timeline was visible at
+    /// the last render, unless one is running, or one has started recently,
+    /// or the start of the timeline is reached. Return whether a pagination
+    /// has started.
+    pub fn paginate_backwards_automatically(&self) -> bool {
+        // The timelines without updates, like the room preview, are not
+        // paginated.
+        let Some(input_sender) = &self.input_sender else {
+            return false;
+        };
+
+        let mut pagination = self.pagination.lock();
+
+        // Until the next render tells again whether the top is visible, e.g.
+        // if the timeline is hidden meanwhile.
+        let top_is_visible = mem::take(&mut *self.top_is_visible.lock());
+
+        if !top_is_visible || pagination.is_running() {
+            return false;
+        }
+
+        let has_reached_start = self.items.front().is_some_and(|item| {
+            matches!(item.kind(), TimelineItemKind::Virtual(VirtualTimelineItem::TimelineStart))
+        });
+        let is_debounced = pagination
+            .started_at
+            .is_some_and(|started_at| started_at.elapsed() < PAGINATION_DEBOUNCE);
+
+        if has_reached_start || is_debounced {
+            return false;
+        }
+
+        pagination.started_at = Some(Instant::now());
+        pagination.handle = Some(
+            spawn(paginate_backwards_task(self.timeline.clone(), input_sender.clone()))
+                .abort_on_drop(),
+        );
+
+        true
+    }
+
+    /// The height of the items from the read marker, or else from the event
+    /// of the own read receipt, to the bottom. `None` if there is no read
+    /// position in the loaded items.
+    fn height_from_read_position(&self, heights: &mut Heights, area: &Rect) -> Option<usize> {
+        let index = self
+            .items
+            .iter()
+            .rposition(|item| {
+                matches!(item.kind(), TimelineItemKind::Virtual(VirtualTimelineItem::ReadMarker))
+            })
+            .or_else(|| {
+                let own_read_receipt = self.own_read_receipt.as_deref()?;
+
+                self.items.iter().rposition(|item| {
+                    item.as_event().and_then(|event| event.event_id()) == Some(own_read_receipt)
+                })
+            })?;
+
+        Some(self.height_from(index, heights, area))
+    }
+
+    /// The height of the items from `index` to the bottom, rendering the ones
+    /// whose height is unknown.
+    fn height_from(&self, index: usize, heights: &mut Heights, area: &Rect) -> usize {
+        let mut height = 0;
+
+        for (index, item) in self.items.iter().enumerate().skip(index) {
+            height += match heights.heights.get(index).copied().flatten() {
+                Some(item_height) => item_height,
+                None => {
+                    let item_height =
+                        self.render_timeline_item(item, area).as_ref().map_or(0, Text::height);
+                    heights.heights.set(index, Some(item_height));
+
+                    item_height
+                }
+            };
+        }
+
+        height
     }
 
     pub fn render_timeline_item<'a>(
         &self,
         item: &'a Arc<TimelineItem>,
         area: &'a Rect,
-    ) -> Option<ListItem<'a>> {
-        Some(ListItem::new(match item.kind() {
+    ) -> Option<Text<'a>> {
+        Some(match item.kind() {
             TimelineItemKind::Event(event_item) => {
                 let content = event_item.content();
                 let mut output = Text::default();
 
+                // A compact divider above every N messages, counted from the most
+                // recent one.
+                if let (divider::Dividers::Every(number), TimelineItemContent::MsgLike(_)) =
+                    (divider::dividers(), content)
+                {
+                    let number_of_more_recent_messages = self
+                        .items
+                        .iter()
+                        .rev()
+                        .take_while(|other_item| other_item.unique_id() != item.unique_id())
+                        .filter(|other_item| {
+                            other_item.as_event().is_some_and(|event| {
+                                matches!(event.content(), TimelineItemContent::MsgLike(_))
+                            })
+                        })
+                        .count();
+
+                    if (number_of_more_recent_messages + 1) % number == 0 {
+                        output.push_line(Line::from("· · ·").dark_gray().centered());
+                    }
+                }
+
                 // Sender and time.
                 {
                     let sender = if let TimelineDetails::Ready(Profile {
@@ -360,17 +2138,91 @@ impl Model {
                         Span::raw(event_item.sender().as_str())
                     };
 
-                    let time = if let Some(time) = event_item.timestamp().to_system_time() {
-                        Span::raw(DateTime::<Local>::from(time).format("%H:%M").to_string())
-                    } else {
-                        Span::raw("???")
-                    };
+                    let time = if let Some(time) = event_item.timestamp().to_system_time() {
+                        Span::raw(time::format(time))
+                    } else {
+                        Span::raw("???")
+                    };
+
+                    let mut line = Line::default()
+                        .spans([sender.fg(sender_color(event_item.sender())), " ".into()]);
+
+                    if bot::is_bot(event_item.sender()) {
+                        line.push_span(Span::raw(" bot ").black().on_gray());
+                        line.push_span(" ");
+                    }
+
+                    // The authenticity of the event, explained with `Details::Shield`.
+                    match event_item.get_shield(true) {
+                        Some(ShieldState::Red { .. }) => line.push_span(Span::raw("⛨ ").red()),
+                        Some(ShieldState::Grey { .. }) => line.push_span(Span::raw("⛨ ").gray()),
+                        Some(ShieldState::None) => line.push_span(Span::raw("⛨ ").green()),
+                        None => {}
+                    }
+
+                    line.push_span(time.dark_gray());
+
+                    // The delivery of the own messages, like in the room list.
+                    if event_item.is_own() && matches!(content, TimelineItemContent::MsgLike(_)) {
+                        line.push_span(match event_item.send_state() {
+                            Some(EventSendState::SendingFailed { .. }) => Span::raw(" ❗️").red(),
+                            Some(
+                                EventSendState::NotSentYet { .. } | EventSendState::Sent { .. },
+                            ) => Span::raw(" 🕙"),
+                            // The remote echo has been received, and maybe read by
+                            // all the joined members.
+                            None if event_item.event_id().is_some_and(|event_id| {
+                                self.seen_by_everyone.contains(event_id)
+                            }) =>
+                            {
+                                Span::raw(" ✓✓").green()
+                            }
+                            None => Span::raw(" ✓").green(),
+                        });
+                    }
+
+                    output.push_line(line);
+                }
 
-                    output.push_line(Line::default().spans([
-                        sender.yellow(),
-                        " ".into(),
-                        time.dark_gray(),
-                    ]));
+                // The replied-to event, quoted above the reply. The timeline has
+                // already stripped the legacy reply fallback from the reply.
+                if let Some(in_reply_to) = content.in_reply_to().filter(|_| {
+                    matches!(
+                        self.details,
+                        Details::None | Details::ReadReceipts | Details::UnreadBy
+                    )
+                }) {
+                    let quote_style = Style::default().dark_gray();
+                    let mut quote = vec![Span::styled("▎ ", quote_style)];
+
+                    match &in_reply_to.event {
+                        TimelineDetails::Ready(event) => {
+                            let sender = match &event.sender_profile {
+                                TimelineDetails::Ready(Profile {
+                                    display_name: Some(display_name),
+                                    ..
+                                }) => display_name.clone(),
+                                _ => event.sender.to_string(),
+                            };
+
+                            quote.push(Span::styled(format!("{sender}: "), quote_style.bold()));
+                            quote.push(
+                                summarize_timeline_item_content(&event.content)
+                                    .patch_style(quote_style),
+                            );
+                        }
+                        TimelineDetails::Pending => {
+                            quote.push(Span::styled("loading the replied-to event…", quote_style))
+                        }
+                        TimelineDetails::Unavailable | TimelineDetails::Error(_) => {
+                            quote.push(Span::styled(
+                                format!("in reply to {}", in_reply_to.event_id),
+                                quote_style,
+                            ))
+                        }
+                    }
+
+                    output.push_line(Line::from(quote));
                 }
 
                 // Message.
@@ -403,23 +2255,80 @@ impl Model {
                         } else {
                             spans.push(Span::styled(id, Style::default().green().bold()));
                         }
+                    } else if content.as_message().is_some_and(has_spoiler)
+                        && event_item
+                            .event_id()
+                            .is_none_or(|event_id| self.revealed_spoilers.contains(event_id).not())
+                    {
+                        spans.push(Span::styled(SPOILER, Style::default().dark_gray()));
                     } else {
                         spans.extend(render_timeline_item_content(&content, &area));
                     }
 
                     let is_local_item = event_item.is_local_echo();
+                    let is_notice = content
+                        .as_message()
+                        .is_some_and(|message| matches!(message.msgtype(), MessageType::Notice(_)));
+                    let is_mentioning_us = event_item.is_highlighted()
+                        || content
+                            .as_message()
+                            .and_then(|message| message.mentions())
+                            .zip(self.client.user_id())
+                            .is_some_and(|(mentions, own_user_id)| {
+                                mentions.user_ids.contains(own_user_id)
+                            });
 
                     output.extend(spans.into_iter().map(|span| {
-                        if is_local_item {
+                        let span = if is_local_item {
                             span.set_style(Style::default().italic().dim())
+                        } else if is_notice {
+                            span.patch_style(NOTICE_STYLE)
                         } else {
                             span
-                        }
+                        };
+
+                        if is_mentioning_us { span.patch_style(MENTION_STYLE) } else { span }
                     }));
                 }
 
+                // URL previews.
+                if let Some(message) = content.as_message().filter(|_| {
+                    matches!(
+                        self.details,
+                        Details::None | Details::ReadReceipts | Details::UnreadBy
+                    )
+                }) {
+                    let card_style = Style::default().fg(Color::Indexed(111));
+                    let width = (area.width as usize).saturating_sub(4);
+
+                    for preview in url_preview::find_urls(message.body())
+                        .filter_map(|url| self.url_previews.get(url))
+                    {
+                        if let Some(title) = &preview.title {
+                            output.push_line(Line::from(vec![
+                                Span::styled("▎ ", card_style),
+                                Span::raw(title.clone()).bold(),
+                            ]));
+                        }
+
+                        if let Some(description) = &preview.description {
+                            output.extend(
+                                textwrap::wrap(description, width).into_iter().take(2).map(
+                                    |line| {
+                                        Line::from(vec![
+                                            Span::styled("▎ ", card_style),
+                                            Span::raw(line.into_owned()).dim(),
+                                        ])
+                                    },
+                                ),
+                            );
+                        }
+                    }
+                }
+
                 // Reactions.
-                if matches!(self.details, Details::None) {
+                if matches!(self.details, Details::None | Details::ReadReceipts | Details::UnreadBy)
+                {
                     let reactions = content.reactions();
 
                     if let Some(reactions) = reactions {
@@ -444,7 +2353,8 @@ impl Model {
                 }
 
                 // Read receipts.
-                if matches!(self.details, Details::None) {
+                if matches!(self.details, Details::None | Details::ReadReceipts | Details::UnreadBy)
+                {
                     let read_receipts = event_item.read_receipts();
 
                     if read_receipts.is_empty().not() {
@@ -465,6 +2375,26 @@ impl Model {
                     }
                 }
 
+                // Own read receipt.
+                if matches!(self.details, Details::None | Details::ReadReceipts | Details::UnreadBy)
+                    && event_item
+                        .event_id()
+                        .is_some_and(|event_id| self.own_read_receipt.as_deref() == Some(event_id))
+                {
+                    output.push_line(
+                        Line::styled(
+                            "───── my read receipt ─────",
+                            Style::default().fg(Color::Indexed(67)).dim(),
+                        )
+                        .centered(),
+                    );
+                }
+
+                // Read markers.
+                if let Details::ReadMarkers = self.details {
+                    output.push_line(self.render_read_markers_position(item));
+                }
+
                 output.push_line("\n");
 
                 // Right align event sent by us.
@@ -478,9 +2408,17 @@ impl Model {
             }
 
             TimelineItemKind::Virtual(virtual_item) => match virtual_item {
+                VirtualTimelineItem::DateDivider(_)
+                    if divider::dividers() != divider::Dividers::Date =>
+                {
+                    return None;
+                }
                 VirtualTimelineItem::DateDivider(time) => {
-                    let time = if let Some(time) = time.to_system_time() {
-                        Span::raw(DateTime::<Local>::from(time).format("%a, %e %b %Y").to_string())
+                    let time = time.to_system_time().map(DateTime::<Local>::from);
+                    let is_anchor = self.anchor_date.is_some()
+                        && time.map(|time| time.date_naive()) == self.anchor_date;
+                    let time = if let Some(time) = time {
+                        Span::raw(time.format("%a, %e %b %Y").to_string())
                     } else {
                         Span::raw("date divider")
                     };
@@ -490,20 +2428,25 @@ impl Model {
                     text.push_span(time);
                     text.push_span(" ─────");
 
-                    text
+                    if is_anchor { text.yellow().bold() } else { text }
                 }
-                VirtualTimelineItem::ReadMarker => return None,
+                VirtualTimelineItem::ReadMarker => Text::styled(
+                    "───── fully read ─────",
+                    Style::default().fg(Color::Indexed(132)).dim(),
+                )
+                .centered(),
                 VirtualTimelineItem::TimelineStart => {
+                    let start_marker = divider::start_marker()?;
                     let mut text = Text::default().centered();
 
                     text.push_span("───── ");
-                    text.push_span("Beginning of the room");
+                    text.push_span(start_marker);
                     text.push_span(" ─────");
 
                     text
                 }
             },
-        }))
+        })
     }
 }
 
@@ -512,55 +2455,150 @@ pub(crate) fn render_timeline_item_content<'a>(
     content: &'a TimelineItemContent,
     area: &Rect,
 ) -> Vec<Span<'a>> {
+    match content {
+        TimelineItemContent::MsgLike(MsgLikeContent {
+            kind: MsgLikeKind::Message(message),
+            ..
+        }) if matches!(message.msgtype(), MessageType::Text(_) | MessageType::Notice(_)) => {
+            textwrap::wrap(message.body(), area.width as usize - 2)
+                .into_iter()
+                .map(|c| Span::raw(c.into_owned()))
+                .collect()
+        }
+        _ => vec![summarize_timeline_item_content(content)],
+    }
+}
+
+/// Whether the formatted body of `message` hides some content behind a spoiler.
+pub(crate) fn has_spoiler(message: &MessageContent) -> bool {
+    let formatted = match message.msgtype() {
+        MessageType::Text(text) => text.formatted.as_ref(),
+        MessageType::Notice(notice) => notice.formatted.as_ref(),
+        MessageType::Emote(emote) => emote.formatted.as_ref(),
+        _ => None,
+    };
+
+    formatted.is_some_and(|formatted| {
+        formatted.format == MessageFormat::Html && formatted.body.contains("data-mx-spoiler")
+    })
+}
+
+// Summarize a single timeline item content in a single line, without its
+// reactions.
+pub(crate) fn summarize_timeline_item_content(content: &TimelineItemContent) -> Span<'static> {
     let non_message_style = Style::default().fg(Color::Indexed(247)).italic();
+    let error_style = non_message_style.fg(Color::Red);
 
     match content {
         TimelineItemContent::MsgLike(message_like) => match &message_like.kind {
             MsgLikeKind::Message(message) => {
-                textwrap::wrap(message.body(), area.width as usize - 2)
-                    .into_iter()
-                    .map(|c| Span::raw(c.into_owned()))
-                    .collect()
+                let first_line = message.body().lines().next().unwrap_or_default();
+
+                match message.msgtype() {
+                    _ if has_spoiler(message) => Span::raw(SPOILER),
+                    MessageType::Text(_) | MessageType::Notice(_) => {
+                        Span::raw(first_line.to_owned())
+                    }
+                    MessageType::Emote(_) => Span::raw(format!("* {first_line}")),
+                    MessageType::Image(_) => {
+                        Span::styled(format!("<image `{first_line}`>"), non_message_style)
+                    }
+                    MessageType::Video(_) => {
+                        Span::styled(format!("<video `{first_line}`>"), non_message_style)
+                    }
+                    MessageType::Audio(_) => {
+                        Span::styled(format!("<audio `{first_line}`>"), non_message_style)
+                    }
+                    MessageType::File(_) => {
+                        Span::styled(format!("<file `{first_line}`>"), non_message_style)
+                    }
+                    MessageType::Location(_) => Span::styled("<location>", non_message_style),
+                    MessageType::VerificationRequest(_) => {
+                        Span::styled("<verification request>", non_message_style)
+                    }
+                    message_type => Span::styled(
+                        format!("<message `{}`>", message_type.msgtype()),
+                        non_message_style,
+                    ),
+                }
             }
-            MsgLikeKind::UnableToDecrypt(_) => {
-                vec![Span::styled("<unable to decrypt>", non_message_style.fg(Color::Red))]
+            MsgLikeKind::Sticker(sticker) => {
+                Span::styled(format!("<sticker `{}`>", sticker.content().body), non_message_style)
             }
-            MsgLikeKind::Redacted => {
-                vec![Span::styled("<redacted>", non_message_style)]
+            MsgLikeKind::Poll(poll) => {
+                Span::styled(format!("<poll `{}`>", poll.results().question), non_message_style)
             }
-            MsgLikeKind::Poll(_) => {
-                vec![Span::styled("<poll>", non_message_style)]
+            MsgLikeKind::Redacted => Span::styled("<redacted>", non_message_style),
+            MsgLikeKind::UnableToDecrypt(_) => Span::styled("<unable to decrypt>", error_style),
+            MsgLikeKind::Other(other) => {
+                Span::styled(format!("<message-like `{}`>", other.event_type()), non_message_style)
             }
-            _ => vec![Span::styled("<unsupported messagge-like event>", non_message_style)],
         },
         TimelineItemContent::MembershipChange(membership_change) => {
-            vec![Span::styled(
-                format!("<membership change `{:?}`>", membership_change.change()),
-                non_message_style,
-            )]
+            let user = membership_change
+                .display_name()
+                .unwrap_or_else(|| membership_change.user_id().to_string());
+            let change = describe_membership_change(membership_change.change());
+
+            Span::styled(format!("<{user} {change}>"), non_message_style)
         }
-        TimelineItemContent::ProfileChange(_) => {
-            vec![Span::styled("<profile change>", non_message_style)]
+        TimelineItemContent::ProfileChange(profile_change) => {
+            let change = describe_profile_change(profile_change);
+
+            Span::styled(format!("<{} {change}>", profile_change.user_id()), non_message_style)
         }
-        TimelineItemContent::OtherState(other_state) => vec![Span::styled(
+        TimelineItemContent::OtherState(other_state) => Span::styled(
             format!("<state `{}`>", other_state.content().event_type()),
             non_message_style,
-        )],
-        TimelineItemContent::CallInvite => {
-            vec![Span::styled("<call invite>", non_message_style)]
-        }
+        ),
+        TimelineItemContent::CallInvite => Span::styled("<call invite>", non_message_style),
         TimelineItemContent::RtcNotification => {
-            vec![Span::styled("<rtc notification>", non_message_style)]
+            Span::styled("<rtc notification>", non_message_style)
         }
-        TimelineItemContent::FailedToParseMessageLike { .. } => {
-            vec![Span::styled("<failed to parse message-like>", non_message_style.fg(Color::Red))]
+        TimelineItemContent::FailedToParseMessageLike { event_type, .. } => {
+            Span::styled(format!("<failed to parse message-like `{event_type}`>"), error_style)
         }
-        TimelineItemContent::FailedToParseState { .. } => {
-            vec![Span::styled("<failed to parse state>", non_message_style.fg(Color::Red))]
+        TimelineItemContent::FailedToParseState { event_type, .. } => {
+            Span::styled(format!("<failed to parse state `{event_type}`>"), error_style)
         }
     }
 }
 
+/// Describe a membership change, after the name of the member, e.g. `joined`.
+pub(crate) fn describe_membership_change(change: Option<MembershipChange>) -> &'static str {
+    match change {
+        Some(MembershipChange::Joined) => "joined",
+        Some(MembershipChange::Left) => "left",
+        Some(MembershipChange::Banned) => "has been banned",
+        Some(MembershipChange::Unbanned) => "has been unbanned",
+        Some(MembershipChange::Kicked) => "has been kicked",
+        Some(MembershipChange::Invited) => "has been invited",
+        Some(MembershipChange::KickedAndBanned) => "has been kicked and banned",
+        Some(MembershipChange::InvitationAccepted) => "accepted the invitation",
+        Some(MembershipChange::InvitationRejected) => "rejected the invitation",
+        Some(MembershipChange::InvitationRevoked) => "had their invitation revoked",
+        Some(MembershipChange::Knocked) => "knocked",
+        Some(MembershipChange::KnockAccepted) => "had their knock accepted",
+        Some(MembershipChange::KnockRetracted) => "retracted their knock",
+        Some(MembershipChange::KnockDenied) => "had their knock denied",
+        Some(
+            MembershipChange::None | MembershipChange::Error | MembershipChange::NotImplemented,
+        )
+        | None => "changed their membership",
+    }
+}
+
+/// Describe a profile change, after the name of the member, e.g. `changed
+/// their avatar`.
+pub(crate) fn describe_profile_change(profile_change: &MemberProfileChange) -> &'static str {
+    match (profile_change.displayname_change(), profile_change.avatar_url_change()) {
+        (Some(_), Some(_)) => "changed their display name and avatar",
+        (Some(_), None) => "changed their display name",
+        (None, Some(_)) => "changed their avatar",
+        (None, None) => "changed their profile",
+    }
+}
+
 // Load all chunks until one doesn't contain the first timeline item's event.
 async fn reload_linked_chunks(
     linked_chunks: &mut Vec<(ChunkIdentifier, ChunkContent<TimelineEvent, String>)>,
@@ -614,46 +2652,405 @@ async fn reload_linked_chunks(
     Some(())
 }
 
+// The token of the gap a back-pagination would fill: the oldest loaded chunk if
+// it is a gap, otherwise the chunk before it if it is a gap. `None` if the
+// events are loaded from the store, or if the start of the room is reached.
+async fn load_pagination_token(
+    linked_chunks: &[(ChunkIdentifier, ChunkContent<TimelineEvent, String>)],
+    client: &Client,
+    room_id: &OwnedRoomId,
+) -> Option<String> {
+    let (oldest_chunk_identifier, oldest_chunk_content) = linked_chunks.last()?;
+
+    if let ChunkContent::Gap(prev_token) = oldest_chunk_content {
+        return Some(prev_token.clone());
+    }
+
+    let event_cache_store = client.event_cache_store();
+    let event_cache_store = event_cache_store.lock().await.ok()?;
+    let event_cache_store = event_cache_store.as_clean()?;
+
+    let previous_chunk = event_cache_store
+        .load_previous_chunk(LinkedChunkId::Room(room_id), *oldest_chunk_identifier)
+        .await
+        .ok()??;
+
+    match previous_chunk.content {
+        ChunkContent::Gap(gap) => Some(gap.prev_token),
+        ChunkContent::Items(_) => None,
+    }
+}
+
+// Try to decrypt the last UTDs with room keys downloaded from the backup only,
+// i.e. without requesting them from other devices.
+async fn decrypt_with_backup(
+    timeline: &Timeline,
+    items: &Vector<Arc<TimelineItem>>,
+) -> Vec<(OwnedEventId, BackupDecryption)> {
+    let room = timeline.room();
+    let backups = room.client().encryption().backups();
+    let mut decryptions = Vec::new();
+
+    let utds = items
+        .iter()
+        .rev()
+        .filter_map(|item| item.as_event())
+        .filter(|event_item| event_item.content().is_unable_to_decrypt())
+        .take(NUMBER_OF_UTDS_TO_DECRYPT_WITH_BACKUP);
+
+    for event_item in utds {
+        let (Some(event_id), Some(original_json)) =
+            (event_item.event_id(), event_item.original_json())
+        else {
+            continue;
+        };
+
+        let Some(EncryptedMessage::MegolmV1AesSha2 { session_id, .. }) =
+            event_item.content().as_unable_to_decrypt()
+        else {
+            decryptions.push((event_id.to_owned(), BackupDecryption::NotMegolm));
+
+            continue;
+        };
+
+        let decryption = match backups.download_room_key(room.room_id(), session_id).await {
+            Ok(true) => match room.decrypt_event(original_json.cast_ref_unchecked(), None).await {
+                Ok(event) if matches!(event.kind, TimelineEventKind::Decrypted(_)) => {
+                    BackupDecryption::Decrypted
+                }
+                _ => BackupDecryption::StillUnableToDecrypt,
+            },
+            Ok(false) => BackupDecryption::BackupUnavailable,
+            Err(error) => BackupDecryption::NotInBackup(error.to_string()),
+        };
+
+        decryptions.push((event_id.to_owned(), decryption));
+    }
+
+    decryptions
+}
+
+async fn own_read_receipt_updates_task(timeline: Arc<Timeline>, input_sender: Sender<Input>) {
+    let Some(own_user_id) = timeline.room().client().user_id().map(ToOwned::to_owned) else {
+        return;
+    };
+    let room_id = timeline.room().room_id().to_owned();
+
+    let own_read_receipt_changes = timeline.subscribe_own_user_read_receipts_changed().await;
+
+    pin_mut!(own_read_receipt_changes);
+
+    while own_read_receipt_changes.next().await.is_some() {
+        let event_id = timeline.latest_user_read_receipt_timeline_event_id(&own_user_id).await;
+
+        let _ = input_sender.send(Input::OwnReadReceiptUpdate(room_id.clone(), event_id)).await;
+    }
+}
+
+async fn pagination_status_updates_task(timeline: Arc<Timeline>, input_sender: Sender<Input>) {
+    let room_id = timeline.room().room_id().to_owned();
+
+    // The timeline is focused on an event: there is no live back-pagination.
+    let Some((initial_status, status_stream)) = timeline.live_back_pagination_status().await else {
+        return;
+    };
+
+    let _ = input_sender.send(Input::PaginationStatusUpdate(room_id.clone(), initial_status)).await;
+
+    pin_mut!(status_stream);
+
+    while let Some(status) = status_stream.next().await {
+        let _ = input_sender.send(Input::PaginationStatusUpdate(room_id.clone(), status)).await;
+    }
+}
+
 async fn items_updates_task(timeline: Arc<Timeline>, input_sender: Sender<Input>) {
+    let room_id = timeline.room().room_id().to_owned();
     let (initial_items, items_stream) = timeline.subscribe().await;
 
     let _ = input_sender
-        .send(Input::TimelineUpdate(vec![VectorDiff::Reset { values: initial_items }]))
+        .send(Input::TimelineUpdate(
+            room_id.clone(),
+            vec![VectorDiff::Reset { values: initial_items }],
+        ))
         .await;
 
     pin_mut!(items_stream);
 
     while let Some(diffs) = items_stream.next().await {
-        let _ = input_sender.send(Input::TimelineUpdate(diffs)).await;
+        let _ = input_sender.send(Input::TimelineUpdate(room_id.clone(), diffs)).await;
     }
 }
 
-fn update_scroll_position_for_timeline(
-    scroll: &Scroll,
-    scroll_position: usize,
+async fn paginate_backwards_task(timeline: Arc<Timeline>, input_sender: Sender<Input>) {
+    let pagination = timeline.paginate_backwards(NUMBER_OF_EVENTS_TO_PAGINATE);
+    pin_mut!(pagination);
+
+    // Redraw regularly to animate the spinner.
+    let mut spinner = interval(SPINNER_INTERVAL);
+
+    loop {
+        select! {
+            result = &mut pagination => {
+                if let Err(error) = result {
+                    error!("Failed to paginate backwards: {error}");
+                }
+
+                break;
+            }
+            _ = spinner.tick() => {
+                let _ = input_sender.send(Input::Redraw).await;
+            }
+        }
+    }
+
+    let _ = input_sender.send(Input::Redraw).await;
+}
+
+/// The users who have read `event_id`: those with a read receipt on it or on a
+/// later event, and the senders of the later events.
+fn readers_from(items: &Vector<Arc<TimelineItem>>, event_id: &EventId) -> HashSet<OwnedUserId> {
+    let mut readers = HashSet::new();
+
+    for event_item in items.iter().rev().filter_map(|item| item.as_event()) {
+        readers.extend(event_item.read_receipts().keys().cloned());
+        readers.insert(event_item.sender().to_owned());
+
+        if event_item.event_id() == Some(event_id) {
+            return readers;
+        }
+    }
+
+    // The event isn't loaded.
+    HashSet::new()
+}
+
+/// The joined members of `room` known by the store, with their display name.
+async fn load_joined_members(room: &Room) -> Vec<(OwnedUserId, Option<String>)> {
+    match room.members_no_sync(RoomMemberships::JOIN).await {
+        Ok(members) => members
+            .iter()
+            .map(|member| {
+                (member.user_id().to_owned(), member.display_name().map(ToOwned::to_owned))
+            })
+            .collect(),
+        Err(error) => {
+            error!("Failed to load the joined members: {error}");
+
+            Vec::new()
+        }
+    }
+}
+
+async fn load_read_receipts(
+    timeline: &Timeline,
     items: &Vector<Arc<TimelineItem>>,
-) -> usize {
-    match scroll {
-        Scroll::Up => min(
-            items.len().saturating_sub(MINIMUM_NUMBER_OF_VISIBLE_ITEMS),
-            scroll_position.saturating_add(1),
-        ),
-        Scroll::Down => min(
-            items.len().saturating_sub(MINIMUM_NUMBER_OF_VISIBLE_ITEMS),
-            scroll_position.saturating_sub(1),
-        ),
-        Scroll::Start => items.len().saturating_sub(MINIMUM_NUMBER_OF_VISIBLE_ITEMS),
-        Scroll::End => 0,
+    event_id: &EventId,
+) -> Vec<ReadReceipt> {
+    let Some(event_item) = items
+        .iter()
+        .find_map(|item| item.as_event().filter(|event| event.event_id() == Some(event_id)))
+    else {
+        return Vec::new();
+    };
+
+    let room = timeline.room();
+    let mut read_receipts = Vec::new();
+
+    for (user_id, receipt) in event_item.read_receipts() {
+        let display_name = match room.get_member_no_sync(user_id).await {
+            Ok(Some(member)) => member.display_name().map(ToOwned::to_owned),
+            _ => None,
+        };
+
+        // The timeline merges the public and the private read receipts.
+        let is_private = matches!(
+            room.load_user_receipt(ReceiptType::ReadPrivate, ReceiptThread::Unthreaded, user_id)
+                .await,
+            Ok(Some((private_event_id, _))) if private_event_id == event_id
+        );
+
+        read_receipts.push(ReadReceipt {
+            user_id: user_id.clone(),
+            display_name,
+            timestamp: receipt.ts.and_then(|ts| ts.to_system_time()).map(DateTime::<Local>::from),
+            receipt_type: if is_private { ReceiptType::ReadPrivate } else { ReceiptType::Read },
+        });
+    }
+
+    read_receipts
+}
+
+/// Build the tree of the relations of `event_id`: its in-reply-to ancestors,
+/// its thread, and the events relating to it, from the timeline items and from
+/// the event cache.
+async fn load_relations(
+    timeline: &Timeline,
+    items: &Vector<Arc<TimelineItem>>,
+    event_id: &EventId,
+) -> Vec<(usize, String)> {
+    let mut tree = vec![(0, event_id.to_string())];
+    let event_item = items
+        .iter()
+        .find_map(|item| item.as_event().filter(|event| event.event_id() == Some(event_id)));
+    let room_event_cache = timeline.room().event_cache().await.ok();
+
+    // The in-reply-to ancestors, resolved with the event cache.
+    tree.push((1, "in reply to".to_owned()));
+
+    let mut ancestor = event_item
+        .and_then(|event_item| event_item.content().in_reply_to())
+        .map(|in_reply_to| in_reply_to.event_id);
+    let mut depth = 2;
+
+    if ancestor.is_none() {
+        tree.push((2, "nothing".to_owned()));
+    }
+
+    while let Some(ancestor_id) = ancestor.take() {
+        if depth - 2 == MAXIMUM_NUMBER_OF_ANCESTORS {
+            tree.push((depth, "…".to_owned()));
+            break;
+        }
+
+        let event = match &room_event_cache {
+            Some((room_event_cache, _)) => room_event_cache.find_event(&ancestor_id).await.ok(),
+            None => None,
+        }
+        .flatten();
+
+        tree.push((
+            depth,
+            match &event {
+                Some(event) => describe_related_event(event),
+                None => format!("{ancestor_id} (not in the event cache)"),
+            },
+        ));
+
+        ancestor = event.and_then(|event| {
+            relates_to(&event)?
+                .pointer("/m.in_reply_to/event_id")?
+                .as_str()
+                .and_then(|event_id| EventId::parse(event_id).ok())
+        });
+        depth += 1;
+    }
+
+    // The thread.
+    if let Some(content) = event_item.map(|event_item| event_item.content()) {
+        if let Some(thread_root) = content.thread_root() {
+            tree.push((1, format!("in the thread of {thread_root}")));
+        }
+
+        if let Some(thread_summary) = content.thread_summary() {
+            tree.push((1, format!("thread root, with {} replies", thread_summary.num_replies)));
+        }
+    }
+
+    // The related events, grouped by type of relation.
+    let related_events = match &room_event_cache {
+        Some((room_event_cache, _)) => {
+            room_event_cache.find_event_with_relations(event_id, None).await
+        }
+        None => Ok(None),
+    };
+
+    match related_events {
+        Ok(Some((_, related_events))) => {
+            let mut related_events = related_events
+                .iter()
+                .map(|event| {
+                    let relation_type = relates_to(event)
+                        .and_then(|relates_to| {
+                            relates_to.get("rel_type")?.as_str().map(ToOwned::to_owned)
+                        })
+                        .unwrap_or_else(|| "unknown".to_owned());
+                    let timestamp = event
+                        .raw()
+                        .get_field::<u64>("origin_server_ts")
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+
+                    (relation_type, timestamp, describe_related_event(event))
+                })
+                .collect::<Vec<_>>();
+
+            // Edits are shown in the order they have been made.
+            related_events.sort();
+
+            for (relation_type, related_events) in
+                &related_events.into_iter().chunk_by(|(relation_type, ..)| relation_type.clone())
+            {
+                tree.push((
+                    1,
+                    match relation_type.as_str() {
+                        "m.replace" => "edits".to_owned(),
+                        "m.annotation" => "reactions".to_owned(),
+                        "m.thread" => "thread replies".to_owned(),
+                        "m.reference" => "references".to_owned(),
+                        relation_type => format!("`{relation_type}` relations"),
+                    },
+                ));
+                tree.extend(related_events.map(|(_, _, description)| (2, description)));
+            }
+        }
+        Ok(None) => tree.push((1, "not in the event cache".to_owned())),
+        Err(error) => tree.push((1, format!("failed to load the related events: {error}"))),
+    }
+
+    // The reactions, as aggregated by the timeline.
+    if let Some(reactions) = event_item.and_then(|event_item| event_item.content().reactions()) {
+        tree.push((1, "reactions, aggregated by the timeline".to_owned()));
+        tree.extend(
+            reactions.iter().map(|(key, senders)| (2, format!("{key} × {}", senders.len()))),
+        );
     }
+
+    tree
+}
+
+/// The `m.relates_to` of the content of `event`.
+fn relates_to(event: &TimelineEvent) -> Option<serde_json::Value> {
+    let mut content = event.raw().get_field::<serde_json::Value>("content").ok()??;
+
+    Some(content.get_mut("m.relates_to")?.take())
 }
 
-fn update_scroll_position_for_linked_chunk(scroll: &Scroll, scroll_position: usize) -> usize {
+fn describe_related_event(event: &TimelineEvent) -> String {
+    let raw = event.raw();
+    let field = |name: &str| raw.get_field::<String>(name).ok().flatten().unwrap_or_default();
+    let key = relates_to(event)
+        .and_then(|relates_to| relates_to.get("key")?.as_str().map(|key| format!(" {key}")))
+        .unwrap_or_default();
+
+    format!(
+        "{} {}{key} from {}",
+        event.event_id().map_or_else(|| "no ID".to_owned(), |event_id| event_id.to_string()),
+        field("type"),
+        field("sender"),
+    )
+}
+
+// The scroll position is a number of lines from the bottom. It is clamped by
+// `render`.
+fn update_scroll_position(
+    scroll: &Scroll,
+    scroll_position: usize,
+    viewport_height: usize,
+) -> usize {
+    let half_page = (viewport_height / 2).max(1);
+
     match scroll {
         Scroll::Up => scroll_position.saturating_add(1),
         Scroll::Down => scroll_position.saturating_sub(1),
+        Scroll::PageUp => scroll_position.saturating_add(viewport_height),
+        Scroll::PageDown => scroll_position.saturating_sub(viewport_height),
+        Scroll::HalfPageUp => scroll_position.saturating_add(half_page),
+        Scroll::HalfPageDown => scroll_position.saturating_sub(half_page),
         Scroll::Start => {
-            // We don't know the size of the linked chunk rendering. Let's set
-            // to `usize::MAX` and let `render` updates the scroll position. Not
+            // We don't know the size of the rendering. Let's set to
+            // `usize::MAX` and let `render` updates the scroll position. Not
             // ideal, but eh, it's simpler like this.
             usize::MAX
         }
@@ -661,6 +3058,307 @@ fn update_scroll_position_for_linked_chunk(scroll: &Scroll, scroll_position: usi
     }
 }
 
+/// Return a colour for a sender, always the same for a given user.
+pub(crate) fn sender_color(user_id: &UserId) -> Color {
+    color_of(user_id.as_str())
+}
+
+/// Return a colour among the ones of the senders, always the same for a given
+/// value, e.g. a room ID.
+pub(crate) fn color_of(value: &str) -> Color {
+    let hash = value
+        .bytes()
+        .fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(usize::from(byte)));
+
+    SENDER_COLORS[hash % SENDER_COLORS.len()]
+}
+
+/// The violations of the ordering invariants of the timeline `items`: the
+/// remote events are sorted by timestamp, appear only once, and are before the
+/// local echoes.
+fn timeline_ordering_violations(items: &Vector<Arc<TimelineItem>>) -> Vec<OrderingViolation> {
+    let mut violations = Vec::new();
+    let mut seen_event_ids = HashSet::new();
+    let mut previous_event: Option<(&EventId, MilliSecondsSinceUnixEpoch)> = None;
+    let mut first_local_echo = None;
+
+    for event_item in items.iter().filter_map(|item| item.as_event()) {
+        if event_item.is_local_echo() {
+            first_local_echo = first_local_echo.or(event_item.transaction_id());
+
+            continue;
+        }
+
+        let Some(event_id) = event_item.event_id() else { continue };
+
+        if let Some(transaction_id) = first_local_echo {
+            violations.push(OrderingViolation {
+                location: "timeline".to_owned(),
+                explanation: format!("a remote event is after the local echo {transaction_id}"),
+                event_ids: vec![event_id.to_owned()],
+            });
+        }
+
+        if seen_event_ids.insert(event_id).not() {
+            violations.push(OrderingViolation {
+                location: "timeline".to_owned(),
+                explanation: "the event is present more than once".to_owned(),
+                event_ids: vec![event_id.to_owned()],
+            });
+        }
+
+        let timestamp = event_item.timestamp();
+
+        if let Some((previous_event_id, previous_timestamp)) =
+            previous_event.filter(|(_, previous_timestamp)| timestamp < *previous_timestamp)
+        {
+            violations.push(OrderingViolation {
+                location: "timeline".to_owned(),
+                explanation: format!(
+                    "the event is {} older than the previous one",
+                    format_duration_between(timestamp, previous_timestamp)
+                ),
+                event_ids: vec![previous_event_id.to_owned(), event_id.to_owned()],
+            });
+        }
+
+        previous_event = Some((event_id, timestamp));
+    }
+
+    violations
+}
+
+/// The violations of the ordering invariants of the `linked_chunks`, the most
+/// recent first: the events of a chunk are sorted by timestamp, the chunks
+/// don't interleave, an event is in one chunk only, and the events are in the
+/// same order as in the timeline `items`.
+fn linked_chunk_ordering_violations(
+    linked_chunks: &[(ChunkIdentifier, ChunkContent<TimelineEvent, String>)],
+    items: &Vector<Arc<TimelineItem>>,
+) -> Vec<OrderingViolation> {
+    let mut violations = Vec::new();
+    // The position of the events in the linked chunk, from the oldest.
+    let mut positions = HashMap::new();
+    let mut previous_event: Option<(OwnedEventId, MilliSecondsSinceUnixEpoch)> = None;
+
+    for (chunk_identifier, chunk_content) in linked_chunks.iter().rev() {
+        let ChunkContent::Items(events) = chunk_content else { continue };
+        let location = format!("chunk #{}", chunk_identifier.index());
+        let mut is_first_event_of_chunk = true;
+
+        for event in events {
+            let Some(event_id) = event.event_id() else { continue };
+
+            if positions.insert(event_id.clone(), positions.len()).is_some() {
+                violations.push(OrderingViolation {
+                    location: location.clone(),
+                    explanation: "the event is present more than once in the linked chunk"
+                        .to_owned(),
+                    event_ids: vec![event_id.clone()],
+                });
+            }
+
+            let Some(timestamp) = event.timestamp() else { continue };
+
+            if let Some((previous_event_id, previous_timestamp)) = previous_event
+                .as_ref()
+                .filter(|(_, previous_timestamp)| timestamp < *previous_timestamp)
+            {
+                violations.push(OrderingViolation {
+                    location: location.clone(),
+                    explanation: format!(
+                        "the event is {} older than the previous one{}",
+                        format_duration_between(timestamp, *previous_timestamp),
+                        if is_first_event_of_chunk {
+                            ", the last of the previous chunk: the chunks interleave"
+                        } else {
+                            ""
+                        }
+                    ),
+                    event_ids: vec![previous_event_id.clone(), event_id.clone()],
+                });
+            }
+
+            previous_event = Some((event_id, timestamp));
+            is_first_event_of_chunk = false;
+        }
+    }
+
+    // The events of the timeline must be in the same order in the linked chunk.
+    let mut previous_event: Option<(&EventId, usize)> = None;
+
+    for event_id in items.iter().filter_map(|item| item.as_event()?.event_id()) {
+        let Some(&position) = positions.get(event_id) else { continue };
+
+        if let Some((previous_event_id, _)) =
+            previous_event.filter(|(_, previous_position)| position < *previous_position)
+        {
+            violations.push(OrderingViolation {
+                location: "linked chunk".to_owned(),
+                explanation: "the events are in the opposite order in the timeline".to_owned(),
+                event_ids: vec![previous_event_id.to_owned(), event_id.to_owned()],
+            });
+        }
+
+        previous_event = Some((event_id, position));
+    }
+
+    violations
+}
+
+/// Compare the events of the `linked_chunks`, from the one of the oldest event
+/// of the timeline, with the remote events of the timeline `items`.
+fn event_cache_consistency(
+    linked_chunks: &[(ChunkIdentifier, ChunkContent<TimelineEvent, String>)],
+    items: &Vector<Arc<TimelineItem>>,
+) -> EventCacheConsistency {
+    let timeline_event_ids = items
+        .iter()
+        .filter_map(|item| item.as_event())
+        .filter(|event_item| event_item.is_local_echo().not())
+        .filter_map(|event_item| event_item.event_id())
+        .collect::<Vec<_>>();
+
+    // From the oldest.
+    let mut events = linked_chunks
+        .iter()
+        .rev()
+        .filter_map(|(_, chunk_content)| match chunk_content {
+            ChunkContent::Items(events) => Some(events),
+            ChunkContent::Gap(_) => None,
+        })
+        .flatten()
+        .filter_map(|event| Some((event.event_id()?, event)))
+        .collect::<Vec<_>>();
+
+    // The oldest chunk may have events older than the timeline, i.e. not
+    // paginated yet.
+    if let Some(first_position) = timeline_event_ids.first().and_then(|first_event_id| {
+        events.iter().position(|(event_id, _)| &**event_id == *first_event_id)
+    }) {
+        events.drain(..first_position);
+    }
+
+    let event_cache_event_ids =
+        events.iter().map(|(event_id, _)| &**event_id).collect::<HashSet<&EventId>>();
+    let timeline_event_id_set = timeline_event_ids.iter().copied().collect::<HashSet<_>>();
+
+    EventCacheConsistency {
+        time: Local::now(),
+        only_in_event_cache: events
+            .iter()
+            .filter(|(event_id, _)| timeline_event_id_set.contains(&**event_id).not())
+            .map(|(event_id, event)| {
+                let event_type = event
+                    .raw()
+                    .get_field::<String>("type")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| "???".to_owned());
+                let is_edit = relates_to(event).is_some_and(|relates_to| {
+                    relates_to.get("rel_type").and_then(|rel_type| rel_type.as_str())
+                        == Some("m.replace")
+                });
+                let is_aggregated =
+                    is_edit || matches!(event_type.as_str(), "m.reaction" | "m.room.redaction");
+
+                (
+                    event_id.clone(),
+                    if is_edit { format!("{event_type} (edit)") } else { event_type },
+                    is_aggregated,
+                )
+            })
+            .collect(),
+        only_in_timeline: timeline_event_ids
+            .into_iter()
+            .filter(|event_id| event_cache_event_ids.contains(event_id).not())
+            .map(ToOwned::to_owned)
+            .unique()
+            .collect(),
+    }
+}
+
+/// The duration between `older` and `newer`, e.g. `3.2s`.
+fn format_duration_between(
+    older: MilliSecondsSinceUnixEpoch,
+    newer: MilliSecondsSinceUnixEpoch,
+) -> String {
+    let milliseconds = u64::from(newer.0).saturating_sub(u64::from(older.0));
+
+    format!("{:.1}s", milliseconds as f64 / 1000.0)
+}
+
+/// Describe `diff` for the diff log, with the `items` it's applied to.
+fn describe_diff(
+    diff: &VectorDiff<Arc<TimelineItem>>,
+    items: &Vector<Arc<TimelineItem>>,
+) -> diff_log::Entry {
+    let (kind, items) = match diff {
+        VectorDiff::Append { values } => {
+            (format!("Append {}", values.len()), values.iter().map(describe_item).collect())
+        }
+        VectorDiff::Clear => (format!("Clear {}", items.len()), Vec::new()),
+        VectorDiff::PushFront { value } => ("PushFront".to_owned(), vec![describe_item(value)]),
+        VectorDiff::PushBack { value } => ("PushBack".to_owned(), vec![describe_item(value)]),
+        VectorDiff::PopFront => {
+            ("PopFront".to_owned(), items.front().map(describe_item).into_iter().collect())
+        }
+        VectorDiff::PopBack => {
+            ("PopBack".to_owned(), items.back().map(describe_item).into_iter().collect())
+        }
+        VectorDiff::Insert { index, value } => {
+            (format!("Insert {index}"), vec![describe_item(value)])
+        }
+        VectorDiff::Set { index, value } => {
+            let new_item = describe_item(value);
+
+            // The item replaced by another one, e.g. a local echo by its remote echo.
+            let item = match items.get(*index).map(describe_item) {
+                Some(previous_item) if previous_item != new_item => {
+                    format!("{previous_item} → {new_item}")
+                }
+                _ => new_item,
+            };
+
+            (format!("Set {index}"), vec![item])
+        }
+        VectorDiff::Remove { index } => {
+            (format!("Remove {index}"), items.get(*index).map(describe_item).into_iter().collect())
+        }
+        VectorDiff::Truncate { length } => {
+            (format!("Truncate {length}"), items.iter().skip(*length).map(describe_item).collect())
+        }
+        VectorDiff::Reset { values } => (format!("Reset {}", values.len()), Vec::new()),
+    };
+
+    diff_log::Entry {
+        time: Local::now(),
+        kind,
+        items,
+        duplicated_event_ids: Vec::new(),
+        diff: serde_json::Value::Null,
+    }
+}
+
+/// Describe an item for the diff log: the event ID of an event, or else its
+/// transaction ID, or the kind of a virtual item.
+fn describe_item(item: &Arc<TimelineItem>) -> String {
+    match item.kind() {
+        TimelineItemKind::Event(event_item) => {
+            match (event_item.event_id(), event_item.transaction_id()) {
+                (Some(event_id), _) => format_event_id(event_id.to_owned()),
+                (None, Some(transaction_id)) => format!("local {transaction_id}"),
+                (None, None) => "???".to_owned(),
+            }
+        }
+        TimelineItemKind::Virtual(VirtualTimelineItem::DateDivider(_)) => "date divider".to_owned(),
+        TimelineItemKind::Virtual(VirtualTimelineItem::ReadMarker) => "read marker".to_owned(),
+        TimelineItemKind::Virtual(VirtualTimelineItem::TimelineStart) => {
+            "timeline start".to_owned()
+        }
+    }
+}
+
 fn format_event_id(event_id: OwnedEventId) -> String {
     let event_id = event_id.as_str();
 
@@ -0,0 +1,90 @@
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use tokio::{
+    spawn,
+    sync::mpsc::Sender,
+    time::{Instant, sleep},
+};
+
+use crate::input::Input;
+
+/// How long a toast stays on screen.
+const DURATION: Duration = Duration::from_secs(6);
+
+/// The maximum number of toasts on screen, the oldest ones are dropped first.
+const MAXIMUM_NUMBER_OF_TOASTS: usize = 3;
+
+const WIDTH: u16 = 50;
+
+const STYLE: Style = Style::new().fg(Color::Red);
+
+/// The toasts on screen, the most recent last.
+static TOASTS: Mutex<VecDeque<Toast>> = Mutex::new(VecDeque::new());
+
+struct Toast {
+    message: String,
+    shown_until: Instant,
+}
+
+/// Show an error for a few seconds, over the bottom right corner of the app,
+/// e.g. when a background task fails. It's redrawn once expired.
+pub fn error(message: String, input_sender: &Sender<Input>) {
+    {
+        let mut toasts = TOASTS.lock().unwrap();
+
+        if toasts.len() == MAXIMUM_NUMBER_OF_TOASTS {
+            toasts.pop_front();
+        }
+
+        toasts.push_back(Toast { message, shown_until: Instant::now() + DURATION });
+    }
+
+    let input_sender = input_sender.clone();
+
+    spawn(async move {
+        let _ = input_sender.send(Input::Redraw).await;
+        sleep(DURATION).await;
+        let _ = input_sender.send(Input::Redraw).await;
+    });
+}
+
+/// Draw the toasts that haven't expired, stacked from the bottom of `area`.
+pub fn render(area: Rect, buffer: &mut Buffer) {
+    let mut toasts = TOASTS.lock().unwrap();
+    let now = Instant::now();
+
+    toasts.retain(|toast| toast.shown_until > now);
+
+    let [area] = Layout::horizontal([Constraint::Length(WIDTH)]).flex(Flex::End).areas(area);
+    let mut bottom = area.bottom();
+
+    for toast in toasts.iter().rev() {
+        let lines = textwrap::wrap(&toast.message, usize::from(WIDTH - 2))
+            .into_iter()
+            .map(|line| Line::from(line.into_owned()))
+            .collect::<Vec<_>>();
+        // The borders take 2 lines.
+        let height = (lines.len() as u16 + 2).min(bottom - area.y);
+
+        if height == 0 {
+            break;
+        }
+
+        bottom -= height;
+
+        let toast_area = Rect { y: bottom, height, ..area };
+
+        Clear.render(toast_area, buffer);
+        Paragraph::new(lines)
+            .style(STYLE)
+            .block(Block::new().borders(Borders::ALL).border_style(STYLE))
+            .render(toast_area, buffer);
+    }
+}
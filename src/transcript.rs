@@ -0,0 +1,181 @@
+use std::{fmt, fs, io, path::PathBuf, sync::Arc};
+
+use chrono::{DateTime, Local};
+use matrix_sdk::{
+    Client,
+    linked_chunk::{ChunkContent, LinkedChunkId},
+    ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomId},
+};
+use matrix_sdk_ui::{eyeball_im::Vector, timeline::TimelineItem};
+use serde::{Deserialize, Serialize};
+
+use crate::timeline::summarize_timeline_item_content;
+
+/// The format of an exported transcript.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    Markdown,
+    JsonLines,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Markdown => "md",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Text => "plain text",
+            Self::Markdown => "Markdown",
+            Self::JsonLines => "JSON Lines",
+        })
+    }
+}
+
+/// An event of the transcript.
+#[derive(Serialize)]
+pub struct Entry {
+    event_id: Option<OwnedEventId>,
+    sender: OwnedUserId,
+    timestamp: MilliSecondsSinceUnixEpoch,
+    body: String,
+}
+
+impl Entry {
+    fn time(&self) -> String {
+        self.timestamp
+            .to_system_time()
+            .map(|time| DateTime::<Local>::from(time).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "???".to_owned())
+    }
+
+    fn event_id(&self) -> &str {
+        self.event_id.as_ref().map_or("no ID", |event_id| event_id.as_str())
+    }
+}
+
+/// The entries of the items loaded in a timeline.
+pub fn from_timeline(items: &Vector<Arc<TimelineItem>>) -> Vec<Entry> {
+    items
+        .iter()
+        .filter_map(|item| item.as_event())
+        .map(|event_item| {
+            let content = event_item.content();
+
+            Entry {
+                event_id: event_item.event_id().map(ToOwned::to_owned),
+                sender: event_item.sender().to_owned(),
+                timestamp: event_item.timestamp(),
+                body: match content.as_message() {
+                    Some(message) => message.body().to_owned(),
+                    None => summarize_timeline_item_content(content).content.into_owned(),
+                },
+            }
+        })
+        .collect()
+}
+
+/// The entries of all the events stored in the event cache for a room, from
+/// the oldest to the most recent.
+pub async fn from_event_cache(client: &Client, room_id: &RoomId) -> Result<Vec<Entry>, String> {
+    #[derive(Deserialize)]
+    struct Content {
+        body: Option<String>,
+    }
+
+    let event_cache_store = client.event_cache_store();
+    let event_cache_store = event_cache_store.lock().await.map_err(|error| error.to_string())?;
+    let event_cache_store =
+        event_cache_store.as_clean().ok_or_else(|| "the event cache is dirty".to_owned())?;
+
+    let mut entries = Vec::new();
+    let (mut next_chunk, _) = event_cache_store
+        .load_last_chunk(LinkedChunkId::Room(room_id))
+        .await
+        .map_err(|error| error.to_string())?;
+
+    while let Some(chunk) = next_chunk {
+        if let ChunkContent::Items(events) = chunk.content {
+            for event in events.iter().rev() {
+                let raw = event.raw();
+                let (Ok(Some(sender)), Ok(Some(timestamp))) =
+                    (raw.get_field("sender"), raw.get_field("origin_server_ts"))
+                else {
+                    continue;
+                };
+                let body = match raw.get_field::<Content>("content") {
+                    Ok(Some(Content { body: Some(body) })) => body,
+                    _ => match raw.get_field::<String>("type") {
+                        Ok(Some(event_type)) => format!("<{event_type}>"),
+                        _ => "<unknown event>".to_owned(),
+                    },
+                };
+
+                entries.push(Entry { event_id: event.event_id(), sender, timestamp, body });
+            }
+        }
+
+        next_chunk = event_cache_store
+            .load_previous_chunk(LinkedChunkId::Room(room_id), chunk.identifier)
+            .await
+            .map_err(|error| error.to_string())?;
+    }
+
+    entries.reverse();
+
+    Ok(entries)
+}
+
+/// Write `entries` in a timestamped file, in the given `format`. Return the
+/// path of the file.
+pub fn export(entries: &[Entry], format: Format) -> io::Result<PathBuf> {
+    let path = PathBuf::from(format!(
+        "multiverse-transcript-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+    let mut transcript = String::new();
+
+    for entry in entries {
+        match format {
+            Format::Text => {
+                transcript.push_str(&format!(
+                    "[{}] {}: {} ({})\n",
+                    entry.time(),
+                    entry.sender,
+                    entry.body,
+                    entry.event_id()
+                ));
+            }
+            Format::Markdown => {
+                transcript.push_str(&format!(
+                    "**{}** — {} — `{}`\n\n",
+                    entry.sender,
+                    entry.time(),
+                    entry.event_id()
+                ));
+
+                for line in entry.body.lines() {
+                    transcript.push_str(&format!("> {line}\n"));
+                }
+
+                transcript.push('\n');
+            }
+            Format::JsonLines => {
+                transcript.push_str(&serde_json::to_string(entry)?);
+                transcript.push('\n');
+            }
+        }
+    }
+
+    fs::write(&path, transcript)?;
+
+    Ok(path.canonicalize().unwrap_or(path))
+}
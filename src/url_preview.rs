@@ -0,0 +1,105 @@
+use std::{
+    collections::VecDeque,
+    ops::Not,
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use matrix_sdk::{
+    Client, Room,
+    ruma::{api::client::authenticated_media::get_media_preview, exports::serde_json},
+};
+use regex::Regex;
+
+use crate::bandwidth;
+
+/// Maximum number of previews kept in memory.
+const CAPACITY: usize = 128;
+
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"https?://[^\s<>"]+"#).unwrap());
+
+static IS_ENABLED_IN_ENCRYPTED_ROOMS: AtomicBool = AtomicBool::new(false);
+
+/// The preview of a URL, fetched from the homeserver.
+#[derive(Debug, Clone)]
+pub struct UrlPreview {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Allow fetching previews in encrypted rooms. The homeserver learns the URLs
+/// it previews, so it's disabled by default.
+pub fn enable_in_encrypted_rooms(is_enabled: bool) {
+    IS_ENABLED_IN_ENCRYPTED_ROOMS.store(is_enabled, Ordering::Relaxed);
+}
+
+/// Whether previews can be fetched for the URLs of `room`.
+pub fn is_enabled_for(room: &Room) -> bool {
+    if bandwidth::is_low() {
+        return false;
+    }
+
+    room.encryption_state().is_encrypted().not()
+        || IS_ENABLED_IN_ENCRYPTED_ROOMS.load(Ordering::Relaxed)
+}
+
+/// Find all the URLs in `body`.
+pub fn find_urls(body: &str) -> impl Iterator<Item = &str> {
+    URL.find_iter(body).map(|found| {
+        found.as_str().trim_end_matches(|c| matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')'))
+    })
+}
+
+/// Fetch the preview of `url` through the homeserver, `None` if there is
+/// nothing to preview.
+pub async fn fetch(client: &Client, url: String) -> Option<UrlPreview> {
+    let response = client.send(get_media_preview::v1::Request::new(url)).await.ok()?;
+    let data: serde_json::Value = serde_json::from_str(response.data?.get()).ok()?;
+
+    let field = |name: &str| data.get(name)?.as_str().map(ToOwned::to_owned);
+    let preview = UrlPreview { title: field("og:title"), description: field("og:description") };
+
+    (preview.title.is_some() || preview.description.is_some()).then_some(preview)
+}
+
+/// The last fetched previews, the least recently used being evicted first.
+#[derive(Default)]
+pub struct Cache {
+    /// The previews, from the least to the most recently used. `None` if there
+    /// is nothing to preview, so that it's not fetched again.
+    entries: VecDeque<(String, Option<UrlPreview>)>,
+}
+
+impl Cache {
+    pub fn get(&self, url: &str) -> Option<&UrlPreview> {
+        self.entries.iter().find(|(entry_url, _)| entry_url == url)?.1.as_ref()
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.entries.iter().any(|(entry_url, _)| entry_url == url)
+    }
+
+    /// Mark `url` as used, so that it's evicted later.
+    pub fn touch(&mut self, url: &str) {
+        if let Some(entry) = self
+            .entries
+            .iter()
+            .position(|(entry_url, _)| entry_url == url)
+            .and_then(|index| self.entries.remove(index))
+        {
+            self.entries.push_back(entry);
+        }
+    }
+
+    pub fn insert(&mut self, url: String, preview: Option<UrlPreview>) {
+        self.entries.retain(|(entry_url, _)| *entry_url != url);
+
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back((url, preview));
+    }
+}